@@ -6,8 +6,29 @@
  * monitoring and control via debug probes. Variables are automatically discovered
  * by the MCU Link desktop application through flash memory scanning.
  * 
+ * Two descriptor formats are supported:
+ *  - v2 (recommended): a single table emitted by MCULINK_DEFINE_TABLE(), made of a
+ *    descriptor header, a packed entry table and a string pool. The desktop
+ *    application reads it with a handful of bulk reads. Run
+ *    `mcu-link --seal-mculink firmware.elf` after linking to fill in the
+ *    descriptor CRC so corrupted tables are rejected. The CRC covers variable
+ *    addresses, so it can only be computed once the image is linked: seal the ELF
+ *    before converting it to .bin/.hex, and flash only the sealed ELF or images
+ *    made from it. Unsealed tables are rejected unless explicitly allowed.
+ *  - v1 (legacy): one self-contained record per MCULINK_EXPOSE_* macro, found by
+ *    scanning for the magic number. Records carry no CRC and cannot be sealed;
+ *    they are still used, with a warning in the discovery report.
+ * 
+ * Scaling to engineering units (scale/offset, Qn fixed-point) and display units
+ * are carried by the MCULINK_EXPOSE_SCALED / _QN / _SENSOR records. Variables in
+ * a v2 table get them from a host-side decoding config instead.
+ * 
+ * For loops faster than debug memory reads can follow, the mculink_stream_*
+ * helpers pack samples into binary frames that the firmware pushes to an RTT
+ * up channel every iteration.
+ * 
  * @author MCU Link Project
- * @version 1.0.0
+ * @version 2.3.0
  */
 
 #ifndef MCU_LINK_H
@@ -23,8 +44,9 @@ extern "C" {
 /* MCU Link magic number "MCLK" */
 #define MCULINK_MAGIC           0x4D434C4B
 
-/* MCU Link version */
-#define MCULINK_VERSION         0x00010000
+/* MCU Link descriptor version (major << 16 | minor) */
+#define MCULINK_VERSION         0x00020002
+#define MCULINK_VERSION_LEGACY  0x00010003
 
 /* Variable types */
 #define MCULINK_TYPE_UINT8      0
@@ -34,23 +56,45 @@ extern "C" {
 #define MCULINK_TYPE_UINT32     4
 #define MCULINK_TYPE_INT32      5
 #define MCULINK_TYPE_FLOAT      6
+#define MCULINK_TYPE_UINT64     7
+#define MCULINK_TYPE_INT64      8
+#define MCULINK_TYPE_DOUBLE     9
+#define MCULINK_TYPE_BOOL       10   /* bool / _Bool, one byte */
+#define MCULINK_TYPE_STRING     11   /* char buffer; element count is the buffer length */
+
+/* Size in bytes of one element of each variable type */
+#define MCULINK_SIZE_UINT8      1
+#define MCULINK_SIZE_INT8       1
+#define MCULINK_SIZE_UINT16     2
+#define MCULINK_SIZE_INT16      2
+#define MCULINK_SIZE_UINT32     4
+#define MCULINK_SIZE_INT32      4
+#define MCULINK_SIZE_FLOAT      4
+#define MCULINK_SIZE_UINT64     8
+#define MCULINK_SIZE_INT64      8
+#define MCULINK_SIZE_DOUBLE     8
+#define MCULINK_SIZE_BOOL       1
+#define MCULINK_SIZE_STRING     1
 
 /* Access flags */
 #define MCULINK_ACCESS_RO       0   /* Read-only */
 #define MCULINK_ACCESS_RW       1   /* Read-write */
+#define MCULINK_ACCESS_ARRAY    0x80 /* Legacy record carries an element count (v1.2+) */
+#define MCULINK_ACCESS_SCALED   0x40 /* Legacy record carries scale, offset, Qn and unit (v1.3+) */
 
 /**
  * @brief MCU Link variable entry structure
  * 
  * This structure describes a single variable that can be monitored/controlled.
- * All entries are stored in flash memory (.rodata section) for discovery.
+ * All entries are stored in flash memory (.mculink section) for discovery.
+ * String offsets are relative to the start of the descriptor header.
  */
 typedef struct {
     uint32_t name_offset;       /**< Offset to variable name string (from descriptor start) */
     uint32_t address;           /**< RAM address of the actual variable */ 
     uint8_t  var_type;          /**< Variable type (MCULINK_TYPE_*) */
     uint8_t  access_flags;      /**< Access permissions (MCULINK_ACCESS_*) */
-    uint8_t  reserved[2];       /**< Reserved for alignment */
+    uint16_t element_count;     /**< Number of array elements; 0 or 1 for scalars (v2.2+) */
     uint32_t category_offset;   /**< Offset to category string (0 if none) */
     float    min_value;         /**< Minimum value (for UI sliders) */
     float    max_value;         /**< Maximum value (for UI sliders) */
@@ -60,76 +104,224 @@ typedef struct {
  * @brief MCU Link descriptor header
  * 
  * This structure appears at the start of the .mculink section in flash.
- * The MCU Link application scans flash memory looking for the magic number.
+ * It is followed by entry_count packed mculink_entry_t records (starting at
+ * entries_offset) and then by the string pool holding names and categories.
  */
 typedef struct {
     uint32_t magic;             /**< Magic number (MCULINK_MAGIC) */
     uint32_t version;           /**< Version number */
     uint32_t entry_count;       /**< Number of variable entries */
     uint32_t entries_offset;    /**< Offset to entries array */
+    uint32_t total_size;        /**< Size of header, entries and string pool in bytes */
+    uint32_t crc32;             /**< CRC32 of total_size bytes with this field as 0; 0 = unsealed (v2.1+) */
 } mculink_descriptor_t;
 
 /* Internal counters for macro system */
 extern uint32_t _mculink_entry_count;
 extern uint32_t _mculink_string_offset;
 
+/* Field sizes of legacy per-variable records (category length may be overridden) */
+#define MCULINK_NAME_LEN        32
+#define MCULINK_UNIT_LEN        16
+#ifndef MCULINK_CATEGORY_LEN
+#define MCULINK_CATEGORY_LEN    32
+#endif
+
 /* Section attribute for GCC */
 #define MCULINK_SECTION __attribute__((section(".mculink"), used))
 
+/* End of the .mculink section, defined by the linker script (weak: 0 if missing) */
+extern const uint8_t __mculink_end[] __attribute__((weak));
+
 /* Helper macros for string management */
 #define _MCULINK_STR_HELPER(x) #x
 #define _MCULINK_STR(x) _MCULINK_STR_HELPER(x)
 
+/* Internal helpers for MCULINK_DEFINE_TABLE(); each takes one variable list row */
+#define _MCULINK_TABLE_COUNT(var_name, type, access, category, min_val, max_val) + 1
+
+#define _MCULINK_TABLE_STRING_FIELDS(var_name, type, access, category, min_val, max_val) \
+    char _name_##var_name[sizeof(#var_name)]; \
+    char _cat_##var_name[sizeof(category)];
+
+#define _MCULINK_TABLE_STRING_INIT(var_name, type, access, category, min_val, max_val) \
+    ._name_##var_name = #var_name, \
+    ._cat_##var_name = category,
+
+#define _MCULINK_TABLE_ENTRY_INIT(var_name, type, access, category, min_val, max_val) \
+    { \
+        .name_offset = offsetof(_mculink_table_t, strings._name_##var_name), \
+        .address = (uint32_t)&var_name, \
+        .var_type = MCULINK_TYPE_##type, \
+        .access_flags = MCULINK_ACCESS_##access, \
+        .element_count = sizeof(var_name) / MCULINK_SIZE_##type, \
+        .category_offset = offsetof(_mculink_table_t, strings._cat_##var_name), \
+        .min_value = min_val, \
+        .max_value = max_val \
+    },
+
 /**
- * @brief Initialize MCU Link descriptor
+ * @brief Define the MCU Link v2 descriptor table
  * 
- * This macro must be called once before any MCULINK_EXPOSE_* macros.
- * It creates the descriptor header in flash memory.
+ * Emits the descriptor header, the packed entry table and the string pool as a
+ * single object in the .mculink section. All offsets are computed at compile time.
+ * The variable list is an X-macro: a macro taking a callback X that is invoked once
+ * per variable as X(var_name, type, access, category, min_val, max_val), where
+ * access is RO or RW. Arrays are listed like any other variable; their element
+ * count is taken from sizeof(var_name). char buffers are listed with type STRING
+ * and show up as text.
  * 
- * @note Place this macro in a .c file, not in a header.
+ * @param LIST          Name of the variable list macro
+ * 
+ * @note Use this macro once, in a single .c file, instead of MCULINK_INIT() and
+ *       the MCULINK_EXPOSE_* macros.
+ * 
+ * @example
+ * #define MY_VARIABLES(X) \
+ *     X(temperature, FLOAT, RO, "Sensors", -40.0f, 85.0f) \
+ *     X(motor_speed, UINT16, RW, "Controls", 0.0f, 3000.0f) \
+ *     X(adc_samples, FLOAT,  RO, "Sensors", 0.0f, 3.3f)
+ * MCULINK_DEFINE_TABLE(MY_VARIABLES);
  */
-#define MCULINK_INIT() \
-    MCULINK_SECTION const uint32_t _mculink_magic = MCULINK_MAGIC;
+#define MCULINK_DEFINE_TABLE(LIST) \
+    typedef struct { \
+        mculink_descriptor_t header; \
+        mculink_entry_t entries[0 LIST(_MCULINK_TABLE_COUNT)]; \
+        struct { LIST(_MCULINK_TABLE_STRING_FIELDS) } strings; \
+    } _mculink_table_t; \
+    MCULINK_SECTION const _mculink_table_t _mculink_table = { \
+        .header = { \
+            .magic = MCULINK_MAGIC, \
+            .version = MCULINK_VERSION, \
+            .entry_count = 0 LIST(_MCULINK_TABLE_COUNT), \
+            .entries_offset = offsetof(_mculink_table_t, entries), \
+            .total_size = sizeof(_mculink_table_t), \
+            .crc32 = 0 /* filled in by mcu-link --seal-mculink */ \
+        }, \
+        .entries = { LIST(_MCULINK_TABLE_ENTRY_INIT) }, \
+        .strings = { LIST(_MCULINK_TABLE_STRING_INIT) } \
+    }
 
 /**
- * @brief Expose a read-only variable
- * 
- * Makes a variable visible to MCU Link for real-time monitoring.
- * The variable will appear as a sensor/display in the UI.
+ * @brief Initialize MCU Link descriptor (legacy v1 format)
  * 
- * @param var_name      Name of the C variable to expose
- * @param type          Variable type (UINT8, INT8, UINT16, INT16, UINT32, INT32, FLOAT)
- * @param category      UI category string (e.g., "Sensors", "Status")
- * @param min_val       Minimum value for UI scaling
- * @param max_val       Maximum value for UI scaling
+ * This macro must be called once before any MCULINK_EXPOSE_* macros.
+ * It creates the descriptor header in flash memory: magic, version and the
+ * section end address, so MCU Link can read the whole section in one transfer.
+ * Define __mculink_end at the end of the .mculink output section in the linker
+ * script; without it the desktop application falls back to a chunked scan.
  * 
- * @example
- * float temperature = 25.0f;
- * MCULINK_EXPOSE_RO(temperature, FLOAT, "Sensors", -40.0f, 85.0f);
+ * @note Place this macro in a .c file, not in a header.
  */
-#define MCULINK_EXPOSE_RO(var_name, type, category, min_val, max_val) \
+#define MCULINK_INIT() \
+    MCULINK_SECTION const uint32_t _mculink_header[3] = { \
+        MCULINK_MAGIC, \
+        MCULINK_VERSION_LEGACY, \
+        (uint32_t)__mculink_end \
+    };
+
+/*
+ * Internal helpers for the MCULINK_EXPOSE_* macros: one self-contained legacy record.
+ * record_size holds sizeof the record so the desktop application reads exactly the
+ * bytes laid out here (v1.0 records left it 0 and were always 84 bytes).
+ */
+#define _MCULINK_CONCAT_HELPER(a, b) a##b
+#define _MCULINK_CONCAT(a, b) _MCULINK_CONCAT_HELPER(a, b)
+
+/*
+ * Unique record symbol for macros whose arguments can't be pasted into one (field
+ * paths contain dots). __COUNTER__ (GCC, Clang, IAR, Arm Compiler, MSVC) is unique
+ * per translation unit; otherwise the struct variable and line number are used, so
+ * struct_var must then be a plain identifier with one field per line.
+ */
+#ifdef __COUNTER__
+#define _MCULINK_UNIQUE(prefix, struct_var) _MCULINK_CONCAT(prefix, __COUNTER__)
+#else
+#define _MCULINK_UNIQUE(prefix, struct_var) _MCULINK_CONCAT(_MCULINK_CONCAT(prefix, struct_var), _MCULINK_CONCAT(_, __LINE__))
+#endif
+
+#define _MCULINK_RECORD(var_name, type, access, category, min_val, max_val) \
+    _MCULINK_RECORD_NAMED(_mculink_var_##var_name, var_name, #var_name, type, access, category, min_val, max_val)
+
+#define _MCULINK_RECORD_NAMED(symbol, lvalue, name_str, type, access, category, min_val, max_val) \
     MCULINK_SECTION static const struct { \
         uint32_t magic; \
         uint32_t address; \
         uint8_t var_type; \
         uint8_t access_flags; \
-        uint8_t reserved[2]; \
+        uint16_t record_size; \
         float min_value; \
         float max_value; \
-        char name[32]; \
-        char cat[32]; \
+        char name[MCULINK_NAME_LEN]; \
+        char cat[MCULINK_CATEGORY_LEN]; \
+    } symbol = { \
+        .magic = MCULINK_MAGIC, \
+        .address = (uint32_t)&(lvalue), \
+        .var_type = MCULINK_TYPE_##type, \
+        .access_flags = MCULINK_ACCESS_##access, \
+        .record_size = sizeof(symbol), \
+        .min_value = min_val, \
+        .max_value = max_val, \
+        .name = name_str, \
+        .cat = category \
+    };
+
+/*
+ * Scaled legacy record: the host converts raw values to engineering units as
+ * raw / 2^q_format * scale + offset, and back (rounded, saturated) on writes.
+ */
+#define _MCULINK_SCALED_RECORD(var_name, type, access, category, scale_val, offset_val, q, unit_str, min_val, max_val) \
+    MCULINK_SECTION static const struct { \
+        uint32_t magic; \
+        uint32_t address; \
+        uint8_t var_type; \
+        uint8_t access_flags; \
+        uint16_t record_size; \
+        float min_value; \
+        float max_value; \
+        float scale; \
+        float offset; \
+        uint8_t q_format; \
+        uint8_t reserved[3]; \
+        char unit[MCULINK_UNIT_LEN]; \
+        char name[MCULINK_NAME_LEN]; \
+        char cat[MCULINK_CATEGORY_LEN]; \
     } _mculink_var_##var_name = { \
         .magic = MCULINK_MAGIC, \
         .address = (uint32_t)&var_name, \
         .var_type = MCULINK_TYPE_##type, \
-        .access_flags = MCULINK_ACCESS_RO, \
-        .reserved = {0, 0}, \
+        .access_flags = MCULINK_ACCESS_##access | MCULINK_ACCESS_SCALED, \
+        .record_size = sizeof(_mculink_var_##var_name), \
         .min_value = min_val, \
         .max_value = max_val, \
+        .scale = scale_val, \
+        .offset = offset_val, \
+        .q_format = q, \
+        .unit = unit_str, \
         .name = #var_name, \
         .cat = category \
     };
 
+/**
+ * @brief Expose a read-only variable
+ * 
+ * Makes a variable visible to MCU Link for real-time monitoring.
+ * The variable will appear as a sensor/display in the UI.
+ * 
+ * @param var_name      Name of the C variable to expose
+ * @param type          Variable type (UINT8, INT8, UINT16, INT16, UINT32, INT32, FLOAT,
+ *                      UINT64, INT64, DOUBLE, BOOL)
+ * @param category      UI category string (e.g., "Sensors", "Status")
+ * @param min_val       Minimum value for UI scaling
+ * @param max_val       Maximum value for UI scaling
+ * 
+ * @example
+ * float temperature = 25.0f;
+ * MCULINK_EXPOSE_RO(temperature, FLOAT, "Sensors", -40.0f, 85.0f);
+ */
+#define MCULINK_EXPOSE_RO(var_name, type, category, min_val, max_val) \
+    _MCULINK_RECORD(var_name, type, RO, category, min_val, max_val)
+
 /**
  * @brief Expose a read-write variable
  * 
@@ -137,7 +329,8 @@ extern uint32_t _mculink_string_offset;
  * The variable will appear as a control (button, slider) in the UI.
  * 
  * @param var_name      Name of the C variable to expose
- * @param type          Variable type (UINT8, INT8, UINT16, INT16, UINT32, INT32, FLOAT)
+ * @param type          Variable type (UINT8, INT8, UINT16, INT16, UINT32, INT32, FLOAT,
+ *                      UINT64, INT64, DOUBLE, BOOL)
  * @param category      UI category string (e.g., "Controls", "Settings")
  * @param min_val       Minimum value for UI scaling
  * @param max_val       Maximum value for UI scaling
@@ -147,27 +340,7 @@ extern uint32_t _mculink_string_offset;
  * MCULINK_EXPOSE_RW(led_brightness, UINT8, "Controls", 0.0f, 255.0f);
  */
 #define MCULINK_EXPOSE_RW(var_name, type, category, min_val, max_val) \
-    MCULINK_SECTION static const struct { \
-        uint32_t magic; \
-        uint32_t address; \
-        uint8_t var_type; \
-        uint8_t access_flags; \
-        uint8_t reserved[2]; \
-        float min_value; \
-        float max_value; \
-        char name[32]; \
-        char cat[32]; \
-    } _mculink_var_##var_name = { \
-        .magic = MCULINK_MAGIC, \
-        .address = (uint32_t)&var_name, \
-        .var_type = MCULINK_TYPE_##type, \
-        .access_flags = MCULINK_ACCESS_RW, \
-        .reserved = {0, 0}, \
-        .min_value = min_val, \
-        .max_value = max_val, \
-        .name = #var_name, \
-        .cat = category \
-    };
+    _MCULINK_RECORD(var_name, type, RW, category, min_val, max_val)
 
 /**
  * @brief Expose a boolean toggle variable
@@ -192,7 +365,8 @@ extern uint32_t _mculink_string_offset;
  * 
  * @param var_name      Name of the C variable to expose
  * @param type          Variable type (typically FLOAT)
- * @param unit          Unit string for display (e.g., "°C", "V", "A") - currently unused
+ * @param unit          Unit string for display (e.g., "°C", "V", "A"), up to
+ *                      MCULINK_UNIT_LEN - 1 bytes
  * @param min_val       Expected minimum value
  * @param max_val       Expected maximum value
  * 
@@ -201,10 +375,283 @@ extern uint32_t _mculink_string_offset;
  * MCULINK_EXPOSE_SENSOR(cpu_temperature, FLOAT, "°C", -40.0f, 85.0f);
  */
 #define MCULINK_EXPOSE_SENSOR(var_name, type, unit, min_val, max_val) \
-    MCULINK_EXPOSE_RO(var_name, type, "Sensors", min_val, max_val)
+    _MCULINK_SCALED_RECORD(var_name, type, RO, "Sensors", 1.0f, 0.0f, 0, unit, min_val, max_val)
+
+/**
+ * @brief Expose a variable with linear scaling to engineering units
+ * 
+ * MCU Link shows and writes the variable as raw * scale + offset, e.g. ADC
+ * counts as volts. Writes are converted back to raw counts, rounded to the
+ * nearest integer and saturated to the range of the type.
+ * 
+ * @param var_name      Name of the C variable to expose
+ * @param type          Raw variable type (UINT8 ... INT64, FLOAT, DOUBLE)
+ * @param access        RO or RW
+ * @param category      UI category string (e.g., "Sensors")
+ * @param scale         Engineering units per raw count
+ * @param offset        Engineering value of a raw 0
+ * @param unit          Unit string for display (e.g., "V")
+ * @param min_val       Minimum value in engineering units
+ * @param max_val       Maximum value in engineering units
+ * 
+ * @example
+ * uint16_t vbus_adc;
+ * MCULINK_EXPOSE_SCALED(vbus_adc, UINT16, RO, "Power", 3.3f / 4096.0f, 0.0f, "V", 0.0f, 3.3f);
+ */
+#define MCULINK_EXPOSE_SCALED(var_name, type, access, category, scale, offset, unit, min_val, max_val) \
+    _MCULINK_SCALED_RECORD(var_name, type, access, category, scale, offset, 0, unit, min_val, max_val)
+
+/**
+ * @brief Expose a Qn fixed-point variable
+ * 
+ * MCU Link shows and writes the variable as raw / 2^q, e.g. q = 15 for Q15
+ * (INT16) and q = 31 for Q31 (INT32).
+ * 
+ * @param var_name      Name of the C variable to expose
+ * @param type          Raw integer type (INT16, INT32, ...)
+ * @param access        RO or RW
+ * @param category      UI category string (e.g., "Control")
+ * @param q             Number of fractional bits
+ * @param unit          Unit string for display ("" for none)
+ * @param min_val       Minimum value in engineering units
+ * @param max_val       Maximum value in engineering units
+ * 
+ * @example
+ * int16_t id_ref_q15;
+ * MCULINK_EXPOSE_QN(id_ref_q15, INT16, RW, "Control", 15, "", -1.0f, 1.0f);
+ */
+#define MCULINK_EXPOSE_QN(var_name, type, access, category, q, unit, min_val, max_val) \
+    _MCULINK_SCALED_RECORD(var_name, type, access, category, 1.0f, 0.0f, q, unit, min_val, max_val)
+
+/**
+ * @brief Expose one field of a struct variable
+ * 
+ * The field appears in MCU Link under its dotted path (e.g. "motor.current.q")
+ * and is grouped under its parent struct in the UI. Use one macro per field;
+ * the full path must fit in MCULINK_NAME_LEN - 1 characters.
+ * 
+ * @param struct_var    Name of the C struct variable
+ * @param field         Field path inside the struct (e.g. current.q)
+ * @param type          Field type (UINT8, INT8, UINT16, INT16, UINT32, INT32, FLOAT,
+ *                      UINT64, INT64, DOUBLE, BOOL)
+ * @param access        RO or RW
+ * @param category      UI category string (e.g., "Motor")
+ * @param min_val       Minimum value for UI scaling
+ * @param max_val       Maximum value for UI scaling
+ * 
+ * @note Firmware built with debug info doesn't need these: loading the ELF in
+ *       MCU Link expands every struct field from the DWARF member offsets.
+ * 
+ * @example
+ * motor_state_t motor;
+ * MCULINK_EXPOSE_FIELD(motor, current.q, FLOAT, RO, "Motor", -20.0f, 20.0f);
+ * MCULINK_EXPOSE_FIELD(motor, speed_ref, FLOAT, RW, "Motor", 0.0f, 3000.0f);
+ */
+#define MCULINK_EXPOSE_FIELD(struct_var, field, type, access, category, min_val, max_val) \
+    _MCULINK_RECORD_NAMED(_MCULINK_UNIQUE(_mculink_field_, struct_var), struct_var.field, \
+        #struct_var "." #field, type, access, category, min_val, max_val)
+
+/**
+ * @brief Expose an array variable
+ * 
+ * Makes a whole C array visible to MCU Link. The desktop application reads it
+ * in one bulk transfer and can write single elements or slices (RW only).
+ * The element count is taken from sizeof(var_name), so pass the array itself,
+ * not a pointer to it.
+ * 
+ * @param var_name      Name of the C array to expose
+ * @param type          Element type (UINT8, INT8, UINT16, INT16, UINT32, INT32, FLOAT,
+ *                      UINT64, INT64, DOUBLE, BOOL)
+ * @param access        RO or RW
+ * @param category      UI category string (e.g., "Buffers", "Tables")
+ * @param min_val       Minimum element value for UI scaling
+ * @param max_val       Maximum element value for UI scaling
+ * 
+ * @example
+ * float adc_samples[64];
+ * MCULINK_EXPOSE_ARRAY(adc_samples, FLOAT, RO, "Buffers", 0.0f, 3.3f);
+ */
+#define MCULINK_EXPOSE_ARRAY(var_name, type, access, category, min_val, max_val) \
+    MCULINK_SECTION static const struct { \
+        uint32_t magic; \
+        uint32_t address; \
+        uint8_t var_type; \
+        uint8_t access_flags; \
+        uint16_t record_size; \
+        float min_value; \
+        float max_value; \
+        uint32_t element_count; \
+        char name[MCULINK_NAME_LEN]; \
+        char cat[MCULINK_CATEGORY_LEN]; \
+    } _mculink_var_##var_name = { \
+        .magic = MCULINK_MAGIC, \
+        .address = (uint32_t)&var_name, \
+        .var_type = MCULINK_TYPE_##type, \
+        .access_flags = MCULINK_ACCESS_##access | MCULINK_ACCESS_ARRAY, \
+        .record_size = sizeof(_mculink_var_##var_name), \
+        .min_value = min_val, \
+        .max_value = max_val, \
+        .element_count = sizeof(var_name) / MCULINK_SIZE_##type, \
+        .name = #var_name, \
+        .cat = category \
+    };
+
+/**
+ * @brief Expose a char buffer as a text variable
+ * 
+ * MCU Link shows the buffer contents up to the first NUL. RW buffers can be
+ * written from the UI: the text must leave room for its terminator and the
+ * rest of the buffer is zeroed. Pass the array itself, not a pointer to it.
+ * 
+ * @param var_name      Name of the C char array to expose
+ * @param access        RO or RW
+ * @param category      UI category string (e.g., "Info", "Status")
+ * 
+ * @example
+ * char fw_version[16] = "1.4.2";
+ * char last_error[64];
+ * MCULINK_EXPOSE_STRING(fw_version, RO, "Info");
+ * MCULINK_EXPOSE_STRING(last_error, RW, "Status");
+ */
+#define MCULINK_EXPOSE_STRING(var_name, access, category) \
+    MCULINK_EXPOSE_ARRAY(var_name, STRING, access, category, 0.0f, 0.0f)
+
+/*
+ * Binary sample stream over RTT
+ * 
+ * Frame layout (little endian):
+ *   0xA5 0x5A | seq u16 | timestamp u32 | length u8 | payload[length] | crc8
+ * 
+ * seq counts frames, including frames the RTT buffer had no room for, so the
+ * host can count drops. The timestamp is in core cycles (normally DWT_CYCCNT).
+ * The payload holds the raw sample values back to back, in the order the host
+ * lists the streamed variables. crc8 (polynomial 0x07, initial value 0) covers
+ * everything from seq to the end of the payload.
+ */
+#define MCULINK_STREAM_SYNC0        0xA5
+#define MCULINK_STREAM_SYNC1        0x5A
+#define MCULINK_STREAM_HEADER_SIZE  9    /* Sync, seq, timestamp and length */
+#define MCULINK_STREAM_OVERHEAD     10   /* Header plus CRC */
+
+#ifndef MCULINK_STREAM_MAX_PAYLOAD
+#define MCULINK_STREAM_MAX_PAYLOAD  64   /* Bytes of samples per frame, at most 255 */
+#endif
+
+/* Cortex-M DWT cycle counter; enable it with DEMCR.TRCENA and DWT_CTRL.CYCCNTENA */
+#define MCULINK_CYCCNT              (*(volatile uint32_t *)0xE0001004u)
+
+typedef struct {
+    uint16_t seq;
+    uint8_t length;
+    uint8_t overflow;   /* Set when a sample did not fit; the frame is not sent */
+    uint8_t frame[MCULINK_STREAM_OVERHEAD + MCULINK_STREAM_MAX_PAYLOAD];
+} mculink_stream_t;
+
+static inline uint8_t _mculink_crc8(const uint8_t *data, size_t size) {
+    uint8_t crc = 0;
+    while (size--) {
+        crc ^= *data++;
+        for (int bit = 0; bit < 8; bit++) {
+            crc = (crc & 0x80) ? (uint8_t)((crc << 1) ^ 0x07) : (uint8_t)(crc << 1);
+        }
+    }
+    return crc;
+}
+
+/**
+ * @brief Start a new frame stamped with `timestamp` (core cycles)
+ */
+static inline void mculink_stream_begin(mculink_stream_t *stream, uint32_t timestamp) {
+    stream->frame[0] = MCULINK_STREAM_SYNC0;
+    stream->frame[1] = MCULINK_STREAM_SYNC1;
+    stream->frame[2] = (uint8_t)stream->seq;
+    stream->frame[3] = (uint8_t)(stream->seq >> 8);
+    stream->frame[4] = (uint8_t)timestamp;
+    stream->frame[5] = (uint8_t)(timestamp >> 8);
+    stream->frame[6] = (uint8_t)(timestamp >> 16);
+    stream->frame[7] = (uint8_t)(timestamp >> 24);
+    stream->length = 0;
+    stream->overflow = 0;
+}
+
+/**
+ * @brief Append the raw bytes of one sample value to the frame
+ */
+static inline void mculink_stream_add(mculink_stream_t *stream, const void *value, size_t size) {
+    if (stream->length + size > MCULINK_STREAM_MAX_PAYLOAD) {
+        stream->overflow = 1;
+        return;
+    }
+    const uint8_t *bytes = (const uint8_t *)value;
+    for (size_t i = 0; i < size; i++) {
+        stream->frame[MCULINK_STREAM_HEADER_SIZE + stream->length++] = bytes[i];
+    }
+}
+
+/**
+ * @brief Close the frame and return its size in bytes (0 if a sample overflowed it)
+ * 
+ * The sequence number advances either way, so a frame that is not sent counts
+ * as dropped on the host.
+ */
+static inline size_t mculink_stream_finish(mculink_stream_t *stream) {
+    stream->seq++;
+    if (stream->overflow) {
+        return 0;
+    }
+    stream->frame[8] = stream->length;
+    stream->frame[MCULINK_STREAM_HEADER_SIZE + stream->length] =
+        _mculink_crc8(&stream->frame[2], MCULINK_STREAM_HEADER_SIZE - 2 + stream->length);
+    return MCULINK_STREAM_OVERHEAD + stream->length;
+}
+
+/* Append one variable to the frame */
+#define MCULINK_STREAM_ADD(stream, var_name) \
+    mculink_stream_add((stream), &(var_name), sizeof(var_name))
+
+/**
+ * @brief Send the frame to an RTT up channel (include SEGGER_RTT.h first)
+ * 
+ * Configure the channel with SEGGER_RTT_MODE_NO_BLOCK_SKIP so a frame that does
+ * not fit is skipped whole instead of split or blocking the control loop.
+ * 
+ * @example
+ * static char stream_buffer[4096];
+ * static mculink_stream_t stream;
+ * 
+ * SEGGER_RTT_ConfigUpBuffer(1, "mculink", stream_buffer, sizeof(stream_buffer),
+ *                           SEGGER_RTT_MODE_NO_BLOCK_SKIP);
+ * 
+ * void control_loop_isr(void) {
+ *     mculink_stream_begin(&stream, MCULINK_CYCCNT);
+ *     MCULINK_STREAM_ADD(&stream, motor_current);
+ *     MCULINK_STREAM_ADD(&stream, motor_speed);
+ *     MCULINK_STREAM_SEND(&stream, 1);
+ * }
+ */
+#define MCULINK_STREAM_SEND(stream, channel) \
+    SEGGER_RTT_Write((channel), (stream)->frame, mculink_stream_finish(stream))
+
+/* 
+ * Example usage in firmware (v2 table format):
+ * 
+ * // In main.c:
+ * #include "mcu_link.h"
+ * 
+ * float temperature = 22.5f;
+ * uint8_t led_state = 0;
+ * uint16_t motor_speed = 1000;
+ * 
+ * #define MCULINK_VARIABLES(X) \
+ *     X(temperature, FLOAT,  RO, "Temperature", -40.0f, 85.0f) \
+ *     X(led_state,   UINT8,  RW, "Controls",    0.0f,   1.0f) \
+ *     X(motor_speed, UINT16, RW, "Controls",    0.0f,   3000.0f)
+ * 
+ * MCULINK_DEFINE_TABLE(MCULINK_VARIABLES);
+ */
 
 /* 
- * Example usage in firmware:
+ * Example usage in firmware (legacy v1 format):
  * 
  * // In main.c:
  * #include "mcu_link.h"
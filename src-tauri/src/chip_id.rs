@@ -0,0 +1,179 @@
+use probe_rs::architecture::arm::DpAddress;
+use probe_rs::{Architecture, MemoryInterface, Session};
+use serde::{Deserialize, Serialize};
+
+// Cortex-M System Control Block CPUID register (same address on every Cortex-M)
+const CORTEX_M_CPUID_ADDRESS: u32 = 0xE000ED00;
+
+// Vendor identification registers
+const STM32_DBGMCU_IDCODE: u32 = 0xE0042000; // F1/F2/F3/F4/F7/L1/L4/G4/WB/WL
+const STM32_DBGMCU_IDCODE_APB: u32 = 0x40015800; // F0/G0/L0/C0
+const STM32_DBGMCU_IDCODE_H7: u32 = 0x5C001000;
+const STM32_DBGMCU_IDCODE_V8M: u32 = 0xE0044000; // L5/U5
+const STM32_DBGMCU_IDCODE_H5: u32 = 0x44024000;
+const NRF52_FICR_INFO_PART: u32 = 0x10000100;
+const NRF52_FICR_INFO_VARIANT: u32 = 0x10000104;
+const RP2040_SYSINFO_CHIP_ID: u32 = 0x40000000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ChipIdentity {
+    pub idcode: Option<u32>,           // Raw vendor ID register (DBGMCU_IDCODE, CHIP_ID, FICR)
+    pub part_number: Option<u32>,      // Device/part number decoded from the ID register
+    pub revision: Option<String>,      // Silicon revision, e.g. "0x1003" or "B0"
+    pub cpuid: Option<u32>,            // Raw Cortex-M CPUID register
+    pub core: Option<String>,          // Decoded core, e.g. "Cortex-M7 r1p2"
+    pub manufacturer: Option<String>,  // JEP106 manufacturer from the ROM table
+    pub rom_table_part: Option<u16>,   // Part number from the ROM table
+}
+
+/// Read every identification register we know about for the attached chip.
+///
+/// Each source is optional: a failed read just leaves the corresponding field empty,
+/// since not every probe/target combination exposes all of them.
+pub fn identify_chip(session: &mut Session) -> ChipIdentity {
+    let mut identity = ChipIdentity::default();
+
+    if session.architecture() != Architecture::Arm {
+        eprintln!("Chip identification is only supported on ARM targets");
+        return identity;
+    }
+
+    // ROM table walk through the debug port
+    match session.get_arm_interface() {
+        Ok(interface) => match interface.read_chip_info_from_rom_table(DpAddress::Default) {
+            Ok(Some(info)) => {
+                identity.manufacturer = info.manufacturer.get().map(|m| m.to_string());
+                identity.rom_table_part = Some(info.part);
+            },
+            Ok(None) => eprintln!("ROM table did not contain chip information"),
+            Err(e) => eprintln!("Failed to read ROM table: {}", e),
+        },
+        Err(e) => eprintln!("Failed to get ARM interface: {}", e),
+    }
+
+    let target_name = session.target().name.to_uppercase();
+    let mut core = match session.core(0) {
+        Ok(core) => core,
+        Err(e) => {
+            eprintln!("Failed to get core for chip identification: {}", e);
+            return identity;
+        }
+    };
+
+    if let Ok(cpuid) = core.read_word_32(CORTEX_M_CPUID_ADDRESS as u64) {
+        identity.cpuid = Some(cpuid);
+        identity.core = Some(decode_cpuid(cpuid));
+    }
+
+    if target_name.starts_with("STM32") {
+        let idcode_addr = stm32_idcode_address(&target_name);
+        if let Ok(idcode) = core.read_word_32(idcode_addr as u64) {
+            identity.idcode = Some(idcode);
+            identity.part_number = Some(idcode & 0xFFF); // DEV_ID
+            identity.revision = Some(format!("0x{:04X}", idcode >> 16)); // REV_ID
+        }
+    } else if target_name.starts_with("NRF52") {
+        if let Ok(part) = core.read_word_32(NRF52_FICR_INFO_PART as u64) {
+            identity.idcode = Some(part);
+            identity.part_number = Some(part);
+        }
+        if let Ok(variant) = core.read_word_32(NRF52_FICR_INFO_VARIANT as u64) {
+            identity.revision = Some(nrf52_variant(variant));
+        }
+    } else if target_name.starts_with("RP2040") {
+        if let Ok(chip_id) = core.read_word_32(RP2040_SYSINFO_CHIP_ID as u64) {
+            identity.idcode = Some(chip_id);
+            identity.part_number = Some((chip_id >> 12) & 0xFFFF);
+            identity.revision = rp2040_revision(chip_id);
+        }
+    }
+
+    identity
+}
+
+// FICR INFO.VARIANT is four ASCII characters, e.g. "AAE0"
+fn nrf52_variant(variant: u32) -> String {
+    variant.to_be_bytes().iter()
+        .filter(|b| b.is_ascii_graphic())
+        .map(|&b| b as char)
+        .collect()
+}
+
+// CHIP_ID.REVISION counts from 1 for B0; None for a revision that doesn't exist
+fn rp2040_revision(chip_id: u32) -> Option<String> {
+    match chip_id >> 28 {
+        0 => None,
+        revision => Some(format!("B{}", revision - 1)),
+    }
+}
+
+fn stm32_idcode_address(target_name: &str) -> u32 {
+    if target_name.starts_with("STM32H7") {
+        STM32_DBGMCU_IDCODE_H7
+    } else if target_name.starts_with("STM32H5") {
+        STM32_DBGMCU_IDCODE_H5
+    } else if target_name.starts_with("STM32L5") || target_name.starts_with("STM32U5") {
+        STM32_DBGMCU_IDCODE_V8M
+    } else if ["STM32F0", "STM32G0", "STM32L0", "STM32C0"].iter().any(|p| target_name.starts_with(p)) {
+        STM32_DBGMCU_IDCODE_APB
+    } else {
+        STM32_DBGMCU_IDCODE
+    }
+}
+
+fn decode_cpuid(cpuid: u32) -> String {
+    let implementer = cpuid >> 24;
+    let variant = (cpuid >> 20) & 0xF;
+    let part = (cpuid >> 4) & 0xFFF;
+    let revision = cpuid & 0xF;
+
+    let core_name = match (implementer, part) {
+        (0x41, 0xC20) => "Cortex-M0".to_string(),
+        (0x41, 0xC60) => "Cortex-M0+".to_string(),
+        (0x41, 0xC21) => "Cortex-M1".to_string(),
+        (0x41, 0xC23) => "Cortex-M3".to_string(),
+        (0x41, 0xC24) => "Cortex-M4".to_string(),
+        (0x41, 0xC27) => "Cortex-M7".to_string(),
+        (0x41, 0xD20) => "Cortex-M23".to_string(),
+        (0x41, 0xD21) => "Cortex-M33".to_string(),
+        (0x41, 0xD22) => "Cortex-M55".to_string(),
+        (0x41, 0xD23) => "Cortex-M85".to_string(),
+        _ => format!("Unknown core (implementer 0x{:02X}, part 0x{:03X})", implementer, part),
+    };
+
+    format!("{} r{}p{}", core_name, variant, revision)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_cortex_m_cpuid() {
+        assert_eq!(decode_cpuid(0x411F_C272), "Cortex-M7 r1p2");
+        assert_eq!(decode_cpuid(0x410C_C601), "Cortex-M0+ r0p1");
+        assert_eq!(decode_cpuid(0x410F_D213), "Cortex-M33 r0p3");
+        assert_eq!(decode_cpuid(0x5100_1234), "Unknown core (implementer 0x51, part 0x123) r0p4");
+    }
+
+    #[test]
+    fn picks_the_stm32_idcode_register_by_family() {
+        assert_eq!(stm32_idcode_address("STM32H735ZGTX"), STM32_DBGMCU_IDCODE_H7);
+        assert_eq!(stm32_idcode_address("STM32H563ZITX"), STM32_DBGMCU_IDCODE_H5);
+        assert_eq!(stm32_idcode_address("STM32U575ZITXQ"), STM32_DBGMCU_IDCODE_V8M);
+        assert_eq!(stm32_idcode_address("STM32G071RBTX"), STM32_DBGMCU_IDCODE_APB);
+        assert_eq!(stm32_idcode_address("STM32F407VGTX"), STM32_DBGMCU_IDCODE);
+    }
+
+    #[test]
+    fn formats_vendor_revisions() {
+        // RP2040 B0, B1 and B2 report REVISION 1, 2 and 3
+        assert_eq!(rp2040_revision(0x1000_2927).as_deref(), Some("B0"));
+        assert_eq!(rp2040_revision(0x2000_2927).as_deref(), Some("B1"));
+        assert_eq!(rp2040_revision(0x3000_2927).as_deref(), Some("B2"));
+        assert_eq!(rp2040_revision(0x0000_2927), None);
+        assert_eq!(nrf52_variant(u32::from_be_bytes(*b"AAE0")), "AAE0");
+        // Unprogrammed FICR reads as all ones
+        assert_eq!(nrf52_variant(0xFFFF_FFFF), "");
+    }
+}
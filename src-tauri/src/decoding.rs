@@ -0,0 +1,277 @@
+// Enum label and bit-field decoding for integer variables.
+// Labels and fields come from DWARF (enum types, bit-field members) or from a
+// JSON sidecar file loaded with apply_decoding_config(), which can also override
+// a variable's scaling and unit.
+
+use serde::{Deserialize, Serialize};
+
+use crate::layout::{decode_value, var_type_size, RawValue};
+use crate::scaling::validate_scaling;
+use crate::VariableInfo;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct EnumLabel {
+    pub value: i64,
+    pub label: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BitField {
+    pub name: String,
+    pub offset: u32, // Position of the least significant bit
+    pub width: u32,  // Number of bits
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BitValue {
+    pub name: String,
+    pub value: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DecodedValue {
+    pub raw: f64,
+    pub text: String,          // Exact raw value, see read_variable_exact
+    pub label: Option<String>, // Enum label matching the value, if any
+    pub bits: Vec<BitValue>,   // One entry per bit field
+}
+
+// Sidecar file format: decoding tables and scaling keyed by variable name
+#[derive(Debug, Deserialize)]
+struct DecodingConfig {
+    variables: Vec<VariableDecoding>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VariableDecoding {
+    name: String,
+    #[serde(default)]
+    enum_labels: Vec<EnumLabel>,
+    #[serde(default)]
+    bit_fields: Vec<BitField>,
+    scale: Option<f64>,
+    offset: Option<f64>,
+    q_format: Option<u8>,
+    unit: Option<String>,
+}
+
+fn is_integer_type(var_type: &str) -> bool {
+    !matches!(var_type, "FLOAT" | "DOUBLE" | "STRING")
+}
+
+fn field_mask(width: u32) -> u64 {
+    if width >= 64 { u64::MAX } else { (1u64 << width) - 1 }
+}
+
+// Little-endian integer view of a variable's bytes, for bit manipulation
+fn bytes_to_bits(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(buf)
+}
+
+/// Check that the bit fields of a variable fit inside its type.
+pub fn validate_bit_fields(var_type: &str, bit_fields: &[BitField]) -> Result<(), String> {
+    if bit_fields.is_empty() {
+        return Ok(());
+    }
+    if !is_integer_type(var_type) {
+        return Err(format!("Bit fields need an integer type, not {}", var_type));
+    }
+    let bits = var_type_size(var_type)? as u32 * 8;
+    for field in bit_fields {
+        if field.width == 0 || field.offset.checked_add(field.width).is_none_or(|end| end > bits) {
+            return Err(format!("Bit field '{}' ({} bits at bit {}) does not fit in a {}", field.name, field.width, field.offset, var_type));
+        }
+    }
+    Ok(())
+}
+
+/// Decode a variable's bytes into its raw value, enum label and bit fields.
+pub fn decode(variable: &VariableInfo, bytes: &[u8]) -> Result<DecodedValue, String> {
+    // Bit fields may come straight from a client, without a decoding config
+    validate_bit_fields(&variable.var_type, &variable.bit_fields)?;
+    let raw = decode_value(&variable.var_type, bytes)?;
+    let integer = match raw {
+        RawValue::Unsigned(v) => i64::try_from(v).ok(),
+        RawValue::Signed(v) => Some(v),
+        RawValue::Float(_) => None,
+    };
+    let label = integer.and_then(|value| {
+        variable.enum_labels.iter()
+            .find(|label| label.value == value)
+            .map(|label| label.label.clone())
+    });
+
+    let word = bytes_to_bits(&bytes[..var_type_size(&variable.var_type)?]);
+    let bits = variable.bit_fields.iter()
+        .map(|field| BitValue {
+            name: field.name.clone(),
+            value: (word >> field.offset) & field_mask(field.width),
+        })
+        .collect();
+
+    Ok(DecodedValue {
+        raw: raw.as_f64(),
+        text: raw.to_string(),
+        label,
+        bits,
+    })
+}
+
+/// Value to write for an enum label.
+pub fn label_value(variable: &VariableInfo, label: &str) -> Result<i64, String> {
+    variable.enum_labels.iter()
+        .find(|entry| entry.label == label)
+        .map(|entry| entry.value)
+        .ok_or_else(|| format!("'{}' is not a label of {}", label, variable.name))
+}
+
+/// New bytes for a variable after setting one bit field, keeping every other bit.
+pub fn set_bit_field(variable: &VariableInfo, current: &[u8], field_name: &str, value: u64) -> Result<Vec<u8>, String> {
+    let field = variable.bit_fields.iter()
+        .find(|field| field.name == field_name)
+        .ok_or_else(|| format!("'{}' is not a bit field of {}", field_name, variable.name))?;
+    validate_bit_fields(&variable.var_type, std::slice::from_ref(field))?;
+    let mask = field_mask(field.width);
+    if value > mask {
+        return Err(format!("{} does not fit in the {} bit field '{}'", value, field.width, field.name));
+    }
+
+    let size = var_type_size(&variable.var_type)?;
+    let word = bytes_to_bits(&current[..size]);
+    let word = (word & !(mask << field.offset)) | (value << field.offset);
+    Ok(word.to_le_bytes()[..size].to_vec())
+}
+
+/// Attach the enum labels, bit fields and scaling of a sidecar config (JSON) to matching
+/// variables. Entries in the config replace anything found in the debug info or flash.
+pub fn apply_decoding_config(config_json: &str, mut variables: Vec<VariableInfo>) -> Result<Vec<VariableInfo>, String> {
+    let config: DecodingConfig = serde_json::from_str(config_json)
+        .map_err(|e| format!("Failed to parse decoding config: {}", e))?;
+
+    for decoding in config.variables {
+        let Some(variable) = variables.iter_mut().find(|v| v.name == decoding.name) else {
+            eprintln!("Decoding config: no variable named '{}'", decoding.name);
+            continue;
+        };
+        if !decoding.enum_labels.is_empty() && !is_integer_type(&variable.var_type) {
+            return Err(format!("Enum labels need an integer type, but {} is {}", variable.name, variable.var_type));
+        }
+        validate_bit_fields(&variable.var_type, &decoding.bit_fields)
+            .map_err(|e| format!("{}: {}", variable.name, e))?;
+
+        if !decoding.enum_labels.is_empty() {
+            variable.enum_labels = decoding.enum_labels;
+        }
+        if !decoding.bit_fields.is_empty() {
+            variable.bit_fields = decoding.bit_fields;
+        }
+        if decoding.scale.is_some() || decoding.offset.is_some() || decoding.q_format.is_some() {
+            variable.scale = decoding.scale;
+            variable.offset = decoding.offset;
+            variable.q_format = decoding.q_format;
+            validate_scaling(variable).map_err(|e| format!("{}: {}", variable.name, e))?;
+        }
+        if decoding.unit.is_some() {
+            variable.unit = decoding.unit;
+        }
+    }
+
+    Ok(variables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(var_type: &str) -> VariableInfo {
+        VariableInfo {
+            name: "status".to_string(),
+            parent: None,
+            address: 0x2000_0000,
+            var_type: var_type.to_string(),
+            access_flags: "RW".to_string(),
+            category: None,
+            min_value: None,
+            max_value: None,
+            element_count: 1,
+            enum_labels: vec![
+                EnumLabel { value: 0, label: "IDLE".to_string() },
+                EnumLabel { value: 2, label: "RUN".to_string() },
+            ],
+            bit_fields: vec![
+                BitField { name: "FAULT".to_string(), offset: 0, width: 1 },
+                BitField { name: "MODE".to_string(), offset: 4, width: 3 },
+            ],
+            scale: None,
+            offset: None,
+            q_format: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn decodes_label_and_bits() {
+        let decoded = decode(&variable("UINT8"), &[0b0101_0001]).unwrap();
+        assert_eq!(decoded.raw, 81.0);
+        assert_eq!(decoded.label, None);
+        assert_eq!(decoded.bits[0].value, 1);
+        assert_eq!(decoded.bits[1].value, 5);
+
+        let decoded = decode(&variable("UINT8"), &[2]).unwrap();
+        assert_eq!(decoded.label.as_deref(), Some("RUN"));
+    }
+
+    #[test]
+    fn sets_one_field_and_keeps_the_rest() {
+        let status = variable("UINT16");
+        let bytes = set_bit_field(&status, &[0xFF, 0xFF], "MODE", 0).unwrap();
+        assert_eq!(bytes, vec![0x8F, 0xFF]);
+        assert!(set_bit_field(&status, &[0, 0], "MODE", 8).is_err());
+        assert!(set_bit_field(&status, &[0, 0], "MISSING", 1).is_err());
+    }
+
+    #[test]
+    fn resolves_labels_for_writes() {
+        assert_eq!(label_value(&variable("UINT8"), "RUN"), Ok(2));
+        assert!(label_value(&variable("UINT8"), "STOP").is_err());
+    }
+
+    #[test]
+    fn rejects_fields_outside_the_type() {
+        let fields = [BitField { name: "HIGH".to_string(), offset: 6, width: 3 }];
+        assert!(validate_bit_fields("UINT8", &fields).is_err());
+        assert!(validate_bit_fields("UINT16", &fields).is_ok());
+        assert!(validate_bit_fields("FLOAT", &fields).is_err());
+        let overflowing = [BitField { name: "WRAP".to_string(), offset: u32::MAX, width: 2 }];
+        assert!(validate_bit_fields("UINT64", &overflowing).is_err());
+
+        // Fields sent by a client are checked too, instead of panicking on the shift
+        let mut status = variable("UINT32");
+        status.bit_fields = vec![BitField { name: "FAR".to_string(), offset: 64, width: 1 }];
+        assert!(decode(&status, &[0; 4]).is_err());
+        assert!(set_bit_field(&status, &[0; 4], "FAR", 1).is_err());
+    }
+
+    #[test]
+    fn applies_sidecar_config() {
+        let mut plain = variable("UINT32");
+        plain.enum_labels.clear();
+        plain.bit_fields.clear();
+        let config = r#"{ "variables": [
+            { "name": "status", "bit_fields": [{ "name": "READY", "offset": 31, "width": 1 }],
+              "q_format": 15, "unit": "V" },
+            { "name": "unknown", "enum_labels": [{ "value": 1, "label": "ON" }] }
+        ] }"#;
+
+        let variables = apply_decoding_config(config, vec![plain]).unwrap();
+        assert_eq!(variables[0].bit_fields[0].name, "READY");
+        assert!(variables[0].enum_labels.is_empty());
+        assert_eq!(variables[0].q_format, Some(15));
+        assert_eq!(variables[0].unit.as_deref(), Some("V"));
+
+        let bad_scale = r#"{ "variables": [{ "name": "status", "scale": 0.0 }] }"#;
+        assert!(apply_decoding_config(bad_scale, vec![variable("UINT32")]).is_err());
+    }
+}
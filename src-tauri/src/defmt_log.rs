@@ -0,0 +1,110 @@
+// defmt log decoding: turns the bytes of the defmt RTT channel into structured log
+// records, using the format string table and source locations of the firmware ELF.
+// Records are stamped with the host monotonic time of the RTT read, the same time base
+// as sampled variables, so log lines can be lined up with plots.
+
+use defmt_decoder::{DecodeError, Frame, Locations, Table};
+use serde::Serialize;
+
+pub(crate) const DEFMT_LOG_EVENT: &str = "defmt-log";
+
+#[derive(Debug, Serialize, Clone)]
+pub struct LogRecord {
+    pub timestamp_us: u64,                // Host monotonic time of the RTT read
+    pub target_timestamp: Option<String>, // As formatted by the firmware's defmt::timestamp!
+    pub level: Option<String>,            // "trace" .. "error"; None for println-style frames
+    pub module: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u64>,
+    pub message: String,
+}
+
+// The decoder's stream decoders borrow the table and are not Send, so received bytes are
+// kept here and decoded with a stream decoder that lives for one feed
+pub(crate) struct DefmtLog {
+    table: Table,
+    locations: Locations,
+    pending: Vec<u8>,   // Bytes of frames not yet complete
+    pub malformed: u64, // Frames that could not be decoded
+}
+
+impl DefmtLog {
+    /// Load the defmt table of a firmware ELF file.
+    pub fn from_elf(data: &[u8]) -> Result<Self, String> {
+        let table = Table::parse(data)
+            .map_err(|e| format!("Failed to parse defmt table: {}", e))?
+            .ok_or("ELF file has no defmt table (.defmt section)")?;
+        let locations = table.get_locations(data).unwrap_or_else(|e| {
+            eprintln!("No defmt source locations: {}", e);
+            Locations::new()
+        });
+        Ok(DefmtLog { table, locations, pending: Vec::new(), malformed: 0 })
+    }
+
+    fn record(&self, frame: &Frame, host_us: u64) -> LogRecord {
+        let location = self.locations.get(&frame.index());
+        LogRecord {
+            timestamp_us: host_us,
+            target_timestamp: frame.display_timestamp().map(|t| t.to_string()),
+            level: frame.level().map(|level| format!("{:?}", level).to_lowercase()),
+            module: location.map(|l| l.module.clone()),
+            file: location.map(|l| l.file.display().to_string()),
+            line: location.map(|l| l.line),
+            message: frame.display_message().to_string(),
+        }
+    }
+
+    fn count_malformed(&mut self) {
+        self.malformed += 1;
+        eprintln!("Malformed defmt frame ({} so far)", self.malformed);
+    }
+
+    /// Decode received bytes into log records; `host_us` is when they were read.
+    pub fn feed(&mut self, bytes: &[u8], host_us: u64) -> Vec<LogRecord> {
+        self.pending.extend_from_slice(bytes);
+        let mut records = Vec::new();
+
+        if self.table.encoding().can_recover() {
+            // rzCOBS frames end with a zero byte; decode every complete frame, and skip
+            // malformed ones
+            let Some(end) = self.pending.iter().rposition(|&byte| byte == 0) else {
+                return records;
+            };
+            let frames: Vec<u8> = self.pending.drain(..=end).collect();
+            let mut malformed = 0;
+            {
+                let mut decoder = self.table.new_stream_decoder();
+                decoder.received(&frames);
+                loop {
+                    match decoder.decode() {
+                        Ok(frame) => records.push(self.record(&frame, host_us)),
+                        Err(DecodeError::UnexpectedEof) => break,
+                        Err(DecodeError::Malformed) => malformed += 1,
+                    }
+                }
+            }
+            for _ in 0..malformed {
+                self.count_malformed();
+            }
+        } else {
+            // Raw frames have no delimiter, so a malformed frame loses the rest of the stream
+            loop {
+                let decoded = self.table.decode(&self.pending)
+                    .map(|(frame, consumed)| (self.record(&frame, host_us), consumed));
+                match decoded {
+                    Ok((record, consumed)) => {
+                        records.push(record);
+                        self.pending.drain(..consumed);
+                    },
+                    Err(DecodeError::UnexpectedEof) => break,
+                    Err(DecodeError::Malformed) => {
+                        self.count_malformed();
+                        self.pending.clear();
+                        break;
+                    },
+                }
+            }
+        }
+        records
+    }
+}
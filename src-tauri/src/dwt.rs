@@ -0,0 +1,110 @@
+// Cortex-M DWT cycle counter (CYCCNT) used as a target-side timestamp for samples.
+// The counter is 32 bits wide, so it wraps every 2^32 core cycles (about 25 s at
+// 168 MHz); CycleClock extends it to 64 bits, using host time to count wraps that
+// happened between two readings.
+
+use probe_rs::MemoryInterface;
+
+const DEMCR: u64 = 0xE000_EDFC; // Debug Exception and Monitor Control Register
+const DEMCR_TRCENA: u32 = 1 << 24; // Enables the DWT and ITM units
+const DWT_CTRL: u64 = 0xE000_1000;
+const DWT_CTRL_CYCCNTENA: u32 = 1 << 0;
+const DWT_CTRL_NOCYCCNT: u32 = 1 << 25; // Set when the cycle counter is not implemented
+const DWT_CTRL_NUMCOMP_SHIFT: u32 = 28; // Number of comparators in bits 31:28
+pub(crate) const DWT_CYCCNT: u32 = 0xE000_1004;
+pub(crate) const CYCCNT_SIZE: usize = 4;
+
+const COUNTER_RANGE: f64 = 4_294_967_296.0; // 2^32
+
+/// Enable trace (TRCENA) and the DWT cycle counter, leaving other bits untouched.
+pub(crate) fn enable_cycle_counter(memory: &mut impl MemoryInterface) -> Result<(), String> {
+    let demcr = memory.read_word_32(DEMCR)
+        .map_err(|e| format!("Failed to read DEMCR: {}", e))?;
+    if demcr & DEMCR_TRCENA == 0 {
+        memory.write_word_32(DEMCR, demcr | DEMCR_TRCENA)
+            .map_err(|e| format!("Failed to set DEMCR.TRCENA: {}", e))?;
+    }
+
+    let ctrl = memory.read_word_32(DWT_CTRL)
+        .map_err(|e| format!("Failed to read DWT_CTRL: {}", e))?;
+    if ctrl & DWT_CTRL_NOCYCCNT != 0 {
+        return Err("This core has no DWT cycle counter".to_string());
+    }
+    if ctrl & DWT_CTRL_CYCCNTENA == 0 {
+        memory.write_word_32(DWT_CTRL, ctrl | DWT_CTRL_CYCCNTENA)
+            .map_err(|e| format!("Failed to enable DWT_CYCCNT: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Number of DWT comparators available for data trace.
+pub(crate) fn comparator_count(memory: &mut impl MemoryInterface) -> Result<usize, String> {
+    let ctrl = memory.read_word_32(DWT_CTRL)
+        .map_err(|e| format!("Failed to read DWT_CTRL: {}", e))?;
+    Ok((ctrl >> DWT_CTRL_NUMCOMP_SHIFT) as usize)
+}
+
+/// Check a configured core clock frequency.
+pub(crate) fn validate_core_clock(core_clock_hz: f64) -> Result<(), String> {
+    if !core_clock_hz.is_finite() || core_clock_hz <= 0.0 {
+        return Err(format!("Core clock {} Hz is not a positive frequency", core_clock_hz));
+    }
+    Ok(())
+}
+
+// Converts raw CYCCNT readings into seconds since the first reading
+#[derive(Debug, Clone)]
+pub(crate) struct CycleClock {
+    core_clock_hz: f64,
+    last: Option<(u32, u64)>, // Raw counter and host time (µs) of the last reading
+    cycles: u64,              // Cycles elapsed since the first reading
+}
+
+impl CycleClock {
+    pub fn new(core_clock_hz: f64) -> Self {
+        CycleClock { core_clock_hz, last: None, cycles: 0 }
+    }
+
+    /// Target time in seconds of a counter value read at host time `host_us`.
+    pub fn seconds(&mut self, raw: u32, host_us: u64) -> f64 {
+        if let Some((last_raw, last_host_us)) = self.last {
+            let delta = raw.wrapping_sub(last_raw) as u64;
+            // A modular delta cannot see whole wraps; host time tells how many happened
+            let expected = host_us.saturating_sub(last_host_us) as f64 * 1e-6 * self.core_clock_hz;
+            let wraps = ((expected - delta as f64) / COUNTER_RANGE).round().max(0.0) as u64;
+            self.cycles += delta + (wraps << 32);
+        }
+        self.last = Some((raw, host_us));
+        self.cycles as f64 / self.core_clock_hz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_cycles_to_seconds_across_wraparound() {
+        let mut clock = CycleClock::new(100e6);
+        assert_eq!(clock.seconds(0xFFFF_0000, 0), 0.0);
+        // 0x2_0000 cycles later, past the wrap
+        assert_eq!(clock.seconds(0x0001_0000, 1_311), 131_072.0 / 100e6);
+    }
+
+    #[test]
+    fn counts_whole_wraps_between_slow_samples() {
+        // At 100 MHz the counter wraps every ~42.9 s; read it 100 s apart
+        let mut clock = CycleClock::new(100e6);
+        clock.seconds(1_000, 0);
+        let raw = (1_000u64 + 10_000_000_000) as u32;
+        let seconds = clock.seconds(raw, 100_000_000);
+        assert!((seconds - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_invalid_clocks() {
+        assert!(validate_core_clock(168e6).is_ok());
+        assert!(validate_core_clock(0.0).is_err());
+        assert!(validate_core_clock(f64::NAN).is_err());
+    }
+}
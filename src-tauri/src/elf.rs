@@ -0,0 +1,776 @@
+use gimli::{AttributeValue, DebuggingInformationEntry, EndianSlice, Operation, RunTimeEndian, Unit};
+use object::{Object, ObjectSection, ObjectSymbol, SectionKind, SymbolKind};
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::decoding::{BitField, EnumLabel};
+use crate::VariableInfo;
+
+type Reader<'a> = EndianSlice<'a, RunTimeEndian>;
+
+const MCULINK_SECTION_NAME: &str = ".mculink";
+
+// Category used for symbols that have no DWARF description
+const SYMBOL_TABLE_CATEGORY: &str = "Symbols (no debug info)";
+
+// Guard against runaway recursion in malformed type information
+const MAX_STRUCT_DEPTH: usize = 8;
+
+/// List global and static variables from a firmware ELF file.
+///
+/// DWARF debug info is used to find addresses and base types. Symbols that only appear in
+/// the ELF symbol table are added with a type guessed from their size.
+pub fn discover_variables(data: &[u8]) -> Result<Vec<VariableInfo>, String> {
+    let file = object::File::parse(data).map_err(|e| format!("Failed to parse ELF file: {}", e))?;
+
+    let mut variables = discover_from_dwarf(&file)?;
+    eprintln!("Found {} variables in DWARF debug info", variables.len());
+
+    // Fill in variables the DWARF info didn't cover (or all of them if the ELF was stripped of DWARF)
+    let known_addresses: HashSet<u32> = variables.iter().map(|v| v.address).collect();
+    let known_names: HashSet<String> = variables.iter().map(|v| v.name.clone()).collect();
+    let mut symbol_count = 0;
+    for symbol in file.symbols() {
+        if symbol.kind() != SymbolKind::Data || symbol.size() == 0 {
+            continue;
+        }
+        let address = symbol.address() as u32;
+        let Ok(name) = symbol.name() else { continue };
+        if known_addresses.contains(&address) || known_names.contains(name) {
+            continue;
+        }
+        let var_type = match symbol.size() {
+            1 => "UINT8",
+            2 => "UINT16",
+            4 => "UINT32",
+            8 => "UINT64",
+            _ => continue,
+        };
+        let read_only = symbol.section_index()
+            .and_then(|index| file.section_by_index(index).ok())
+            .map(|section| section.kind() == SectionKind::ReadOnlyData)
+            .unwrap_or(false);
+
+        variables.push(VariableInfo {
+            name: name.to_string(),
+            parent: None,
+            address,
+            var_type: var_type.to_string(),
+            access_flags: if read_only { "RO" } else { "RW" }.to_string(),
+            category: Some(SYMBOL_TABLE_CATEGORY.to_string()),
+            min_value: None,
+            max_value: None,
+            element_count: 1,
+            enum_labels: Vec::new(),
+            bit_fields: Vec::new(),
+            scale: None,
+            offset: None,
+            q_format: None,
+            unit: None,
+        });
+        symbol_count += 1;
+    }
+    eprintln!("Added {} variables from the symbol table only", symbol_count);
+
+    variables.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(variables)
+}
+
+/// Extract the `.mculink` section from a firmware ELF file.
+/// Returns the section's load address and contents.
+pub fn read_mculink_section(data: &[u8]) -> Result<(u32, Vec<u8>), String> {
+    let file = object::File::parse(data).map_err(|e| format!("Failed to parse ELF file: {}", e))?;
+    let section = file.section_by_name(MCULINK_SECTION_NAME)
+        .ok_or_else(|| format!("ELF file has no {} section", MCULINK_SECTION_NAME))?;
+    let contents = section.uncompressed_data()
+        .map_err(|e| format!("Failed to read {} section: {}", MCULINK_SECTION_NAME, e))?;
+
+    Ok((section.address() as u32, contents.into_owned()))
+}
+
+/// Address of a named symbol in a firmware ELF file.
+pub fn find_symbol(data: &[u8], name: &str) -> Result<u32, String> {
+    let file = object::File::parse(data).map_err(|e| format!("Failed to parse ELF file: {}", e))?;
+    file.symbols()
+        .find(|symbol| symbol.name() == Ok(name))
+        .map(|symbol| symbol.address() as u32)
+        .ok_or_else(|| format!("ELF file has no {} symbol", name))
+}
+
+/// Locate the `.mculink` section's contents within the ELF file itself.
+/// Returns the file offset and size, for patching the section in place.
+pub fn mculink_section_file_range(data: &[u8]) -> Result<(usize, usize), String> {
+    let file = object::File::parse(data).map_err(|e| format!("Failed to parse ELF file: {}", e))?;
+    let section = file.section_by_name(MCULINK_SECTION_NAME)
+        .ok_or_else(|| format!("ELF file has no {} section", MCULINK_SECTION_NAME))?;
+    let (offset, size) = section.file_range()
+        .ok_or_else(|| format!("{} section has no data in the file", MCULINK_SECTION_NAME))?;
+
+    Ok((offset as usize, size as usize))
+}
+
+fn discover_from_dwarf(file: &object::File) -> Result<Vec<VariableInfo>, String> {
+    let endian = if file.is_little_endian() { RunTimeEndian::Little } else { RunTimeEndian::Big };
+
+    let load_section = |id: gimli::SectionId| -> Result<Cow<[u8]>, gimli::Error> {
+        Ok(file.section_by_name(id.name())
+            .and_then(|section| section.uncompressed_data().ok())
+            .unwrap_or(Cow::Borrowed(&[])))
+    };
+    let sections = gimli::DwarfSections::load(load_section)
+        .map_err(|e| format!("Failed to load DWARF sections: {}", e))?;
+    let dwarf = sections.borrow(|section| EndianSlice::new(section, endian));
+
+    let mut variables = Vec::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().map_err(|e| format!("Failed to read DWARF unit: {}", e))? {
+        let unit = dwarf.unit(header).map_err(|e| format!("Failed to parse DWARF unit: {}", e))?;
+
+        // Source file name of the compilation unit, used as the category
+        let unit_name = unit.name
+            .and_then(|name| name.to_string().ok())
+            .map(|name| name.rsplit(['/', '\\']).next().unwrap_or(name).to_string());
+
+        // Track enclosing function names so static locals can be qualified
+        let mut scope: Vec<Option<String>> = Vec::new();
+        let mut depth = 0isize;
+        let mut entries = unit.entries();
+        while let Some((delta, entry)) = entries.next_dfs().map_err(|e| format!("Failed to read DWARF entry: {}", e))? {
+            depth += delta;
+            scope.truncate(depth.max(0) as usize);
+
+            let name = entry_name(&dwarf, &unit, entry);
+            if entry.tag() == gimli::DW_TAG_variable {
+                if let Some(found) = parse_variable(&dwarf, &unit, entry, &scope, unit_name.as_deref()) {
+                    variables.extend(found);
+                }
+            }
+            let function_name = if entry.tag() == gimli::DW_TAG_subprogram { name } else { None };
+            scope.push(function_name);
+        }
+    }
+
+    Ok(variables)
+}
+
+fn parse_variable(
+    dwarf: &gimli::Dwarf<Reader>,
+    unit: &Unit<Reader>,
+    entry: &DebuggingInformationEntry<Reader>,
+    scope: &[Option<String>],
+    unit_name: Option<&str>,
+) -> Option<Vec<VariableInfo>> {
+    // Only variables with a fixed address (globals and statics) can be watched
+    let address = match entry.attr_value(gimli::DW_AT_location).ok()?? {
+        AttributeValue::Exprloc(expr) => {
+            match expr.operations(unit.encoding()).next().ok()?? {
+                Operation::Address { address } => address as u32,
+                _ => return None,
+            }
+        },
+        _ => return None,
+    };
+
+    // C++ definitions refer back to their declaration for name and type
+    let declaration = match entry.attr_value(gimli::DW_AT_specification).ok()? {
+        Some(AttributeValue::UnitRef(offset)) => unit.entry(offset).ok(),
+        _ => None,
+    };
+    let described = declaration.as_ref().unwrap_or(entry);
+
+    let mut name = entry_name(dwarf, unit, entry).or_else(|| entry_name(dwarf, unit, described))?;
+    if let Some(function) = scope.iter().rev().flatten().next() {
+        name = format!("{}::{}", function, name);
+    }
+
+    let type_offset = match described.attr_value(gimli::DW_AT_type).ok()?? {
+        AttributeValue::UnitRef(offset) => offset,
+        _ => return None,
+    };
+    let (resolved, is_const) = match resolve_type(dwarf, unit, type_offset) {
+        Some(resolved) => resolved,
+        None => {
+            eprintln!("Skipping '{}' at 0x{:08X}: unsupported type", name, address);
+            return None;
+        }
+    };
+
+    match resolved {
+        ResolvedType::Base(var_type, element_count, enum_labels) => Some(vec![VariableInfo {
+            name,
+            parent: None,
+            address,
+            var_type: var_type.to_string(),
+            access_flags: if is_const { "RO" } else { "RW" }.to_string(),
+            category: unit_name.map(|n| n.to_string()),
+            min_value: None,
+            max_value: None,
+            element_count,
+            enum_labels,
+            bit_fields: Vec::new(),
+            scale: None,
+            offset: None,
+            q_format: None,
+            unit: None,
+        }]),
+        ResolvedType::Struct(struct_offset) => {
+            let mut fields = Vec::new();
+            expand_members(dwarf, unit, struct_offset, &name, address, is_const, unit_name, 0, &mut fields);
+            eprintln!("Expanded struct '{}' at 0x{:08X} into {} fields", name, address, fields.len());
+            Some(fields)
+        },
+    }
+}
+
+// Add one variable per readable field of a struct or union, named by its dotted path
+// (e.g. `motor.current.q`). Anonymous members are flattened into their parent.
+#[allow(clippy::too_many_arguments)]
+fn expand_members(
+    dwarf: &gimli::Dwarf<Reader>,
+    unit: &Unit<Reader>,
+    struct_offset: gimli::UnitOffset,
+    path: &str,
+    address: u32,
+    is_const: bool,
+    unit_name: Option<&str>,
+    depth: usize,
+    fields: &mut Vec<VariableInfo>,
+) {
+    if depth >= MAX_STRUCT_DEPTH {
+        eprintln!("Not expanding '{}': nested deeper than {} levels", path, MAX_STRUCT_DEPTH);
+        return;
+    }
+    let Ok(mut tree) = unit.entries_tree(Some(struct_offset)) else { return };
+    let Ok(root) = tree.root() else { return };
+    let mut children = root.children();
+
+    while let Ok(Some(child)) = children.next() {
+        let member = child.entry();
+        if member.tag() != gimli::DW_TAG_member {
+            continue;
+        }
+        let member_name = entry_name(dwarf, unit, member);
+        let field_path = match &member_name {
+            Some(member_name) => format!("{}.{}", path, member_name),
+            None => path.to_string(),
+        };
+
+        let Some(AttributeValue::UnitRef(type_offset)) = member.attr_value(gimli::DW_AT_type).ok().flatten() else { continue };
+        // Union members have no location: they all start at offset 0
+        let byte_offset = match member.attr_value(gimli::DW_AT_data_member_location).ok().flatten() {
+            Some(location) => match member_offset(unit, location) {
+                Some(offset) => offset,
+                None => continue,
+            },
+            None => 0,
+        };
+
+        match resolve_type(dwarf, unit, type_offset) {
+            // Labels of an enum-typed bit-field would describe the field, not the storage unit
+            Some((ResolvedType::Base(var_type, 1, _), member_const)) if member.attr(gimli::DW_AT_bit_size).ok().flatten().is_some() => {
+                // Bit-fields are exposed as their storage unit, decoded through a single bit field
+                let Some((storage_offset, bit_field)) = bit_field_location(member, byte_offset, var_type, member_name.as_deref()) else {
+                    eprintln!("Skipping bit-field '{}': unsupported layout", field_path);
+                    continue;
+                };
+                fields.push(VariableInfo {
+                    name: field_path,
+                    parent: Some(path.to_string()),
+                    address: address + storage_offset,
+                    var_type: var_type.to_string(),
+                    access_flags: if is_const || member_const { "RO" } else { "RW" }.to_string(),
+                    category: unit_name.map(|n| n.to_string()),
+                    min_value: None,
+                    max_value: None,
+                    element_count: 1,
+                    enum_labels: Vec::new(),
+                    bit_fields: vec![bit_field],
+                    scale: None,
+                    offset: None,
+                    q_format: None,
+                    unit: None,
+                });
+            },
+            Some((ResolvedType::Base(var_type, element_count, enum_labels), member_const)) => fields.push(VariableInfo {
+                name: field_path,
+                parent: Some(path.to_string()),
+                address: address + byte_offset,
+                var_type: var_type.to_string(),
+                access_flags: if is_const || member_const { "RO" } else { "RW" }.to_string(),
+                category: unit_name.map(|n| n.to_string()),
+                min_value: None,
+                max_value: None,
+                element_count,
+                enum_labels,
+                bit_fields: Vec::new(),
+                scale: None,
+                offset: None,
+                q_format: None,
+                unit: None,
+            }),
+            Some((ResolvedType::Struct(nested), member_const)) => {
+                expand_members(dwarf, unit, nested, &field_path, address + byte_offset, is_const || member_const, unit_name, depth + 1, fields);
+            },
+            None => eprintln!("Skipping field '{}': unsupported type", field_path),
+        }
+    }
+}
+
+// Byte offset of a member: a constant, or a `DW_OP_plus_uconst` expression in DWARF 2
+fn member_offset(unit: &Unit<Reader>, location: AttributeValue<Reader>) -> Option<u32> {
+    if let Some(offset) = location.udata_value() {
+        return Some(offset as u32);
+    }
+    let expr = match location {
+        AttributeValue::Exprloc(expr) => expr,
+        AttributeValue::Block(data) => gimli::Expression(data),
+        _ => return None,
+    };
+    match expr.operations(unit.encoding()).next().ok()?? {
+        Operation::PlusConstant { value } => Some(value as u32),
+        _ => None,
+    }
+}
+
+fn entry_name(dwarf: &gimli::Dwarf<Reader>, unit: &Unit<Reader>, entry: &DebuggingInformationEntry<Reader>) -> Option<String> {
+    let value = entry.attr_value(gimli::DW_AT_name).ok()??;
+    let name = dwarf.attr_string(unit, value).ok()?;
+    name.to_string().ok().map(|n| n.to_string())
+}
+
+// What a variable's type resolves to once qualifiers and typedefs are stripped
+enum ResolvedType {
+    Base(&'static str, u32, Vec<EnumLabel>), // MCU Link type name, element count (1 for scalars), enum labels
+    Struct(gimli::UnitOffset),   // Struct or union to expand field by field
+}
+
+// Follow typedef/const/volatile/array chains down to a base type we can read or a struct.
+// Returns the resolved type and whether it was const-qualified. Multi-dimensional
+// arrays are flattened; arrays of structs are not supported.
+fn resolve_type(dwarf: &gimli::Dwarf<Reader>, unit: &Unit<Reader>, offset: gimli::UnitOffset) -> Option<(ResolvedType, bool)> {
+    let mut offset = offset;
+    let mut is_const = false;
+    let mut element_count = 1u32;
+
+    // Bounded to guard against malformed, cyclic type chains
+    for _ in 0..16 {
+        let entry = unit.entry(offset).ok()?;
+        match entry.tag() {
+            gimli::DW_TAG_typedef | gimli::DW_TAG_volatile_type | gimli::DW_TAG_const_type | gimli::DW_TAG_restrict_type => {
+                if entry.tag() == gimli::DW_TAG_const_type {
+                    is_const = true;
+                }
+                offset = match entry.attr_value(gimli::DW_AT_type).ok()?? {
+                    AttributeValue::UnitRef(next) => next,
+                    _ => return None,
+                };
+            },
+            gimli::DW_TAG_array_type => {
+                element_count = element_count.checked_mul(array_length(unit, offset)?)?;
+                offset = match entry.attr_value(gimli::DW_AT_type).ok()?? {
+                    AttributeValue::UnitRef(next) => next,
+                    _ => return None,
+                };
+            },
+            gimli::DW_TAG_base_type => {
+                let byte_size = entry.attr(gimli::DW_AT_byte_size).ok()??.udata_value()?;
+                let encoding = match entry.attr_value(gimli::DW_AT_encoding).ok()?? {
+                    AttributeValue::Encoding(encoding) => encoding,
+                    _ => return None,
+                };
+                // Arrays of plain `char` are text buffers; int8_t/uint8_t arrays stay numeric
+                let is_char = matches!(encoding, gimli::DW_ATE_signed_char | gimli::DW_ATE_unsigned_char)
+                    && entry_name(dwarf, unit, &entry).as_deref() == Some("char");
+                let var_type = match (encoding, byte_size) {
+                    _ if is_char && element_count > 1 => "STRING",
+                    (gimli::DW_ATE_float, 4) => "FLOAT",
+                    (gimli::DW_ATE_float, 8) => "DOUBLE",
+                    (gimli::DW_ATE_boolean, 1) => "BOOL",
+                    (gimli::DW_ATE_signed | gimli::DW_ATE_signed_char, 1) => "INT8",
+                    (gimli::DW_ATE_unsigned | gimli::DW_ATE_unsigned_char, 1) => "UINT8",
+                    (gimli::DW_ATE_signed, 2) => "INT16",
+                    (gimli::DW_ATE_unsigned, 2) => "UINT16",
+                    (gimli::DW_ATE_signed, 4) => "INT32",
+                    (gimli::DW_ATE_unsigned, 4) => "UINT32",
+                    (gimli::DW_ATE_signed, 8) => "INT64",
+                    (gimli::DW_ATE_unsigned, 8) => "UINT64",
+                    _ => return None,
+                };
+                return Some((ResolvedType::Base(var_type, element_count, Vec::new()), is_const));
+            },
+            gimli::DW_TAG_enumeration_type => {
+                let byte_size = entry.attr(gimli::DW_AT_byte_size).ok()??.udata_value()?;
+                let labels = enum_labels(dwarf, unit, offset);
+                // Enums with negative enumerators are stored signed
+                let signed = labels.iter().any(|label| label.value < 0);
+                let var_type = match (byte_size, signed) {
+                    (1, false) => "UINT8",
+                    (1, true) => "INT8",
+                    (2, false) => "UINT16",
+                    (2, true) => "INT16",
+                    (4, false) => "UINT32",
+                    (4, true) => "INT32",
+                    (8, false) => "UINT64",
+                    (8, true) => "INT64",
+                    _ => return None,
+                };
+                return Some((ResolvedType::Base(var_type, element_count, labels), is_const));
+            },
+            gimli::DW_TAG_structure_type | gimli::DW_TAG_union_type if element_count == 1 => {
+                return Some((ResolvedType::Struct(offset), is_const));
+            },
+            _ => return None,
+        }
+    }
+
+    None
+}
+
+// Total number of elements of an array type, from its subrange children.
+// Flexible and variable-length arrays have no fixed length and are skipped.
+fn array_length(unit: &Unit<Reader>, offset: gimli::UnitOffset) -> Option<u32> {
+    let mut tree = unit.entries_tree(Some(offset)).ok()?;
+    let root = tree.root().ok()?;
+    let mut children = root.children();
+    let mut length = 1u32;
+    let mut dimensions = 0;
+    while let Some(child) = children.next().ok()? {
+        let entry = child.entry();
+        if entry.tag() != gimli::DW_TAG_subrange_type {
+            continue;
+        }
+        let count = match entry.attr(gimli::DW_AT_count).ok()? {
+            Some(count) => count.udata_value()?,
+            None => entry.attr(gimli::DW_AT_upper_bound).ok()??.udata_value()? + 1,
+        };
+        length = length.checked_mul(u32::try_from(count).ok()?)?;
+        dimensions += 1;
+    }
+
+    if dimensions == 0 || length == 0 { None } else { Some(length) }
+}
+
+// Enumerator names and values of an enum type
+fn enum_labels(dwarf: &gimli::Dwarf<Reader>, unit: &Unit<Reader>, offset: gimli::UnitOffset) -> Vec<EnumLabel> {
+    let mut labels = Vec::new();
+    let Ok(mut tree) = unit.entries_tree(Some(offset)) else { return labels };
+    let Ok(root) = tree.root() else { return labels };
+    let mut children = root.children();
+    while let Ok(Some(child)) = children.next() {
+        let entry = child.entry();
+        if entry.tag() != gimli::DW_TAG_enumerator {
+            continue;
+        }
+        let value = match entry.attr_value(gimli::DW_AT_const_value).ok().flatten() {
+            Some(AttributeValue::Sdata(value)) => value,
+            Some(AttributeValue::Udata(value)) => value as i64,
+            Some(AttributeValue::Data1(value)) => value as i64,
+            Some(AttributeValue::Data2(value)) => value as i64,
+            Some(AttributeValue::Data4(value)) => value as i64,
+            Some(AttributeValue::Data8(value)) => value as i64,
+            _ => continue,
+        };
+        if let Some(label) = entry_name(dwarf, unit, entry) {
+            labels.push(EnumLabel { value, label });
+        }
+    }
+    labels
+}
+
+// Storage unit offset (bytes from the struct start) and bit position of a bit-field member.
+// Handles both DWARF 4+ DW_AT_data_bit_offset and the older DW_AT_bit_offset, which counts
+// from the most significant bit of the storage unit.
+fn bit_field_location(member: &DebuggingInformationEntry<Reader>, byte_offset: u32, var_type: &str, name: Option<&str>) -> Option<(u32, BitField)> {
+    let width = member.attr(gimli::DW_AT_bit_size).ok()??.udata_value()? as u32;
+    let unit_size = crate::layout::var_type_size(var_type).ok()? as u32;
+    let unit_bits = unit_size * 8;
+
+    let bit_position = match member.attr(gimli::DW_AT_data_bit_offset).ok()? {
+        Some(data_bit_offset) => data_bit_offset.udata_value()? as u32,
+        None => {
+            let msb_offset = member.attr(gimli::DW_AT_bit_offset).ok()??.udata_value()? as u32;
+            byte_offset * 8 + unit_bits.checked_sub(msb_offset + width)?
+        },
+    };
+    let storage_offset = bit_position / unit_bits * unit_size;
+    let offset = bit_position % unit_bits;
+    if offset + width > unit_bits {
+        return None;
+    }
+
+    Some((storage_offset, BitField {
+        name: name.unwrap_or("value").to_string(),
+        offset,
+        width,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gimli::write::{Address, AttributeValue as Value, DwarfUnit, EndianVec, Expression, Sections, UnitEntryId};
+    use object::write::{Object as ElfWriter, StandardSection, Symbol, SymbolSection};
+
+    type Attributes = Vec<(gimli::DwAt, Value)>;
+
+    fn add(dwarf: &mut DwarfUnit, parent: UnitEntryId, tag: gimli::DwTag, attributes: Attributes) -> UnitEntryId {
+        let id = dwarf.unit.add(parent, tag);
+        let entry = dwarf.unit.get_mut(id);
+        for (name, value) in attributes {
+            entry.set(name, value);
+        }
+        id
+    }
+
+    fn name(name: &str) -> (gimli::DwAt, Value) {
+        (gimli::DW_AT_name, Value::String(name.as_bytes().to_vec()))
+    }
+
+    fn type_of(id: UnitEntryId) -> (gimli::DwAt, Value) {
+        (gimli::DW_AT_type, Value::UnitRef(id))
+    }
+
+    fn udata(attribute: gimli::DwAt, value: u64) -> (gimli::DwAt, Value) {
+        (attribute, Value::Udata(value))
+    }
+
+    fn location(address: u32) -> (gimli::DwAt, Value) {
+        let mut expression = Expression::new();
+        expression.op_addr(Address::Constant(address as u64));
+        (gimli::DW_AT_location, Value::Exprloc(expression))
+    }
+
+    fn base(dwarf: &mut DwarfUnit, type_name: &str, size: u64, encoding: gimli::DwAte) -> UnitEntryId {
+        let root = dwarf.unit.root();
+        add(dwarf, root, gimli::DW_TAG_base_type, vec![name(type_name), udata(gimli::DW_AT_byte_size, size), (gimli::DW_AT_encoding, Value::Encoding(encoding))])
+    }
+
+    fn array(dwarf: &mut DwarfUnit, element: UnitEntryId, subranges: Vec<Attributes>) -> UnitEntryId {
+        let root = dwarf.unit.root();
+        let id = add(dwarf, root, gimli::DW_TAG_array_type, vec![type_of(element)]);
+        for subrange in subranges {
+            add(dwarf, id, gimli::DW_TAG_subrange_type, subrange);
+        }
+        id
+    }
+
+    // A relocatable ARM ELF holding the DWARF of `dwarf`, plus data symbols of
+    // (name, value, size, read-only) that have no debug info
+    fn elf(dwarf: &mut DwarfUnit, symbols: &[(&str, u64, u64, bool)]) -> Vec<u8> {
+        let mut sections = Sections::new(EndianVec::new(gimli::LittleEndian));
+        dwarf.write(&mut sections).unwrap();
+
+        let mut object = ElfWriter::new(object::BinaryFormat::Elf, object::Architecture::Arm, object::Endianness::Little);
+        sections.for_each(|id, data| {
+            if !data.slice().is_empty() {
+                let section = object.add_section(Vec::new(), id.name().as_bytes().to_vec(), SectionKind::Debug);
+                object.append_section_data(section, data.slice(), 1);
+            }
+            Ok::<(), gimli::write::Error>(())
+        }).unwrap();
+
+        let bss = object.section_id(StandardSection::UninitializedData);
+        object.append_section_bss(bss, 0x200, 4);
+        let rodata = object.section_id(StandardSection::ReadOnlyData);
+        object.append_section_data(rodata, &[0; 0x200], 4);
+        for &(name, value, size, read_only) in symbols {
+            object.add_symbol(Symbol {
+                name: name.as_bytes().to_vec(),
+                value,
+                size,
+                kind: SymbolKind::Data,
+                scope: object::SymbolScope::Linkage,
+                weak: false,
+                section: SymbolSection::Section(if read_only { rodata } else { bss }),
+                flags: object::SymbolFlags::None,
+            });
+        }
+        object.write().unwrap()
+    }
+
+    // DWARF 4 of a small firmware unit, with one of each construct discovery handles
+    fn firmware() -> Vec<u8> {
+        let encoding = gimli::Encoding { format: gimli::Format::Dwarf32, version: 4, address_size: 4 };
+        let mut dwarf = DwarfUnit::new(encoding);
+        let root = dwarf.unit.root();
+        dwarf.unit.get_mut(root).set(gimli::DW_AT_name, Value::String(b"src/motor.c".to_vec()));
+
+        let float = base(&mut dwarf, "float", 4, gimli::DW_ATE_float);
+        let int = base(&mut dwarf, "int", 4, gimli::DW_ATE_signed);
+        let uint = base(&mut dwarf, "unsigned int", 4, gimli::DW_ATE_unsigned);
+        let ushort = base(&mut dwarf, "unsigned short", 2, gimli::DW_ATE_unsigned);
+        let uchar = base(&mut dwarf, "unsigned char", 1, gimli::DW_ATE_unsigned_char);
+        let char = base(&mut dwarf, "char", 1, gimli::DW_ATE_signed_char);
+
+        let const_float = add(&mut dwarf, root, gimli::DW_TAG_const_type, vec![type_of(float)]);
+        let volatile_float = add(&mut dwarf, root, gimli::DW_TAG_volatile_type, vec![type_of(float)]);
+        let setpoint = add(&mut dwarf, root, gimli::DW_TAG_typedef, vec![name("setpoint_t"), type_of(volatile_float)]);
+
+        let mode = add(&mut dwarf, root, gimli::DW_TAG_enumeration_type, vec![name("mode_t"), udata(gimli::DW_AT_byte_size, 1)]);
+        add(&mut dwarf, mode, gimli::DW_TAG_enumerator, vec![name("IDLE"), udata(gimli::DW_AT_const_value, 0)]);
+        add(&mut dwarf, mode, gimli::DW_TAG_enumerator, vec![name("RUN"), udata(gimli::DW_AT_const_value, 2)]);
+        let direction = add(&mut dwarf, root, gimli::DW_TAG_enumeration_type, vec![name("dir_t"), udata(gimli::DW_AT_byte_size, 1)]);
+        add(&mut dwarf, direction, gimli::DW_TAG_enumerator, vec![name("REVERSE"), (gimli::DW_AT_const_value, Value::Sdata(-1))]);
+        add(&mut dwarf, direction, gimli::DW_TAG_enumerator, vec![name("FORWARD"), (gimli::DW_AT_const_value, Value::Sdata(1))]);
+
+        let text = array(&mut dwarf, char, vec![vec![udata(gimli::DW_AT_count, 16)]]);
+        let matrix = array(&mut dwarf, uchar, vec![vec![udata(gimli::DW_AT_upper_bound, 1)], vec![udata(gimli::DW_AT_upper_bound, 2)]]);
+        let bytes = array(&mut dwarf, uchar, vec![vec![udata(gimli::DW_AT_count, 4)]]);
+        let flexible = array(&mut dwarf, int, vec![Vec::new()]);
+
+        let range = add(&mut dwarf, root, gimli::DW_TAG_structure_type, vec![name("range_t"), udata(gimli::DW_AT_byte_size, 8)]);
+        add(&mut dwarf, range, gimli::DW_TAG_member, vec![name("min"), type_of(float), udata(gimli::DW_AT_data_member_location, 0)]);
+        add(&mut dwarf, range, gimli::DW_TAG_member, vec![name("max"), type_of(float), udata(gimli::DW_AT_data_member_location, 4)]);
+
+        let pid = add(&mut dwarf, root, gimli::DW_TAG_structure_type, vec![name("pid_t"), udata(gimli::DW_AT_byte_size, 28)]);
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![name("kp"), type_of(float), udata(gimli::DW_AT_data_member_location, 0)]);
+        // DWARF 2 style member offset
+        let mut plus_four = Expression::new();
+        plus_four.op_plus_uconst(4);
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![name("ki"), type_of(float), (gimli::DW_AT_data_member_location, Value::Exprloc(plus_four))]);
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![name("mode"), type_of(mode), udata(gimli::DW_AT_data_member_location, 8)]);
+        // Bit-fields sharing the storage unit at offset 12, in DWARF 4 and DWARF 2 form
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![name("ready"), type_of(uint), udata(gimli::DW_AT_bit_size, 1), udata(gimli::DW_AT_data_bit_offset, 96)]);
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![
+            name("count"), type_of(uint), udata(gimli::DW_AT_byte_size, 4), udata(gimli::DW_AT_bit_size, 3),
+            udata(gimli::DW_AT_bit_offset, 28), udata(gimli::DW_AT_data_member_location, 12),
+        ]);
+        let status = add(&mut dwarf, root, gimli::DW_TAG_union_type, vec![udata(gimli::DW_AT_byte_size, 4)]);
+        add(&mut dwarf, status, gimli::DW_TAG_member, vec![name("raw"), type_of(uint)]);
+        add(&mut dwarf, status, gimli::DW_TAG_member, vec![name("half"), type_of(ushort)]);
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![type_of(status), udata(gimli::DW_AT_data_member_location, 16)]);
+        add(&mut dwarf, pid, gimli::DW_TAG_member, vec![name("range"), type_of(range), udata(gimli::DW_AT_data_member_location, 20)]);
+        let const_pid = add(&mut dwarf, root, gimli::DW_TAG_const_type, vec![type_of(pid)]);
+
+        // Malformed: a struct that contains itself
+        let node = add(&mut dwarf, root, gimli::DW_TAG_structure_type, vec![name("node"), udata(gimli::DW_AT_byte_size, 8)]);
+        add(&mut dwarf, node, gimli::DW_TAG_member, vec![name("value"), type_of(int), udata(gimli::DW_AT_data_member_location, 0)]);
+        add(&mut dwarf, node, gimli::DW_TAG_member, vec![name("next"), type_of(node), udata(gimli::DW_AT_data_member_location, 4)]);
+
+        for (variable, variable_type, address) in [
+            ("speed", setpoint, 0x2000_0000),
+            ("limit", const_float, 0x2000_0004),
+            ("mode", mode, 0x2000_0008),
+            ("direction", direction, 0x2000_0009),
+            ("name", text, 0x2000_0010),
+            ("matrix", matrix, 0x2000_0020),
+            ("raw_bytes", bytes, 0x2000_0028),
+            ("samples", flexible, 0x2000_0030),
+            ("pid", pid, 0x2000_0100),
+            ("factory_pid", const_pid, 0x0800_1000),
+            ("list", node, 0x2000_0300),
+        ] {
+            add(&mut dwarf, root, gimli::DW_TAG_variable, vec![name(variable), type_of(variable_type), location(address)]);
+        }
+
+        // Static locals are qualified by their function, also inside nested blocks
+        let function = add(&mut dwarf, root, gimli::DW_TAG_subprogram, vec![name("control_loop")]);
+        add(&mut dwarf, function, gimli::DW_TAG_variable, vec![name("counter"), type_of(uint), location(0x2000_0400)]);
+        let mut on_stack = Expression::new();
+        on_stack.op_fbreg(-4);
+        add(&mut dwarf, function, gimli::DW_TAG_variable, vec![name("error"), type_of(float), (gimli::DW_AT_location, Value::Exprloc(on_stack))]);
+        let block = add(&mut dwarf, function, gimli::DW_TAG_lexical_block, Vec::new());
+        add(&mut dwarf, block, gimli::DW_TAG_variable, vec![name("hits"), type_of(uint), location(0x2000_0404)]);
+        add(&mut dwarf, root, gimli::DW_TAG_variable, vec![name("total"), type_of(uint), location(0x2000_0408)]);
+
+        // C++: the definition refers to its declaration for the name and type
+        let declaration = add(&mut dwarf, root, gimli::DW_TAG_variable, vec![name("gain"), type_of(float), (gimli::DW_AT_declaration, Value::Flag(true))]);
+        add(&mut dwarf, root, gimli::DW_TAG_variable, vec![(gimli::DW_AT_specification, Value::UnitRef(declaration)), location(0x2000_040C)]);
+
+        elf(&mut dwarf, &[("heap_top", 0x100, 2, false), ("table_crc", 0x100, 4, true), ("odd", 0x104, 3, false), ("speed", 0x108, 4, false)])
+    }
+
+    fn find<'a>(variables: &'a [VariableInfo], name: &str) -> &'a VariableInfo {
+        variables.iter().find(|variable| variable.name == name).unwrap_or_else(|| panic!("{} was not discovered", name))
+    }
+
+    #[test]
+    fn resolves_base_types_through_qualifiers_and_typedefs() {
+        let variables = discover_variables(&firmware()).unwrap();
+        let speed = find(&variables, "speed");
+        assert_eq!((speed.address, speed.var_type.as_str(), speed.access_flags.as_str()), (0x2000_0000, "FLOAT", "RW"));
+        assert_eq!(speed.category.as_deref(), Some("motor.c"));
+        assert_eq!(find(&variables, "limit").access_flags, "RO");
+        // Only the definition of a C++ variable is listed, named by its declaration
+        assert_eq!(variables.iter().filter(|variable| variable.name == "gain").count(), 1);
+        assert_eq!(find(&variables, "gain").address, 0x2000_040C);
+    }
+
+    #[test]
+    fn reads_enum_labels_and_array_lengths() {
+        let variables = discover_variables(&firmware()).unwrap();
+        let mode = find(&variables, "mode");
+        assert_eq!(mode.var_type, "UINT8");
+        assert_eq!(mode.enum_labels, vec![EnumLabel { value: 0, label: "IDLE".to_string() }, EnumLabel { value: 2, label: "RUN".to_string() }]);
+        // Negative enumerators make the enum signed
+        let direction = find(&variables, "direction");
+        assert_eq!((direction.var_type.as_str(), direction.enum_labels[0].value), ("INT8", -1));
+
+        let name = find(&variables, "name");
+        assert_eq!((name.var_type.as_str(), name.element_count), ("STRING", 16));
+        // Multi-dimensional arrays are flattened; unsigned char arrays stay numeric
+        let matrix = find(&variables, "matrix");
+        assert_eq!((matrix.var_type.as_str(), matrix.element_count), ("UINT8", 6));
+        assert_eq!(find(&variables, "raw_bytes").element_count, 4);
+        // Flexible arrays have no length to read
+        assert!(variables.iter().all(|variable| variable.name != "samples"));
+    }
+
+    #[test]
+    fn expands_struct_members_and_bit_fields() {
+        let variables = discover_variables(&firmware()).unwrap();
+        let fields: Vec<(&str, u32, &str)> = variables.iter()
+            .filter(|variable| variable.name.starts_with("pid."))
+            .map(|variable| (variable.name.as_str(), variable.address, variable.var_type.as_str()))
+            .collect();
+        assert_eq!(fields, vec![
+            ("pid.count", 0x2000_010C, "UINT32"),
+            ("pid.half", 0x2000_0110, "UINT16"),
+            ("pid.ki", 0x2000_0104, "FLOAT"),
+            ("pid.kp", 0x2000_0100, "FLOAT"),
+            ("pid.mode", 0x2000_0108, "UINT8"),
+            ("pid.range.max", 0x2000_0118, "FLOAT"),
+            ("pid.range.min", 0x2000_0114, "FLOAT"),
+            ("pid.raw", 0x2000_0110, "UINT32"),
+            ("pid.ready", 0x2000_010C, "UINT32"),
+        ]);
+        assert_eq!(find(&variables, "pid.kp").parent.as_deref(), Some("pid"));
+        assert_eq!(find(&variables, "pid.range.min").parent.as_deref(), Some("pid.range"));
+        assert_eq!(find(&variables, "pid.mode").enum_labels.len(), 2);
+        assert_eq!(find(&variables, "pid.ready").bit_fields, vec![BitField { name: "ready".to_string(), offset: 0, width: 1 }]);
+        assert_eq!(find(&variables, "pid.count").bit_fields, vec![BitField { name: "count".to_string(), offset: 1, width: 3 }]);
+        // Fields of a const struct are read-only
+        assert!(variables.iter().filter(|variable| variable.name.starts_with("factory_pid.")).all(|variable| variable.access_flags == "RO"));
+    }
+
+    #[test]
+    fn stops_expanding_structs_nested_too_deep() {
+        let variables = discover_variables(&firmware()).unwrap();
+        let list: Vec<&str> = variables.iter().filter(|variable| variable.name.starts_with("list.")).map(|variable| variable.name.as_str()).collect();
+        assert_eq!(list.len(), MAX_STRUCT_DEPTH);
+        assert!(list.contains(&"list.next.next.next.next.next.next.next.value"));
+    }
+
+    #[test]
+    fn qualifies_static_locals_by_function() {
+        let variables = discover_variables(&firmware()).unwrap();
+        assert_eq!(find(&variables, "control_loop::counter").address, 0x2000_0400);
+        assert_eq!(find(&variables, "control_loop::hits").address, 0x2000_0404);
+        // The scope ends with the function, and locals on the stack are skipped
+        assert_eq!(find(&variables, "total").address, 0x2000_0408);
+        assert!(variables.iter().all(|variable| !variable.name.ends_with("error")));
+    }
+
+    #[test]
+    fn adds_data_symbols_without_debug_info() {
+        let variables = discover_variables(&firmware()).unwrap();
+        let heap_top = find(&variables, "heap_top");
+        assert_eq!((heap_top.var_type.as_str(), heap_top.access_flags.as_str()), ("UINT16", "RW"));
+        assert_eq!(heap_top.category.as_deref(), Some(SYMBOL_TABLE_CATEGORY));
+        let table_crc = find(&variables, "table_crc");
+        assert_eq!((table_crc.var_type.as_str(), table_crc.access_flags.as_str()), ("UINT32", "RO"));
+        // Sizes that are no integer type, and names DWARF already described, are left out
+        assert!(variables.iter().all(|variable| variable.name != "odd"));
+        assert_eq!(find(&variables, "speed").address, 0x2000_0000);
+    }
+}
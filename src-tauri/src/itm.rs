@@ -0,0 +1,288 @@
+// ITM packet decoding for SWO trace: stimulus port writes (printf-style text) and DWT
+// data-trace packets of watched addresses. Packets follow the ARMv7-M ITM protocol;
+// sync, global timestamp, extension, exception and PC packets are skipped. Local
+// timestamps count core cycles and follow the packets they apply to.
+
+use crate::layout::{decode_value, var_type_size};
+use crate::sampler::BatchBuilder;
+use crate::scaling::Scaling;
+use crate::timing::{JitterStats, StreamStats};
+use crate::VariableInfo;
+
+pub(crate) const MAX_DATA_TRACE: usize = 4; // DWT comparators on ARMv7-M
+const MAX_TRACE_SIZE: usize = 4; // Data value packets carry up to a word
+const OVERFLOW: u8 = 0x70;
+const CONTINUATION: u8 = 0x80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ItmEvent {
+    Instrumentation { port: u8, payload: Vec<u8> },
+    DataValue { comparator: u8, write: bool, payload: Vec<u8> },
+    LocalTimestamp { delta: u32 },
+    Overflow, // The ITM dropped packets
+}
+
+// Finds packets in the SWO byte stream
+#[derive(Debug, Default)]
+pub(crate) struct ItmDecoder {
+    pending: Vec<u8>,
+}
+
+// Length of the packet chained by continuation bits starting at `data[1]`, if complete
+fn chained_size(data: &[u8]) -> Option<usize> {
+    data[1..].iter().position(|byte| byte & CONTINUATION == 0).map(|index| index + 2)
+}
+
+impl ItmDecoder {
+    /// Add received bytes and return the events of every complete packet.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<ItmEvent> {
+        self.pending.extend_from_slice(bytes);
+        let mut events = Vec::new();
+        let mut start = 0;
+
+        while start < self.pending.len() {
+            let data = &self.pending[start..];
+            let header = data[0];
+            let size = if header & 0x03 != 0 {
+                // Source packet: instrumentation, or hardware (DWT) when bit 2 is set
+                let payload_size = [0, 1, 2, 4][(header & 0x03) as usize];
+                if data.len() < 1 + payload_size {
+                    break;
+                }
+                let address = header >> 3;
+                let payload = data[1..1 + payload_size].to_vec();
+                if header & 0x04 == 0 {
+                    events.push(ItmEvent::Instrumentation { port: address, payload });
+                } else if address & 0x18 == 0x10 {
+                    // Data value packet: 0b10nnW, comparator n, W set for writes
+                    events.push(ItmEvent::DataValue { comparator: (address >> 1) & 0x03, write: address & 1 != 0, payload });
+                }
+                1 + payload_size
+            } else if header == OVERFLOW {
+                events.push(ItmEvent::Overflow);
+                1
+            } else if header & 0x8F == 0x00 && header != 0x00 {
+                // Single byte local timestamp
+                events.push(ItmEvent::LocalTimestamp { delta: (header >> 4) as u32 });
+                1
+            } else if header & CONTINUATION == 0 {
+                // Sync zeros and single byte extension packets
+                1
+            } else {
+                // Local and global timestamps, extension and sync completion packets
+                // with continuation bytes
+                let size = if header == CONTINUATION {
+                    1
+                } else {
+                    let Some(size) = chained_size(data) else {
+                        break;
+                    };
+                    size
+                };
+                if header & 0xCF == 0xC0 {
+                    let delta = data[1..size].iter().enumerate()
+                        .fold(0u32, |delta, (i, byte)| delta | ((byte & 0x7F) as u32) << (7 * i));
+                    events.push(ItmEvent::LocalTimestamp { delta });
+                }
+                size
+            };
+            start += size;
+        }
+
+        self.pending.drain(..start);
+        events
+    }
+}
+
+// What one watched variable needs to decode its data value packets
+struct TraceChannel {
+    var_type: String,
+    size: usize,
+    scaling: Scaling,
+}
+
+/// DWT data trace of up to four variables, turned into sample batches like the polling
+/// sampler's. Variable `i` is watched by comparator `i`.
+pub(crate) struct DataTraceStream {
+    pub run_id: u64,
+    channels: Vec<TraceChannel>,
+    core_clock_hz: Option<f64>,
+    cycles: u64, // Sum of local timestamps since the stream started
+    pending: Vec<(u64, usize, Option<f64>)>, // Samples waiting for their local timestamp
+    pub batch: BatchBuilder,
+    overflows: u64,
+    stats: JitterStats, // Over target time, or arrival time without a core clock
+}
+
+impl DataTraceStream {
+    /// Check that `variables` can be data traced. With `core_clock_hz`, local
+    /// timestamps are converted to target time in seconds.
+    pub fn new(run_id: u64, variables: &[VariableInfo], core_clock_hz: Option<f64>) -> Result<Self, String> {
+        if variables.is_empty() {
+            return Err("No variables to trace".to_string());
+        }
+        if variables.len() > MAX_DATA_TRACE {
+            return Err(format!("Data trace watches at most {} variables, got {}", MAX_DATA_TRACE, variables.len()));
+        }
+        let channels = variables.iter().map(|variable| {
+            if variable.element_count != 1 || variable.var_type == "STRING" {
+                return Err(format!("{} is not a scalar and cannot be traced", variable.name));
+            }
+            let size = var_type_size(&variable.var_type)?;
+            if size > MAX_TRACE_SIZE {
+                return Err(format!("{} is wider than {} bytes and cannot be traced", variable.name, MAX_TRACE_SIZE));
+            }
+            Ok(TraceChannel { var_type: variable.var_type.clone(), size, scaling: Scaling::of(variable) })
+        }).collect::<Result<Vec<_>, String>>()?;
+
+        Ok(DataTraceStream {
+            run_id,
+            batch: BatchBuilder::new(run_id, channels.len(), 0, core_clock_hz.is_some()),
+            channels,
+            core_clock_hz,
+            cycles: 0,
+            pending: Vec::new(),
+            overflows: 0,
+            stats: JitterStats::new(0.0),
+        })
+    }
+
+    // Add one sample to the batch, with only `channel` set
+    fn push(&mut self, host_us: u64, target_time_s: Option<f64>, channel: usize, value: Option<f64>) {
+        self.stats.record(target_time_s.map_or(host_us, |seconds| (seconds * 1e6) as u64));
+        let mut values = vec![None; self.channels.len()];
+        values[channel] = value;
+        self.batch.push(host_us, target_time_s, values);
+    }
+
+    /// Take one decoded ITM event; `host_us` is when its bytes were read.
+    pub fn event(&mut self, event: &ItmEvent, host_us: u64) {
+        match event {
+            ItmEvent::DataValue { comparator, payload, .. } => {
+                let channel = *comparator as usize;
+                let Some(trace) = self.channels.get(channel) else {
+                    return;
+                };
+                // Narrower accesses are zero extended to the variable's size
+                let mut bytes = payload.clone();
+                bytes.resize(trace.size, 0);
+                let value = decode_value(&trace.var_type, &bytes).ok()
+                    .map(|raw| trace.scaling.to_engineering(raw.as_f64()));
+                if self.core_clock_hz.is_some() {
+                    self.pending.push((host_us, channel, value));
+                } else {
+                    self.push(host_us, None, channel, value);
+                }
+            },
+            ItmEvent::LocalTimestamp { delta } => {
+                self.cycles += *delta as u64;
+                if let Some(core_clock_hz) = self.core_clock_hz {
+                    let target_time_s = self.cycles as f64 / core_clock_hz;
+                    for (host_us, channel, value) in std::mem::take(&mut self.pending) {
+                        self.push(host_us, Some(target_time_s), channel, value);
+                    }
+                }
+            },
+            ItmEvent::Overflow => {
+                self.overflows += 1;
+                self.batch.add_dropped(1);
+            },
+            ItmEvent::Instrumentation { .. } => {},
+        }
+    }
+
+    /// Number of watched variables, one DWT comparator each.
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Times the ITM overflowed; each loses at least one sample.
+    pub fn overflows(&self) -> u64 {
+        self.overflows
+    }
+
+    /// Interval statistics, with overflows as missed deadlines.
+    pub fn report(&self) -> StreamStats {
+        StreamStats {
+            missed_deadlines: self.overflows,
+            ..self.stats.report(self.run_id, &format!("SWO data trace {}", self.run_id))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_stimulus_and_data_trace_packets() {
+        let mut decoder = ItmDecoder::default();
+        let bytes = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x80, // Sync
+            0x01, b'h',                         // Port 0, one byte
+            0x0B, 0x01, 0x02, 0x03, 0x04,       // Port 1, one word
+            0x9F, 0x00, 0x00, 0xC0, 0x3F,       // Comparator 1 write, one word
+            0x94, 0x81, 0x01,                   // Global timestamp
+            0xC0, 0xE8, 0x07,                   // Local timestamp of 1000 cycles
+            0x30,                               // Local timestamp of 3 cycles
+            0x70,                               // Overflow
+        ];
+        assert_eq!(decoder.feed(&bytes), vec![
+            ItmEvent::Instrumentation { port: 0, payload: vec![b'h'] },
+            ItmEvent::Instrumentation { port: 1, payload: vec![0x01, 0x02, 0x03, 0x04] },
+            ItmEvent::DataValue { comparator: 1, write: true, payload: vec![0x00, 0x00, 0xC0, 0x3F] },
+            ItmEvent::LocalTimestamp { delta: 1000 },
+            ItmEvent::LocalTimestamp { delta: 3 },
+            ItmEvent::Overflow,
+        ]);
+    }
+
+    #[test]
+    fn reassembles_packets_split_across_reads() {
+        let mut decoder = ItmDecoder::default();
+        assert!(decoder.feed(&[0x0B, 0x01]).is_empty());
+        assert_eq!(decoder.feed(&[0x02, 0x03, 0x04, 0xC0, 0xE8]).len(), 1);
+        assert_eq!(decoder.feed(&[0x07]), vec![ItmEvent::LocalTimestamp { delta: 1000 }]);
+    }
+
+    fn variable(name: &str, var_type: &str) -> VariableInfo {
+        VariableInfo {
+            name: name.to_string(),
+            parent: None,
+            address: 0x2000_0000,
+            var_type: var_type.to_string(),
+            access_flags: "RO".to_string(),
+            category: None,
+            min_value: None,
+            max_value: None,
+            element_count: 1,
+            enum_labels: Vec::new(),
+            bit_fields: Vec::new(),
+            scale: None,
+            offset: None,
+            q_format: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn data_trace_samples_wait_for_their_timestamp() {
+        let variables = [variable("speed", "UINT16"), variable("current", "FLOAT")];
+        let mut stream = DataTraceStream::new(5, &variables, Some(1e6)).unwrap();
+        stream.event(&ItmEvent::DataValue { comparator: 0, write: true, payload: vec![0x34] }, 100);
+        assert_eq!(stream.batch.len(), 0);
+        stream.event(&ItmEvent::LocalTimestamp { delta: 500 }, 100);
+        stream.event(&ItmEvent::DataValue { comparator: 1, write: true, payload: vec![0x00, 0x00, 0xC0, 0x3F] }, 200);
+        stream.event(&ItmEvent::Overflow, 200);
+        stream.event(&ItmEvent::LocalTimestamp { delta: 1500 }, 200);
+
+        let batch = stream.batch.take();
+        assert_eq!(batch.timestamps_us, vec![100, 200]);
+        assert_eq!(batch.target_times_s, Some(vec![Some(0.0005), Some(0.002)]));
+        assert_eq!(batch.values, vec![vec![Some(52.0), None], vec![None, Some(1.5)]]);
+        assert_eq!(batch.dropped_samples, 1);
+
+        assert!(DataTraceStream::new(6, &[variable("big", "DOUBLE")], None).is_err());
+        assert!(DataTraceStream::new(6, &vec![variables[0].clone(); 5], None).is_err());
+    }
+}
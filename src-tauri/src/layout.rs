@@ -0,0 +1,784 @@
+// Byte-level layout of the .mculink section, shared with mcu_link.h.
+// Everything here decodes from plain byte slices so it can be tested without a probe.
+
+use crate::VariableInfo;
+
+pub(crate) const MCULINK_MAGIC: u32 = 0x4D434C4B; // "MCLK"
+pub(crate) const MAX_SECTION_SIZE: usize = 1024 * 1024; // Sanity limit against corrupted sizes
+pub(crate) const MCULINK_VERSION_MAJOR: u32 = 2; // Table format major version we understand
+pub(crate) const MCULINK_VERSION_MINOR: u32 = 2; // Newest table minor version we know about
+pub(crate) const MCULINK_VERSION_MINOR_CRC: u32 = 1; // First minor version carrying a descriptor CRC
+pub(crate) const MCULINK_LEGACY_VERSION_MAJOR: u32 = 1; // Per-variable record format
+pub(crate) const DESCRIPTOR_SIZE: usize = 24; // sizeof(mculink_descriptor_t)
+pub(crate) const DESCRIPTOR_CRC_OFFSET: usize = 20; // offsetof(mculink_descriptor_t, crc32)
+pub(crate) const LEGACY_HEADER_SIZE: usize = 12; // MCULINK_INIT(): magic, version, section end
+pub(crate) const ENTRY_SIZE: usize = 24; // sizeof(mculink_entry_t)
+
+// Legacy per-variable record: magic, address, type, access, record_size, min, max,
+// [element_count if MCULINK_ACCESS_ARRAY], [scaling if MCULINK_ACCESS_SCALED], name[32], cat[]
+pub(crate) const LEGACY_RECORD_SIZE_OFFSET: usize = 10; // uint16_t record_size (was reserved[2])
+pub(crate) const LEGACY_NAME_OFFSET: usize = 20;
+pub(crate) const LEGACY_ELEMENT_COUNT_SIZE: usize = 4; // uint32_t element_count
+pub(crate) const LEGACY_SCALING_SIZE: usize = 28; // float scale, float offset, uint8_t q_format, pad[3], char unit[16]
+pub(crate) const LEGACY_UNIT_OFFSET: usize = 12; // Within the scaling block
+pub(crate) const LEGACY_NAME_LEN: usize = 32;
+pub(crate) const LEGACY_RECORD_MIN_SIZE: usize = LEGACY_NAME_OFFSET + LEGACY_NAME_LEN; // Scalar record without category
+pub(crate) const LEGACY_ACCESS_ARRAY: u8 = 0x80; // MCULINK_ACCESS_ARRAY
+pub(crate) const LEGACY_ACCESS_SCALED: u8 = 0x40; // MCULINK_ACCESS_SCALED (v1.3+)
+pub(crate) const LEGACY_RECORD_V1_0_SIZE: usize = 84; // v1.0 records leave record_size as 0
+pub(crate) const LEGACY_RECORD_MAX_SIZE: usize = 512; // Sanity limit against garbage sizes
+
+// Largest integer magnitude an f64 holds exactly (2^53)
+const MAX_EXACT_F64_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+// Memory descriptor structure as it appears in MCU flash (v2 table format)
+#[derive(Debug, Clone)]
+pub(crate) struct McuLinkDescriptor {
+    pub magic: u32,        // 0x4D434C4B ("MCLK")
+    pub version: u32,      // Version number
+    pub entry_count: u32,  // Number of variable entries
+    pub entries_offset: u32, // Offset to entries array
+    pub total_size: u32,   // Size of header + entries + string pool in bytes
+    pub crc32: Option<u32>, // CRC32 of the table (v2.1+), 0 if not sealed yet
+}
+
+impl McuLinkDescriptor {
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, String> {
+        if buf.len() < DESCRIPTOR_SIZE {
+            return Err(format!("Descriptor too short: {} bytes", buf.len()));
+        }
+        let version = read_u32_le(buf, 4);
+        Ok(McuLinkDescriptor {
+            magic: read_u32_le(buf, 0),
+            version,
+            entry_count: read_u32_le(buf, 8),
+            entries_offset: read_u32_le(buf, 12),
+            total_size: read_u32_le(buf, 16),
+            crc32: if version & 0xFFFF >= MCULINK_VERSION_MINOR_CRC {
+                Some(read_u32_le(buf, DESCRIPTOR_CRC_OFFSET))
+            } else {
+                None
+            },
+        })
+    }
+
+    pub fn version_string(&self) -> String {
+        version_string(self.version)
+    }
+
+    // Whether the offsets and sizes describe a table that can be read at all
+    fn is_consistent(&self) -> bool {
+        let entries_end = self.entries_offset as u64 + self.entry_count as u64 * ENTRY_SIZE as u64;
+        self.entries_offset as usize >= DESCRIPTOR_SIZE
+            && entries_end <= self.total_size as u64
+            && self.total_size as usize <= MAX_SECTION_SIZE
+    }
+}
+
+// Header emitted by MCULINK_INIT() in front of legacy records
+#[derive(Debug, Clone)]
+pub(crate) struct LegacyInitHeader {
+    pub version: u32,
+    pub section_end: u32, // Address of __mculink_end, 0 if the linker script lacks it
+}
+
+impl LegacyInitHeader {
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, String> {
+        if buf.len() < LEGACY_HEADER_SIZE {
+            return Err(format!("MCULINK_INIT() header too short: {} bytes", buf.len()));
+        }
+        Ok(LegacyInitHeader {
+            version: read_u32_le(buf, 4),
+            section_end: read_u32_le(buf, 8),
+        })
+    }
+
+    pub fn version_string(&self) -> String {
+        version_string(self.version)
+    }
+}
+
+fn version_string(version: u32) -> String {
+    format!("{}.{}", version >> 16, version & 0xFFFF)
+}
+
+// How the contents of a .mculink section are laid out, with the header that says so
+#[derive(Debug, Clone)]
+pub(crate) enum SectionHeader {
+    Table(McuLinkDescriptor),         // v2 descriptor header + entry table + string pool
+    LegacyHeader(LegacyInitHeader),   // v1 records preceded by the MCULINK_INIT() header
+    Legacy,                           // v1 records without any size information
+}
+
+impl SectionHeader {
+    // Both headers start with magic followed by a version word:
+    //  - v2 table: the descriptor header carries the entry table layout and total size
+    //  - v1 with MCULINK_INIT(): the init header carries the section end address and is
+    //    directly followed by the first record
+    // Older v1 images start with a bare magic followed by another magic, or directly
+    // with a per-variable record whose second word is an address. Addresses below
+    // 0x0100_0000 (ITCM, DTCM, flash at 0) look like version words, so each header is
+    // also checked against what follows it.
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, String> {
+        let descriptor = McuLinkDescriptor::from_bytes(buf)?;
+        let major = descriptor.version >> 16;
+        let looks_like_version = descriptor.version != 0 && descriptor.version >> 24 == 0;
+        if descriptor.magic != MCULINK_MAGIC || !looks_like_version {
+            Ok(SectionHeader::Legacy)
+        } else if major == MCULINK_LEGACY_VERSION_MAJOR && read_u32_le(buf, LEGACY_HEADER_SIZE) == MCULINK_MAGIC {
+            Ok(SectionHeader::LegacyHeader(LegacyInitHeader::from_bytes(buf)?))
+        } else if major >= MCULINK_VERSION_MAJOR && descriptor.is_consistent() {
+            Ok(SectionHeader::Table(descriptor))
+        } else {
+            Ok(SectionHeader::Legacy)
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            SectionHeader::Table(descriptor) => format!("v{} table", descriptor.version_string()),
+            SectionHeader::LegacyHeader(header) => format!("v{} records", header.version_string()),
+            SectionHeader::Legacy => "v1 records".to_string(),
+        }
+    }
+}
+
+// CRC32 (IEEE) over the table with the CRC field itself taken as zero
+pub(crate) fn descriptor_crc(table: &[u8]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(&table[..DESCRIPTOR_CRC_OFFSET]);
+    hasher.update(&[0u8; 4]);
+    hasher.update(&table[DESCRIPTOR_CRC_OFFSET + 4..]);
+    hasher.finalize()
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct McuLinkEntry {
+    pub name_offset: u32,   // Offset to null-terminated name string
+    pub address: u32,       // Variable address in RAM
+    pub var_type: u8,       // Type code, see var_type_name()
+    pub access_flags: u8,   // Access: 0=RO, 1=RW
+    pub element_count: u16, // Array length (v2.2+), 0 or 1 for scalars
+    pub category_offset: u32, // Offset to category string
+    pub min_value: f32,     // Min value (for UI)
+    pub max_value: f32,     // Max value (for UI)
+}
+
+impl McuLinkEntry {
+    pub fn from_bytes(buf: &[u8]) -> Result<Self, String> {
+        if buf.len() < ENTRY_SIZE {
+            return Err(format!("Entry too short: {} bytes", buf.len()));
+        }
+        Ok(McuLinkEntry {
+            name_offset: read_u32_le(buf, 0),
+            address: read_u32_le(buf, 4),
+            var_type: buf[8],
+            access_flags: buf[9],
+            // Reserved padding before v2.2, so 0 on older tables
+            element_count: u16::from_le_bytes([buf[10], buf[11]]),
+            category_offset: read_u32_le(buf, 12),
+            min_value: f32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]),
+            max_value: f32::from_le_bytes([buf[20], buf[21], buf[22], buf[23]]),
+        })
+    }
+}
+
+/// Size in bytes of the legacy record starting at `buf`, as laid out by the firmware.
+///
+/// v1.1+ records store `sizeof` the record in the former reserved bytes; v1.0 records
+/// leave them zero and always use the fixed 84-byte layout.
+pub(crate) fn legacy_record_size(buf: &[u8]) -> Result<usize, String> {
+    if buf.len() < LEGACY_RECORD_SIZE_OFFSET + 2 {
+        return Err(format!("Variable record too short: {} bytes", buf.len()));
+    }
+    let size = u16::from_le_bytes([buf[LEGACY_RECORD_SIZE_OFFSET], buf[LEGACY_RECORD_SIZE_OFFSET + 1]]) as usize;
+    let min_size = legacy_name_offset(buf) + LEGACY_NAME_LEN;
+    match size {
+        0 => Ok(LEGACY_RECORD_V1_0_SIZE),
+        _ if (min_size..=LEGACY_RECORD_MAX_SIZE).contains(&size) => Ok(size),
+        _ => Err(format!("Invalid record size: {} bytes", size)),
+    }
+}
+
+// Array records carry an element count and scaled records a scaling block in front of the name
+fn legacy_name_offset(buf: &[u8]) -> usize {
+    legacy_scaling_offset(buf) + if buf[9] & LEGACY_ACCESS_SCALED != 0 { LEGACY_SCALING_SIZE } else { 0 }
+}
+
+fn legacy_scaling_offset(buf: &[u8]) -> usize {
+    LEGACY_NAME_OFFSET + if buf[9] & LEGACY_ACCESS_ARRAY != 0 { LEGACY_ELEMENT_COUNT_SIZE } else { 0 }
+}
+
+/// Best-effort name of a legacy record, for reporting records that fail to decode.
+pub(crate) fn legacy_record_name(buf: &[u8]) -> Option<String> {
+    let start = legacy_name_offset(buf);
+    buf.get(start..start + LEGACY_NAME_LEN).and_then(|name| extract_cstring(name).ok())
+}
+
+// Offset of the record following one of `size` bytes (records are word aligned in flash)
+pub(crate) fn legacy_record_stride(size: usize) -> usize {
+    size.next_multiple_of(4)
+}
+
+/// Decode one legacy per-variable record. `buf` must start at the record's magic
+/// and hold at least `legacy_record_size` bytes; the category runs to the end of the record.
+pub(crate) fn decode_legacy_record(buf: &[u8]) -> Result<VariableInfo, String> {
+    let size = legacy_record_size(buf)?;
+    if buf.len() < size {
+        return Err(format!("Variable record too short: {} of {} bytes", buf.len(), size));
+    }
+
+    // Parse the structure:
+    // uint32_t magic (already verified)
+    // uint32_t address
+    // uint8_t var_type
+    // uint8_t access_flags
+    // uint16_t record_size
+    // float min_value
+    // float max_value
+    // uint32_t element_count (only with MCULINK_ACCESS_ARRAY)
+    // float scale, float offset, uint8_t q_format, uint8_t pad[3], char unit[16]
+    //   (only with MCULINK_ACCESS_SCALED)
+    // char name[32]
+    // char category[] (up to record_size)
+
+    let address = read_u32_le(buf, 4);
+    let var_type = buf[8];
+    let access_flags = buf[9];
+    let min_value = f32::from_le_bytes([buf[12], buf[13], buf[14], buf[15]]);
+    let max_value = f32::from_le_bytes([buf[16], buf[17], buf[18], buf[19]]);
+    let element_count = if access_flags & LEGACY_ACCESS_ARRAY != 0 { read_u32_le(buf, 20) } else { 1 };
+
+    // Extract null-terminated strings
+    let name_offset = legacy_name_offset(buf);
+    let category_offset = name_offset + LEGACY_NAME_LEN;
+    let name = extract_cstring(&buf[name_offset..category_offset])?;
+    let category = extract_cstring(&buf[category_offset..size])?;
+
+    // Identity scaling is stored as scale 1, offset 0, Q0: report those as unset
+    let (mut scale, mut offset, mut q_format, mut unit) = (None, None, None, None);
+    if access_flags & LEGACY_ACCESS_SCALED != 0 {
+        let block = &buf[legacy_scaling_offset(buf)..name_offset];
+        scale = Some(f32::from_le_bytes([block[0], block[1], block[2], block[3]]) as f64).filter(|&s| s != 1.0);
+        offset = Some(f32::from_le_bytes([block[4], block[5], block[6], block[7]]) as f64).filter(|&o| o != 0.0);
+        q_format = Some(block[8]).filter(|&q| q != 0);
+        unit = Some(extract_cstring(&block[LEGACY_UNIT_OFFSET..])?).filter(|u| !u.is_empty());
+    }
+
+    let var_type_str = var_type_name(var_type)?;
+    let access_str = access_name(access_flags & !(LEGACY_ACCESS_ARRAY | LEGACY_ACCESS_SCALED))?;
+
+    Ok(VariableInfo {
+        parent: parent_path(&name),
+        name,
+        address,
+        var_type: var_type_str.to_string(),
+        access_flags: access_str.to_string(),
+        category: if category.is_empty() { None } else { Some(category) },
+        min_value: Some(min_value as f64),
+        max_value: Some(max_value as f64),
+        element_count,
+        enum_labels: Vec::new(),
+        bit_fields: Vec::new(),
+        scale,
+        offset,
+        q_format,
+        unit,
+    })
+}
+
+// Struct fields are exposed under their dotted path; the parent is everything before the last dot
+pub(crate) fn parent_path(name: &str) -> Option<String> {
+    name.rsplit_once('.').map(|(parent, _)| parent.to_string())
+}
+
+pub(crate) fn extract_cstring(bytes: &[u8]) -> Result<String, String> {
+    // Find the null terminator
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+
+    // Convert to string
+    String::from_utf8(bytes[..end].to_vec())
+        .map_err(|e| format!("Invalid UTF-8 string: {}", e))
+}
+
+pub(crate) fn read_u32_le(buf: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([buf[offset], buf[offset + 1], buf[offset + 2], buf[offset + 3]])
+}
+
+pub(crate) fn var_type_name(var_type: u8) -> Result<&'static str, String> {
+    match var_type {
+        0 => Ok("UINT8"),
+        1 => Ok("INT8"),
+        2 => Ok("UINT16"),
+        3 => Ok("INT16"),
+        4 => Ok("UINT32"),
+        5 => Ok("INT32"),
+        6 => Ok("FLOAT"),
+        7 => Ok("UINT64"),
+        8 => Ok("INT64"),
+        9 => Ok("DOUBLE"),
+        10 => Ok("BOOL"),
+        11 => Ok("STRING"),
+        _ => Err(format!("Unknown variable type: {}", var_type)),
+    }
+}
+
+// Size in target memory of a variable of the given type (one character for STRING,
+// whose element count is the buffer length)
+pub(crate) fn var_type_size(var_type: &str) -> Result<usize, String> {
+    match var_type {
+        "UINT8" | "INT8" | "BOOL" | "STRING" => Ok(1),
+        "UINT16" | "INT16" => Ok(2),
+        "UINT32" | "INT32" | "FLOAT" => Ok(4),
+        "UINT64" | "INT64" | "DOUBLE" => Ok(8),
+        _ => Err(format!("Unsupported variable type: {}", var_type)),
+    }
+}
+
+/// A variable's value exactly as stored in target memory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum RawValue {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+}
+
+impl RawValue {
+    // Nearest f64; 64-bit integers beyond 2^53 are rounded
+    pub fn as_f64(self) -> f64 {
+        match self {
+            RawValue::Unsigned(v) => v as f64,
+            RawValue::Signed(v) => v as f64,
+            RawValue::Float(v) => v,
+        }
+    }
+}
+
+impl std::fmt::Display for RawValue {
+    // Exact decimal text; floats use the shortest representation that round-trips
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RawValue::Unsigned(v) => write!(f, "{}", v),
+            RawValue::Signed(v) => write!(f, "{}", v),
+            RawValue::Float(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// Decode little-endian target memory holding a variable of the given type.
+pub(crate) fn decode_value(var_type: &str, buf: &[u8]) -> Result<RawValue, String> {
+    let size = var_type_size(var_type)?;
+    if buf.len() < size {
+        return Err(format!("{} value needs {} bytes, got {}", var_type, size, buf.len()));
+    }
+    let mut bytes = [0u8; 8];
+    bytes[..size].copy_from_slice(&buf[..size]);
+
+    Ok(match var_type {
+        "UINT8" => RawValue::Unsigned(bytes[0] as u64),
+        "INT8" => RawValue::Signed(bytes[0] as i8 as i64),
+        "UINT16" => RawValue::Unsigned(u16::from_le_bytes([bytes[0], bytes[1]]) as u64),
+        "INT16" => RawValue::Signed(i16::from_le_bytes([bytes[0], bytes[1]]) as i64),
+        "UINT32" => RawValue::Unsigned(read_u32_le(&bytes, 0) as u64),
+        "INT32" => RawValue::Signed(read_u32_le(&bytes, 0) as i32 as i64),
+        "FLOAT" => RawValue::Float(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64),
+        "UINT64" => RawValue::Unsigned(u64::from_le_bytes(bytes)),
+        "INT64" => RawValue::Signed(i64::from_le_bytes(bytes)),
+        "DOUBLE" => RawValue::Float(f64::from_le_bytes(bytes)),
+        // Any nonzero byte is true, as in C
+        "BOOL" => RawValue::Unsigned((bytes[0] != 0) as u64),
+        _ => return Err(format!("Unsupported variable type: {}", var_type)),
+    })
+}
+
+// Round `value` to the nearest integer, like Scaling::to_raw, and check that an integer
+// type can hold it; `as` casts would silently truncate and saturate
+fn in_type_range(var_type: &str, value: f64, min: f64, max: f64) -> Result<f64, String> {
+    let rounded = value.round();
+    if value.is_nan() || rounded < min || rounded > max {
+        return Err(format!("{} is outside the range of {} ({}..{})", value, var_type, min, max));
+    }
+    Ok(rounded)
+}
+
+/// Encode a value from the UI as little-endian target memory. Integer types take the
+/// nearest integer; values the type cannot hold are rejected rather than saturated.
+/// NaN and infinities are rejected for every type, floats and BOOL included.
+///
+/// 64-bit integers must be within ±2^53, where f64 is still exact; larger values have
+/// to go through `encode_value_exact`.
+pub(crate) fn encode_value(var_type: &str, value: f64) -> Result<Vec<u8>, String> {
+    if !value.is_finite() {
+        return Err(format!("Cannot write {} to a {}", value, var_type));
+    }
+    Ok(match var_type {
+        "UINT8" => (in_type_range(var_type, value, 0.0, u8::MAX as f64)? as u8).to_le_bytes().to_vec(),
+        "INT8" => (in_type_range(var_type, value, i8::MIN as f64, i8::MAX as f64)? as i8).to_le_bytes().to_vec(),
+        "UINT16" => (in_type_range(var_type, value, 0.0, u16::MAX as f64)? as u16).to_le_bytes().to_vec(),
+        "INT16" => (in_type_range(var_type, value, i16::MIN as f64, i16::MAX as f64)? as i16).to_le_bytes().to_vec(),
+        "UINT32" => (in_type_range(var_type, value, 0.0, u32::MAX as f64)? as u32).to_le_bytes().to_vec(),
+        "INT32" => (in_type_range(var_type, value, i32::MIN as f64, i32::MAX as f64)? as i32).to_le_bytes().to_vec(),
+        "FLOAT" => {
+            if (value as f32).is_infinite() {
+                return Err(format!("{} is outside the range of FLOAT", value));
+            }
+            (value as f32).to_le_bytes().to_vec()
+        },
+        "DOUBLE" => value.to_le_bytes().to_vec(),
+        "BOOL" => vec![(value != 0.0) as u8],
+        "UINT64" | "INT64" => {
+            let value = value.round();
+            if value.is_nan() || value.abs() > MAX_EXACT_F64_INTEGER {
+                return Err(format!("{} cannot be written exactly as a {}; pass it as exact text", value, var_type));
+            }
+            if var_type == "UINT64" {
+                if value < 0.0 {
+                    return Err(format!("{} is negative and cannot be written as a UINT64", value));
+                }
+                (value as u64).to_le_bytes().to_vec()
+            } else {
+                (value as i64).to_le_bytes().to_vec()
+            }
+        },
+        _ => return Err(format!("Unsupported variable type: {}", var_type)),
+    })
+}
+
+/// Encode a value given as decimal text, without going through f64.
+pub(crate) fn encode_value_exact(var_type: &str, text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    let invalid = |e: &dyn std::fmt::Display| format!("Invalid {} value '{}': {}", var_type, text, e);
+    match var_type {
+        "UINT64" => text.parse::<u64>().map(|v| v.to_le_bytes().to_vec()).map_err(|e| invalid(&e)),
+        "INT64" => text.parse::<i64>().map(|v| v.to_le_bytes().to_vec()).map_err(|e| invalid(&e)),
+        "BOOL" => match text {
+            "true" => Ok(vec![1]),
+            "false" => Ok(vec![0]),
+            _ => text.parse::<f64>().map_err(|e| invalid(&e)).and_then(|v| encode_value(var_type, v)),
+        },
+        _ => text.parse::<f64>().map_err(|e| invalid(&e)).and_then(|v| encode_value(var_type, v)),
+    }
+}
+
+/// Text of a char buffer, up to the first NUL (or the whole buffer if there is none).
+pub(crate) fn decode_string(buf: &[u8]) -> Result<String, String> {
+    extract_cstring(buf)
+}
+
+/// Bytes to write into a char buffer of `capacity` bytes: the text, a terminating NUL
+/// and zeros up to the end of the buffer, so no stale text survives after the NUL.
+pub(crate) fn encode_string(text: &str, capacity: usize) -> Result<Vec<u8>, String> {
+    if text.contains('\0') {
+        return Err("Text must not contain NUL characters".to_string());
+    }
+    if text.len() >= capacity {
+        return Err(format!("Text of {} bytes does not fit a {} byte buffer with its terminator", text.len(), capacity));
+    }
+    let mut bytes = vec![0u8; capacity];
+    bytes[..text.len()].copy_from_slice(text.as_bytes());
+    Ok(bytes)
+}
+
+pub(crate) fn access_name(access_flags: u8) -> Result<&'static str, String> {
+    match access_flags {
+        0 => Ok("RO"),
+        1 => Ok("RW"),
+        _ => Err(format!("Unknown access flags: {}", access_flags)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build a legacy record the way MCULINK_EXPOSE_* lays it out
+    fn legacy_record(name: &str, category: &str, category_len: usize, record_size: u16) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&MCULINK_MAGIC.to_le_bytes());
+        buf.extend_from_slice(&0x2000_0010u32.to_le_bytes());
+        buf.push(6); // FLOAT
+        buf.push(1); // RW
+        buf.extend_from_slice(&record_size.to_le_bytes());
+        buf.extend_from_slice(&(-40.0f32).to_le_bytes());
+        buf.extend_from_slice(&85.0f32.to_le_bytes());
+        let mut name_field = [0u8; LEGACY_NAME_LEN];
+        name_field[..name.len()].copy_from_slice(name.as_bytes());
+        buf.extend_from_slice(&name_field);
+        let mut category_field = vec![0u8; category_len];
+        category_field[..category.len()].copy_from_slice(category.as_bytes());
+        buf.extend_from_slice(&category_field);
+        buf
+    }
+
+    #[test]
+    fn v1_0_record_keeps_full_category() {
+        let category = "A very long category name here";
+        let record = legacy_record("motor_speed", category, 32, 0);
+        assert_eq!(record.len(), LEGACY_RECORD_V1_0_SIZE);
+
+        let variable = decode_legacy_record(&record).unwrap();
+        assert_eq!(variable.name, "motor_speed");
+        assert_eq!(variable.address, 0x2000_0010);
+        assert_eq!(variable.var_type, "FLOAT");
+        assert_eq!(variable.access_flags, "RW");
+        assert_eq!(variable.category.as_deref(), Some(category));
+        assert_eq!(variable.min_value, Some(-40.0));
+        assert_eq!(variable.max_value, Some(85.0));
+    }
+
+    #[test]
+    fn record_size_sets_category_length() {
+        let category = "Motor control / closed loop / current regulator";
+        let record = legacy_record("iq_ref", category, 64, 116);
+        assert_eq!(legacy_record_size(&record), Ok(116));
+        assert_eq!(legacy_record_stride(116), 116);
+
+        let variable = decode_legacy_record(&record).unwrap();
+        assert_eq!(variable.category.as_deref(), Some(category));
+    }
+
+    #[test]
+    fn empty_category_is_none() {
+        let record = legacy_record("led_state", "", 32, 84);
+        assert_eq!(decode_legacy_record(&record).unwrap().category, None);
+    }
+
+    #[test]
+    fn category_does_not_read_past_record() {
+        // Next record's magic directly follows a category without a terminator
+        let mut section = legacy_record("a", "Controls", 8, 60);
+        section.extend(legacy_record("b", "Sensors", 32, 0));
+
+        let variable = decode_legacy_record(&section).unwrap();
+        assert_eq!(variable.category.as_deref(), Some("Controls"));
+
+        let stride = legacy_record_stride(legacy_record_size(&section).unwrap());
+        assert_eq!(decode_legacy_record(&section[stride..]).unwrap().name, "b");
+    }
+
+    #[test]
+    fn decodes_array_record() {
+        let mut record = legacy_record("", "", 0, 0);
+        record.truncate(LEGACY_NAME_OFFSET);
+        record[9] = 1 | LEGACY_ACCESS_ARRAY;
+        record[10..12].copy_from_slice(&88u16.to_le_bytes());
+        record.extend_from_slice(&64u32.to_le_bytes());
+        let mut name = [0u8; LEGACY_NAME_LEN];
+        name[..11].copy_from_slice(b"adc_samples");
+        record.extend_from_slice(&name);
+        let mut category = [0u8; 32];
+        category[..7].copy_from_slice(b"Buffers");
+        record.extend_from_slice(&category);
+
+        assert_eq!(legacy_record_name(&record).as_deref(), Some("adc_samples"));
+        let variable = decode_legacy_record(&record).unwrap();
+        assert_eq!(variable.name, "adc_samples");
+        assert_eq!(variable.access_flags, "RW");
+        assert_eq!(variable.element_count, 64);
+        assert_eq!(variable.category.as_deref(), Some("Buffers"));
+    }
+
+    #[test]
+    fn decodes_scaled_array_record() {
+        let mut record = legacy_record("", "", 0, 0);
+        record.truncate(LEGACY_NAME_OFFSET);
+        record[8] = 3; // INT16
+        record[9] = LEGACY_ACCESS_ARRAY | LEGACY_ACCESS_SCALED;
+        record[10..12].copy_from_slice(&116u16.to_le_bytes());
+        record.extend_from_slice(&8u32.to_le_bytes());
+        record.extend_from_slice(&2.5f32.to_le_bytes());
+        record.extend_from_slice(&0.0f32.to_le_bytes());
+        record.extend_from_slice(&[15, 0, 0, 0]);
+        let mut unit = [0u8; 16];
+        unit[..1].copy_from_slice(b"A");
+        record.extend_from_slice(&unit);
+        let mut name = [0u8; LEGACY_NAME_LEN];
+        name[..7].copy_from_slice(b"phase_i");
+        record.extend_from_slice(&name);
+        record.extend_from_slice(&[0u8; 32]);
+
+        assert_eq!(legacy_record_name(&record).as_deref(), Some("phase_i"));
+        let variable = decode_legacy_record(&record).unwrap();
+        assert_eq!(variable.element_count, 8);
+        assert_eq!(variable.scale, Some(2.5));
+        assert_eq!(variable.offset, None);
+        assert_eq!(variable.q_format, Some(15));
+        assert_eq!(variable.unit.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn round_trips_strings_within_the_buffer() {
+        assert_eq!(decode_string(b"v1.4.2\0garbage"), Ok("v1.4.2".to_string()));
+        assert_eq!(decode_string(b"full"), Ok("full".to_string()));
+
+        let bytes = encode_string("E42", 8).unwrap();
+        assert_eq!(bytes, b"E42\0\0\0\0\0".to_vec());
+        assert_eq!(decode_string(&bytes), Ok("E42".to_string()));
+        // The terminator always needs room
+        assert!(encode_string("12345678", 8).is_err());
+        assert!(encode_string("a\0b", 8).is_err());
+    }
+
+    #[test]
+    fn scalar_record_has_one_element() {
+        let record = legacy_record("temperature", "Sensors", 32, 0);
+        assert_eq!(decode_legacy_record(&record).unwrap().element_count, 1);
+    }
+
+    #[test]
+    fn truncated_record_is_rejected() {
+        let record = legacy_record("temperature", "Sensors", 32, 0);
+        assert!(decode_legacy_record(&record[..70]).is_err());
+    }
+
+    #[test]
+    fn invalid_record_size_is_rejected() {
+        let record = legacy_record("temperature", "Sensors", 32, 40);
+        assert!(legacy_record_size(&record).is_err());
+        assert!(decode_legacy_record(&record).is_err());
+    }
+
+    #[test]
+    fn unknown_type_is_rejected() {
+        let mut record = legacy_record("temperature", "Sensors", 32, 0);
+        record[8] = 0xFF;
+        assert!(decode_legacy_record(&record).is_err());
+    }
+
+    #[test]
+    fn detects_section_formats() {
+        let mut header = Vec::new();
+        for word in [MCULINK_MAGIC, 0x0002_0001, 3, 24, 200, 0] {
+            header.extend_from_slice(&word.to_le_bytes());
+        }
+        let Ok(SectionHeader::Table(table)) = SectionHeader::from_bytes(&header) else {
+            panic!("not detected as a table");
+        };
+        assert_eq!(table.version_string(), "2.1");
+        assert_eq!(table.crc32, Some(0));
+
+        header[4..8].copy_from_slice(&0x0001_0001u32.to_le_bytes());
+        header[8..12].copy_from_slice(&0x0800_0400u32.to_le_bytes());
+        header[12..16].copy_from_slice(&MCULINK_MAGIC.to_le_bytes()); // First record
+        let Ok(SectionHeader::LegacyHeader(init)) = SectionHeader::from_bytes(&header) else {
+            panic!("not detected as an MCULINK_INIT() header");
+        };
+        assert_eq!(init.version_string(), "1.1");
+        assert_eq!(init.section_end, 0x0800_0400);
+
+        // Headerless v1 image: a record whose second word is a RAM address
+        let record = legacy_record("temperature", "Sensors", 32, 0);
+        assert!(matches!(SectionHeader::from_bytes(&record), Ok(SectionHeader::Legacy)));
+    }
+
+    #[test]
+    fn low_address_records_are_not_headers() {
+        // ITCM, flash at 0x10000 (v1-like) and flash at 0x20000 (v2-like)
+        for address in [0x0000_0400u32, 0x0001_0400, 0x0002_0000] {
+            for (min, max) in [(-40.0f32, 85.0f32), (0.0, 3000.0), (0.0, 0.0)] {
+                let mut record = legacy_record("temperature", "Sensors", 32, 0);
+                record[4..8].copy_from_slice(&address.to_le_bytes());
+                record[12..16].copy_from_slice(&min.to_le_bytes());
+                record[16..20].copy_from_slice(&max.to_le_bytes());
+                assert!(matches!(SectionHeader::from_bytes(&record), Ok(SectionHeader::Legacy)), "0x{:08X} {}..{}", address, min, max);
+                assert_eq!(decode_legacy_record(&record).unwrap().address, address);
+            }
+        }
+    }
+
+    #[test]
+    fn decodes_table_entry() {
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&48u32.to_le_bytes());
+        entry.extend_from_slice(&0x2000_0004u32.to_le_bytes());
+        entry.extend_from_slice(&[2, 0, 0, 1]); // UINT16, RO, 256 elements
+        entry.extend_from_slice(&60u32.to_le_bytes());
+        entry.extend_from_slice(&0.0f32.to_le_bytes());
+        entry.extend_from_slice(&3000.0f32.to_le_bytes());
+
+        let entry = McuLinkEntry::from_bytes(&entry).unwrap();
+        assert_eq!(entry.name_offset, 48);
+        assert_eq!(entry.address, 0x2000_0004);
+        assert_eq!(entry.var_type, 2);
+        assert_eq!(entry.element_count, 256);
+        assert_eq!(entry.category_offset, 60);
+        assert_eq!(entry.max_value, 3000.0);
+    }
+
+    #[test]
+    fn decodes_64_bit_values_exactly() {
+        let big = u64::MAX - 1;
+        let value = decode_value("UINT64", &big.to_le_bytes()).unwrap();
+        assert_eq!(value, RawValue::Unsigned(big));
+        assert_eq!(value.to_string(), "18446744073709551614");
+
+        let negative = decode_value("INT64", &(-9_007_199_254_740_993i64).to_le_bytes()).unwrap();
+        assert_eq!(negative.to_string(), "-9007199254740993");
+
+        let gain = decode_value("DOUBLE", &0.1f64.to_le_bytes()).unwrap();
+        assert_eq!(gain, RawValue::Float(0.1));
+        assert_eq!(gain.to_string(), "0.1");
+    }
+
+    #[test]
+    fn decodes_bool_as_c_truthiness() {
+        assert_eq!(decode_value("BOOL", &[0]).unwrap().as_f64(), 0.0);
+        assert_eq!(decode_value("BOOL", &[0x5A]).unwrap().as_f64(), 1.0);
+        assert_eq!(encode_value("BOOL", 3.0).unwrap(), vec![1]);
+        assert_eq!(encode_value_exact("BOOL", "false").unwrap(), vec![0]);
+    }
+
+    #[test]
+    fn rejects_64_bit_values_f64_cannot_hold() {
+        assert_eq!(encode_value("INT64", -42.0).unwrap(), (-42i64).to_le_bytes().to_vec());
+        assert!(encode_value("UINT64", 2f64.powi(60)).is_err());
+        assert!(encode_value("UINT64", -1.0).is_err());
+        assert_eq!(encode_value("INT64", 1.5).unwrap(), 2i64.to_le_bytes().to_vec());
+
+        let exact = encode_value_exact("UINT64", "18446744073709551615").unwrap();
+        assert_eq!(exact, u64::MAX.to_le_bytes().to_vec());
+        assert!(encode_value_exact("INT64", "9223372036854775808").is_err());
+    }
+
+    #[test]
+    fn rejects_values_the_type_cannot_hold() {
+        assert_eq!(encode_value("UINT8", 255.0).unwrap(), vec![255]);
+        // Fractions round to the nearest integer, halves away from zero
+        assert_eq!(encode_value("INT8", -1.5).unwrap(), vec![0xFE]);
+        assert_eq!(encode_value("UINT16", 2.4).unwrap(), 2u16.to_le_bytes().to_vec());
+        assert_eq!(encode_value("INT32", -0.6).unwrap(), (-1i32).to_le_bytes().to_vec());
+        assert!(encode_value("UINT8", 255.5).is_err());
+        assert!(encode_value("UINT8", 256.0).is_err());
+        assert!(encode_value("UINT16", -1.0).is_err());
+        assert!(encode_value("INT16", 40000.0).is_err());
+        assert!(encode_value("INT32", f64::NAN).is_err());
+        assert!(encode_value("FLOAT", 1e40).is_err());
+        // NaN would otherwise be written as true or as a NaN float
+        for var_type in ["BOOL", "FLOAT", "DOUBLE", "UINT64"] {
+            assert!(encode_value(var_type, f64::NAN).is_err());
+            assert!(encode_value(var_type, f64::INFINITY).is_err());
+        }
+        assert!(encode_value_exact("FLOAT", "NaN").is_err());
+    }
+
+    #[test]
+    fn round_trips_every_type() {
+        for var_type in ["UINT8", "INT8", "UINT16", "INT16", "UINT32", "INT32", "FLOAT", "UINT64", "INT64", "DOUBLE", "BOOL"] {
+            let bytes = encode_value(var_type, 1.0).unwrap();
+            assert_eq!(bytes.len(), var_type_size(var_type).unwrap());
+            assert_eq!(decode_value(var_type, &bytes).unwrap().as_f64(), 1.0, "{}", var_type);
+        }
+    }
+
+    #[test]
+    fn crc_ignores_crc_field() {
+        let mut table = vec![0u8; 64];
+        table[..4].copy_from_slice(&MCULINK_MAGIC.to_le_bytes());
+        let crc = descriptor_crc(&table);
+        table[DESCRIPTOR_CRC_OFFSET..DESCRIPTOR_CRC_OFFSET + 4].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(descriptor_crc(&table), crc);
+
+        table[40] ^= 1;
+        assert_ne!(descriptor_crc(&table), crc);
+    }
+}
@@ -1,8 +1,42 @@
 use probe_rs::{Permissions, Session, MemoryInterface};
+use probe_rs::config::TargetSelector;
 use probe_rs::probe::list::Lister;
 use serde::{Deserialize, Serialize};
+use std::ops::Range;
 use std::sync::Mutex;
 
+mod chip_id;
+mod decoding;
+mod defmt_log;
+mod dwt;
+mod elf;
+mod itm;
+mod layout;
+mod read_plan;
+mod rtt;
+mod rtt_stream;
+mod sampler;
+mod scaling;
+mod swo;
+mod timing;
+mod write_guard;
+mod write_mode;
+
+use chip_id::ChipIdentity;
+use decoding::{BitField, DecodedValue, EnumLabel};
+use scaling::{validate_scaling, Scaling};
+use timing::{StreamStats, TimedValue, TimedValues};
+use write_guard::LimitPolicy;
+use write_mode::{WriteMode, WriteReport};
+use layout::{
+    parent_path, McuLinkDescriptor, McuLinkEntry, SectionHeader, decode_legacy_record, descriptor_crc, extract_cstring,
+    legacy_record_name, legacy_record_size, legacy_record_stride, read_u32_le, var_type_name, access_name,
+    RawValue, var_type_size, decode_value, encode_value_exact, decode_string, encode_string,
+    MAX_SECTION_SIZE, MCULINK_MAGIC, MCULINK_VERSION_MAJOR, MCULINK_VERSION_MINOR, MCULINK_VERSION_MINOR_CRC,
+    DESCRIPTOR_SIZE, DESCRIPTOR_CRC_OFFSET, LEGACY_HEADER_SIZE, ENTRY_SIZE,
+    LEGACY_RECORD_MIN_SIZE,
+};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProbeInfo {
     pub name: String,
@@ -12,22 +46,92 @@ pub struct ProbeInfo {
     pub probe_type: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TargetInfo {
+    pub name: String,
+    pub family: String,
+    pub manufacturer: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SessionInfo {
     pub target_name: String,
     pub connected: bool,
-    pub chip_id: Option<String>,
+    pub auto_detected: bool,
+    pub chip_id: Option<ChipIdentity>,
+    pub write_mode: WriteMode, // How write_variable writes to this target
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VariableInfo {
     pub name: String,
+    pub parent: Option<String>, // Dotted path of the enclosing struct, e.g. "motor.current"
     pub address: u32,
-    pub var_type: String, // "UINT8", "FLOAT", etc.
+    pub var_type: String, // "UINT8", "FLOAT", "STRING", etc.
     pub access_flags: String, // "RO", "RW"
     pub category: Option<String>, // "Controls", "Sensors", etc.
     pub min_value: Option<f64>,
     pub max_value: Option<f64>,
+    pub element_count: u32, // Number of array elements, 1 for scalars
+    #[serde(default)]
+    pub enum_labels: Vec<EnumLabel>, // Value names for enum variables
+    #[serde(default)]
+    pub bit_fields: Vec<BitField>, // Packed fields of flag/status words
+    pub scale: Option<f64>,   // Engineering units per raw count, see scaling.rs
+    pub offset: Option<f64>,  // Engineering value of a raw 0
+    pub q_format: Option<u8>, // Fractional bits of a Qn fixed-point value
+    pub unit: Option<String>, // Display unit, e.g. "°C", "V"
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RejectedEntry {
+    pub location: u32,         // Flash address of the rejected record or table entry
+    pub name: Option<String>,  // Variable name, if it could be decoded
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum CrcStatus {
+    Valid,      // Descriptor CRC matches the table contents
+    Mismatch,   // Descriptor is corrupted; every entry was rejected
+    Unsealed,   // CRC field present but not filled in by `--seal-mculink`; rejected unless allowed
+    NotPresent, // Format has no CRC: v2.0 tables are rejected unless allowed, v1 records are used with a warning
+}
+
+// Warning for v1 records, which existing MCULINK_EXPOSE_* firmware emits and nothing can seal
+const UNCHECKED_RECORDS: &str = "v1 records carry no CRC; their contents could not be checked";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscoveryReport {
+    pub format: String,                     // "v2 table", "v1 records"
+    pub descriptor_version: Option<String>, // e.g. "2.1"
+    pub crc_status: CrcStatus,
+    pub variables: Vec<VariableInfo>,
+    pub rejected: Vec<RejectedEntry>,
+    pub warnings: Vec<String>,
+}
+
+impl DiscoveryReport {
+    fn new(format: &str) -> Self {
+        DiscoveryReport {
+            format: format.to_string(),
+            descriptor_version: None,
+            crc_status: CrcStatus::NotPresent,
+            variables: Vec::new(),
+            rejected: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+    
+    fn reject(&mut self, location: u32, name: Option<String>, reason: String) {
+        eprintln!("Rejected entry at 0x{:08X} ({}): {}", location, name.as_deref().unwrap_or("?"), reason);
+        self.rejected.push(RejectedEntry { location, name, reason });
+    }
+    
+    fn warn(&mut self, warning: String) {
+        eprintln!("Discovery warning: {}", warning);
+        self.warnings.push(warning);
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -39,28 +143,12 @@ pub struct VariableValue {
 // Global session manager for probe-rs session
 static SESSION_MANAGER: Mutex<Option<Session>> = Mutex::new(None);
 
-// Memory descriptor structure as it appears in MCU flash
-#[derive(Debug, Clone)]
-struct McuLinkDescriptor {
-    magic: u32,        // 0x4D434C4B ("MCLK")
-    version: u32,      // Version number
-    entry_count: u32,  // Number of variable entries
-    entries_offset: u32, // Offset to entries array
-}
-
-#[derive(Debug, Clone)]
-struct McuLinkEntry {
-    name_offset: u32,   // Offset to null-terminated name string
-    address: u32,       // Variable address in RAM
-    var_type: u8,       // Type: 0=UINT8, 1=INT8, 2=UINT16, 3=INT16, 4=UINT32, 5=INT32, 6=FLOAT
-    access_flags: u8,   // Access: 0=RO, 1=RW
-    category_offset: u32, // Offset to category string
-    min_value: f32,     // Min value (for UI)
-    max_value: f32,     // Max value (for UI)
-}
-
-const MCULINK_MAGIC: u32 = 0x4D434C4B; // "MCLK"
+const MAX_ARRAY_BYTES: usize = 64 * 1024; // Largest array read in a single transfer
+const LEGACY_SCAN_CHUNK: usize = 4096; // Bulk read size when the section size is unknown
+const VERIFY_DELAY: std::time::Duration = std::time::Duration::from_millis(10); // Lets firmware clamp a written value before readback
 const DEFAULT_MCULINK_ADDRESS: u32 = 0x080F0000; // Fixed address in linker script
+const MAX_TARGET_RESULTS: usize = 100; // Keep the chip picker list manageable
+const AUTO_TARGET: &str = "auto"; // Special target name for auto-detection
 
 #[tauri::command]
 async fn detect_probes() -> Result<Vec<ProbeInfo>, String> {
@@ -82,15 +170,97 @@ async fn detect_probes() -> Result<Vec<ProbeInfo>, String> {
     Ok(probe_infos)
 }
 
+/// Score how well `query` fuzzy-matches `candidate` (lower is better).
+///
+/// Exact matches beat prefix matches, which beat substring matches, which beat
+/// in-order subsequence matches. Returns `None` if the query does not match at all.
+fn fuzzy_match_score(query: &str, candidate: &str) -> Option<usize> {
+    let query = query.to_lowercase();
+    let candidate = candidate.to_lowercase();
+
+    if query.is_empty() {
+        return Some(0);
+    }
+    if candidate == query {
+        return Some(0);
+    }
+    if candidate.starts_with(&query) {
+        return Some(1 + candidate.len() - query.len());
+    }
+    if let Some(pos) = candidate.find(&query) {
+        return Some(100 + pos);
+    }
+
+    // Subsequence match, penalised by how spread out the matched characters are
+    let mut gaps = 0;
+    let mut last_match: Option<usize> = None;
+    let mut query_chars = query.chars().peekable();
+    for (i, c) in candidate.chars().enumerate() {
+        if query_chars.peek() == Some(&c) {
+            if let Some(last) = last_match {
+                gaps += i - last - 1;
+            }
+            last_match = Some(i);
+            query_chars.next();
+        }
+    }
+
+    if query_chars.peek().is_none() {
+        Some(1000 + gaps)
+    } else {
+        None
+    }
+}
+
 #[tauri::command]
-async fn connect_to_mcu(probe_index: usize) -> Result<SessionInfo, String> {
-    println!("Starting MCU connection with probe index: {}", probe_index);
+async fn list_supported_targets(query: Option<String>) -> Result<Vec<TargetInfo>, String> {
+    let query = query.unwrap_or_default();
+    let query = query.trim();
+
+    let mut matches = Vec::new();
+    for family in probe_rs::config::families() {
+        let manufacturer = family.manufacturer.and_then(|m| m.get()).map(|m| m.to_string());
+        for chip in &family.variants {
+            if let Some(score) = fuzzy_match_score(query, &chip.name) {
+                matches.push((score, TargetInfo {
+                    name: chip.name.clone(),
+                    family: family.name.clone(),
+                    manufacturer: manufacturer.clone(),
+                }));
+            }
+        }
+    }
+
+    matches.sort_by(|(a_score, a), (b_score, b)| a_score.cmp(b_score).then_with(|| a.name.cmp(&b.name)));
+    matches.truncate(MAX_TARGET_RESULTS);
+
+    eprintln!("Found {} targets matching '{}'", matches.len(), query);
+    Ok(matches.into_iter().map(|(_, target)| target).collect())
+}
+
+#[tauri::command]
+async fn connect_to_mcu(probe_index: usize, target: String) -> Result<SessionInfo, String> {
+    eprintln!("Starting MCU connection with probe index: {} (target: {})", probe_index, target);
+    
+    let auto_detect = target.trim().eq_ignore_ascii_case(AUTO_TARGET);
+    let target_selector = if auto_detect {
+        TargetSelector::Auto
+    } else {
+        // Validate the target name against the registry before touching the probe
+        probe_rs::config::get_target_by_name(&target)
+            .map_err(|e| format!("Unknown target '{}': {}", target, e))?;
+        TargetSelector::from(&target)
+    };
     
-    // Clean up any existing session first
+    // Clean up any existing session first; streams of the old session end with it.
+    // Discovered variables are kept: they may come from an ELF file read before connecting
+    sampler::stop();
+    rtt::detach();
+    swo::stop();
     {
         let mut session_guard = SESSION_MANAGER.lock().unwrap();
         if session_guard.is_some() {
-            println!("Cleaning up existing session");
+            eprintln!("Cleaning up existing session");
             *session_guard = None;
             // Give some time for cleanup
             std::thread::sleep(std::time::Duration::from_millis(500));
@@ -99,20 +269,20 @@ async fn connect_to_mcu(probe_index: usize) -> Result<SessionInfo, String> {
     
     let lister = Lister::new();
     let probes = lister.list_all();
-    println!("Found {} probes", probes.len());
+    eprintln!("Found {} probes", probes.len());
     
     if probe_index >= probes.len() {
-        println!("Invalid probe index: {} >= {}", probe_index, probes.len());
+        eprintln!("Invalid probe index: {} >= {}", probe_index, probes.len());
         return Err("Invalid probe index".to_string());
     }
     
-    println!("Opening probe: {}", probes[probe_index].identifier);
+    eprintln!("Opening probe: {}", probes[probe_index].identifier);
     
     // Add a longer delay to avoid probe access conflicts
     std::thread::sleep(std::time::Duration::from_millis(1000));
     
     let probe = probes[probe_index].open().map_err(|e| {
-        println!("Failed to open probe: {}", e);
+        eprintln!("Failed to open probe: {}", e);
         // If probe is busy, suggest waiting
         if e.to_string().contains("could not be created") {
             format!("Probe is busy - please wait a moment before trying again: {}", e)
@@ -121,321 +291,589 @@ async fn connect_to_mcu(probe_index: usize) -> Result<SessionInfo, String> {
         }
     })?;
     
-    println!("Attempting to attach to STM32H735ZGTx");
+    if auto_detect {
+        eprintln!("Attempting to auto-detect and attach to target");
+    } else {
+        eprintln!("Attempting to attach to {}", target);
+    }
     // Try to attach to the target
-    let mut session = probe.attach("STM32H735ZGTx", Permissions::default())
+    let mut session = probe.attach(target_selector, Permissions::default())
         .map_err(|e| {
-            println!("Failed to attach to target: {}", e);
+            eprintln!("Failed to attach to target: {}", e);
             format!("Failed to attach to target: {}", e)
         })?;
     
-    println!("Successfully attached to target");
+    eprintln!("Successfully attached to target");
     
     // Get target info before borrowing for core operations
     let target_name = session.target().name.clone();
-    let chip_id = Some(session.target().name.clone());
-    println!("Target name: {}", target_name);
+    eprintln!("Target name: {}{}", target_name, if auto_detect { " (auto-detected)" } else { "" });
+    
+    // Read the real chip identification registers (IDCODE, CPUID, ROM table)
+    let chip_id = Some(chip_id::identify_chip(&mut session));
     
     // Temporarily halt the core for initial setup, then resume for real-time access
     {
-        println!("Getting core 0");
+        eprintln!("Getting core 0");
         let mut core = session.core(0).map_err(|e| {
-            println!("Failed to get core: {}", e);
+            eprintln!("Failed to get core: {}", e);
             format!("Failed to get core: {}", e)
         })?;
         
-        println!("Halting core for setup");
+        eprintln!("Halting core for setup");
         core.halt(std::time::Duration::from_millis(100))
             .map_err(|e| {
-                println!("Failed to halt core: {}", e);
+                eprintln!("Failed to halt core: {}", e);
                 format!("Failed to halt core: {}", e)
             })?;
-        println!("Core halted successfully");
+        eprintln!("Core halted successfully");
         
         // Resume the core for real-time variable access
-        println!("Resuming core for real-time access");
+        eprintln!("Resuming core for real-time access");
         core.run().map_err(|e| {
-            println!("Failed to resume core: {}", e);
+            eprintln!("Failed to resume core: {}", e);
             format!("Failed to resume core: {}", e)
         })?;
-        println!("Core resumed successfully");
+        eprintln!("Core resumed successfully");
     } // core is dropped here, releasing the borrow
     
     // Store session globally for memory operations
     {
-        println!("Storing session globally");
+        eprintln!("Storing session globally");
         let mut session_guard = SESSION_MANAGER.lock().unwrap();
         *session_guard = Some(session);
     }
     
-    println!("MCU connection completed successfully");
+    eprintln!("MCU connection completed successfully");
     
     Ok(SessionInfo {
+        write_mode: write_mode::mode_for(&target_name),
         target_name,
         connected: true,
+        auto_detected: auto_detect,
         chip_id,
     })
 }
 
 
-fn parse_mculink_variable(session: &mut Session, var_addr: u32) -> Result<VariableInfo, String> {
-    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+// Sanity checks that catch random data which happens to look like a descriptor
+fn validate_variable(variable: &VariableInfo, memory_ranges: &[Range<u64>]) -> Result<(), String> {
+    if variable.name.is_empty() {
+        return Err("Empty variable name".to_string());
+    }
+    if !variable.name.chars().all(|c| c.is_ascii_alphanumeric() || "_.:[]".contains(c)) {
+        return Err(format!("Name '{}' is not a C identifier", variable.name));
+    }
+    if let (Some(min), Some(max)) = (variable.min_value, variable.max_value) {
+        if !min.is_finite() || !max.is_finite() {
+            return Err("min_value/max_value is not a finite number".to_string());
+        }
+        if min > max {
+            return Err(format!("min_value {} is greater than max_value {}", min, max));
+        }
+    }
+    if variable.element_count == 0 {
+        return Err("Element count is zero".to_string());
+    }
+    validate_scaling(variable)?;
+    let size = variable.element_count as u64 * var_type_size(&variable.var_type)? as u64;
+    if size > MAX_ARRAY_BYTES as u64 {
+        return Err(format!("Array of {} elements ({} bytes) exceeds the {} byte limit", variable.element_count, size, MAX_ARRAY_BYTES));
+    }
+    // The whole variable (every array element) must fit in one memory region
+    let extent = variable.address as u64..variable.address as u64 + size;
+    if !memory_ranges.is_empty() && !memory_ranges.iter().any(|r| r.start <= extent.start && extent.end <= r.end) {
+        return Err(format!("Address range 0x{:08X}..0x{:08X} is outside the target memory map", extent.start, extent.end));
+    }
+    Ok(())
+}
+
+fn parse_mculink_table(table: &[u8], base_addr: u32, descriptor: &McuLinkDescriptor, memory_ranges: &[Range<u64>], allow_unsealed: bool, report: &mut DiscoveryReport) -> Result<(), String> {
+    eprintln!("Parsing MCU Link v{} table: {} entries at offset {}, {} bytes total",
+        descriptor.version_string(), descriptor.entry_count, descriptor.entries_offset, descriptor.total_size);
+    report.descriptor_version = Some(descriptor.version_string());
     
-    // Read the simplified variable structure (74 bytes total)
-    let mut var_buf = [0u8; 74];
-    core.read(var_addr as u64, &mut var_buf)
-        .map_err(|e| format!("Failed to read variable at 0x{:08X}: {}", var_addr, e))?;
-    
-    // Parse the structure:
-    // uint32_t magic (already verified)
-    // uint32_t address  
-    // uint8_t var_type
-    // uint8_t access_flags
-    // uint8_t reserved[2]
-    // float min_value
-    // float max_value  
-    // char name[32]
-    // char category[32]
-    
-    let address = u32::from_le_bytes([var_buf[4], var_buf[5], var_buf[6], var_buf[7]]);
-    let var_type = var_buf[8];
-    let access_flags = var_buf[9];
-    let min_value = f32::from_le_bytes([var_buf[12], var_buf[13], var_buf[14], var_buf[15]]);
-    let max_value = f32::from_le_bytes([var_buf[16], var_buf[17], var_buf[18], var_buf[19]]);
-    
-    // Extract null-terminated strings
-    let name = extract_cstring(&var_buf[20..52])?; // name[32]
-    let category = extract_cstring(&var_buf[52..74])?; // category[32]
-    
-    let var_type_str = match var_type {
-        0 => "UINT8",
-        1 => "INT8", 
-        2 => "UINT16",
-        3 => "INT16",
-        4 => "UINT32", 
-        5 => "INT32",
-        6 => "FLOAT",
-        _ => return Err(format!("Unknown variable type: {}", var_type)),
-    };
+    // Version negotiation: a different major version means an incompatible layout,
+    // newer minor versions only append fields we can safely ignore
+    if descriptor.version >> 16 != MCULINK_VERSION_MAJOR {
+        return Err(format!("Unsupported MCU Link descriptor version {} (this build understands {}.x)",
+            descriptor.version_string(), MCULINK_VERSION_MAJOR));
+    }
+    if descriptor.version & 0xFFFF > MCULINK_VERSION_MINOR {
+        report.warn(format!("Descriptor version {} is newer than {}.{}; unknown fields are ignored",
+            descriptor.version_string(), MCULINK_VERSION_MAJOR, MCULINK_VERSION_MINOR));
+    }
+    if descriptor.entry_count == 0 {
+        return Err("MCU Link descriptor has no entries".to_string());
+    }
+    if (descriptor.total_size as usize) < DESCRIPTOR_SIZE || descriptor.total_size as usize > table.len() {
+        return Err(format!("Descriptor size of {} bytes does not fit the {} bytes read", descriptor.total_size, table.len()));
+    }
+    let table = &table[..descriptor.total_size as usize];
     
-    let access_str = match access_flags {
-        0 => "RO",
-        1 => "RW",
-        _ => return Err(format!("Unknown access flags: {}", access_flags)),
-    };
+    let table_start = descriptor.entries_offset as usize;
+    let table_end = table_start + descriptor.entry_count as usize * ENTRY_SIZE;
+    if table_end > table.len() {
+        return Err(format!("Entry table ({} entries at offset {}) exceeds descriptor size of {} bytes",
+            descriptor.entry_count, descriptor.entries_offset, table.len()));
+    }
     
-    Ok(VariableInfo {
-        name,
-        address,
-        var_type: var_type_str.to_string(),
-        access_flags: access_str.to_string(),
-        category: if category.is_empty() { None } else { Some(category) },
-        min_value: Some(min_value as f64),
-        max_value: Some(max_value as f64),
-    })
-}
-
-fn extract_cstring(bytes: &[u8]) -> Result<String, String> {
-    // Find the null terminator
-    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    // Integrity check; a mismatch means none of the entries can be trusted, and without
+    // a CRC nothing tells the table from stray bytes unless the user accepts that
+    let mut crc_error = None;
+    report.crc_status = match descriptor.crc32 {
+        None => {
+            let reason = format!("Descriptor version {} has no CRC", descriptor.version_string());
+            if allow_unsealed {
+                report.warn(format!("{}; entries are not integrity checked", reason));
+            } else {
+                crc_error = Some(format!("{}; allow unsealed descriptors to use it", reason));
+            }
+            CrcStatus::NotPresent
+        },
+        Some(0) => {
+            // Images converted to .bin/.hex before sealing carry the unsealed table too
+            let reason = "Descriptor CRC is not sealed; run `mcu-link --seal-mculink <elf>` after linking and flash the sealed ELF or images made from it";
+            if allow_unsealed {
+                report.warn(reason.to_string());
+            } else {
+                crc_error = Some(reason.to_string());
+            }
+            CrcStatus::Unsealed
+        },
+        Some(expected) => {
+            let computed = descriptor_crc(table);
+            if computed == expected {
+                CrcStatus::Valid
+            } else {
+                crc_error = Some(format!("Descriptor CRC mismatch (expected 0x{:08X}, computed 0x{:08X})", expected, computed));
+                CrcStatus::Mismatch
+            }
+        },
+    };
     
-    // Convert to string
-    String::from_utf8(bytes[..end].to_vec())
-        .map_err(|e| format!("Invalid UTF-8 string: {}", e))
-}
-
-fn read_null_terminated_string(core: &mut probe_rs::Core, addr: u32) -> Result<String, String> {
-    let mut string_buf = Vec::new();
-    let mut current_addr = addr;
+    let pool_string = |offset: u32| -> Result<String, String> {
+        match offset as usize {
+            0 => Ok(String::new()),
+            offset if offset < table.len() => extract_cstring(&table[offset..]),
+            offset => Err(format!("String offset {} outside descriptor", offset)),
+        }
+    };
     
-    loop {
-        let mut byte = [0u8; 1];
-        core.read(current_addr as u64, &mut byte)
-            .map_err(|e| format!("Failed to read string byte: {}", e))?;
+    for (index, entry_buf) in table[table_start..table_end].chunks_exact(ENTRY_SIZE).enumerate() {
+        let location = base_addr + (table_start + index * ENTRY_SIZE) as u32;
+        let entry = McuLinkEntry::from_bytes(entry_buf)?;
+        let name = pool_string(entry.name_offset).ok();
         
-        if byte[0] == 0 {
-            break;
+        if let Some(reason) = &crc_error {
+            report.reject(location, name, reason.clone());
+            continue;
         }
         
-        string_buf.push(byte[0]);
-        current_addr += 1;
+        let parsed = (|| -> Result<VariableInfo, String> {
+            let category = pool_string(entry.category_offset)?;
+            let name = pool_string(entry.name_offset)?;
+            Ok(VariableInfo {
+                parent: parent_path(&name),
+                name,
+                address: entry.address,
+                var_type: var_type_name(entry.var_type)?.to_string(),
+                access_flags: access_name(entry.access_flags)?.to_string(),
+                category: if category.is_empty() { None } else { Some(category) },
+                min_value: Some(entry.min_value as f64),
+                max_value: Some(entry.max_value as f64),
+                element_count: entry.element_count.max(1) as u32,
+                enum_labels: Vec::new(),
+                bit_fields: Vec::new(),
+                scale: None,
+                offset: None,
+                q_format: None,
+                unit: None,
+            })
+        })();
         
-        if string_buf.len() > 256 {
-            return Err("String too long (possible corruption)".to_string());
+        match parsed.and_then(|variable| validate_variable(&variable, memory_ranges).map(|_| variable)) {
+            Ok(variable) => {
+                eprintln!("Parsed table entry {} '{}' at 0x{:08X}", index, variable.name, variable.address);
+                report.variables.push(variable);
+            },
+            Err(reason) => report.reject(location, name, format!("Entry {}: {}", index, reason)),
         }
     }
     
-    String::from_utf8(string_buf)
-        .map_err(|e| format!("Invalid UTF-8 string: {}", e))
+    Ok(())
 }
 
+// Parse every legacy per-variable record found in an in-memory copy of the section.
+// Returns the number of records found and how many bytes were fully scanned.
+fn parse_legacy_records(section: &[u8], base_addr: u32, memory_ranges: &[Range<u64>], report: &mut DiscoveryReport) -> (usize, usize) {
+    let mut found = 0;
+    let mut offset = 0;
+    
+    while offset + LEGACY_RECORD_MIN_SIZE <= section.len() {
+        if read_u32_le(section, offset) == MCULINK_MAGIC {
+            let addr = base_addr + offset as u32;
+            
+            // A bare magic from the old MCULINK_INIT() is directly followed by the first record
+            if read_u32_le(section, offset + 4) == MCULINK_MAGIC {
+                found += 1;
+                offset += 4;
+                continue;
+            }
+            
+            // Each record carries its own size, so read exactly what the firmware laid out
+            let name = legacy_record_name(&section[offset..]);
+            let size = match legacy_record_size(&section[offset..]) {
+                Ok(size) => size,
+                Err(reason) => {
+                    report.reject(addr, name, reason);
+                    offset += 4;
+                    continue;
+                }
+            };
+            if offset + size > section.len() {
+                // Record straddles the end of the buffer
+                break;
+            }
+            
+            found += 1;
+            match decode_legacy_record(&section[offset..offset + size]).and_then(|variable| validate_variable(&variable, memory_ranges).map(|_| variable)) {
+                Ok(variable) => {
+                    eprintln!("Successfully parsed MCU Link variable '{}' at 0x{:08X} ({} byte record)", variable.name, addr, size);
+                    if !report.warnings.iter().any(|warning| warning == UNCHECKED_RECORDS) {
+                        report.warn(UNCHECKED_RECORDS.to_string());
+                    }
+                    report.variables.push(variable);
+                    offset += legacy_record_stride(size);
+                    continue;
+                },
+                Err(reason) => report.reject(addr, name, reason),
+            }
+        }
+        offset += 4;
+    }
+    
+    (found, offset)
+}
+
+// Error out only if nothing at all was found; rejected entries are part of a valid report
+fn finish_report(report: DiscoveryReport, base_addr: u32) -> Result<DiscoveryReport, String> {
+    if report.variables.is_empty() && report.rejected.is_empty() {
+        Err(format!("No MCU Link variables found at address 0x{:08X}", base_addr))
+    } else {
+        eprintln!("Found {} MCU Link variables at 0x{:08X} ({} rejected, CRC {:?})",
+            report.variables.len(), base_addr, report.rejected.len(), report.crc_status);
+        Ok(report)
+    }
+}
+
+/// Discover variables at the default address. Unless `allow_unsealed`, only tables
+/// with a valid CRC are used.
 #[tauri::command]
-async fn discover_variables() -> Result<Vec<VariableInfo>, String> {
-    println!("=== discover_variables called ===");
+async fn discover_variables(allow_unsealed: Option<bool>) -> Result<DiscoveryReport, String> {
+    eprintln!("=== discover_variables called ===");
     
     // Check if we have an active session
     {
         let session_guard = SESSION_MANAGER.lock().unwrap();
         if session_guard.is_none() {
-            println!("No active MCU session for discovery");
+            eprintln!("No active MCU session for discovery");
             return Err("No active MCU session - please connect first".to_string());
         }
-        println!("Session exists, proceeding with discovery");
+        eprintln!("Session exists, proceeding with discovery");
     }
     
     // Use the default address for discovery
-    println!("Using default MCU Link address 0x{:08X}", DEFAULT_MCULINK_ADDRESS);
-    scan_mculink_at_address(DEFAULT_MCULINK_ADDRESS)
+    eprintln!("Using default MCU Link address 0x{:08X}", DEFAULT_MCULINK_ADDRESS);
+    scan_mculink_at_address(DEFAULT_MCULINK_ADDRESS, None, allow_unsealed.unwrap_or(false))
+        .inspect(|report| write_guard::register(&report.variables))
 }
 
+/// Discover variables at `address`. Unless `allow_unsealed`, only tables with a valid
+/// CRC are used.
 #[tauri::command]
-async fn discover_variables_at_address(address: u32) -> Result<Vec<VariableInfo>, String> {
-    println!("=== discover_variables_at_address called with 0x{:08X} ===", address);
+async fn discover_variables_at_address(address: u32, size: Option<u32>, allow_unsealed: Option<bool>) -> Result<DiscoveryReport, String> {
+    eprintln!("=== discover_variables_at_address called with 0x{:08X} (size: {:?}) ===", address, size);
     
     // Check if we have an active session
     {
         let session_guard = SESSION_MANAGER.lock().unwrap();
         if session_guard.is_none() {
-            println!("No active MCU session for discovery");
+            eprintln!("No active MCU session for discovery");
             return Err("No active MCU session - please connect first".to_string());
         }
-        println!("Session exists, proceeding with discovery at fixed address");
+        eprintln!("Session exists, proceeding with discovery at fixed address");
     }
     
-    match scan_mculink_at_address(address) {
-        Ok(variables) => {
-            println!("Found {} variables at address 0x{:08X}", variables.len(), address);
-            Ok(variables)
+    // An explicit size (e.g. from the linker map) overrides the size found in the header
+    match scan_mculink_at_address(address, size, allow_unsealed.unwrap_or(false)) {
+        Ok(report) => {
+            eprintln!("Found {} variables at address 0x{:08X}", report.variables.len(), address);
+            write_guard::register(&report.variables);
+            Ok(report)
         },
         Err(e) => {
-            println!("Failed to read variables at 0x{:08X}: {}", address, e);
+            eprintln!("Failed to read variables at 0x{:08X}: {}", address, e);
             Err(e)
         }
     }
 }
 
-fn scan_mculink_at_address(start_addr: u32) -> Result<Vec<VariableInfo>, String> {
-    println!("Scanning for MCU Link variables starting at 0x{:08X}", start_addr);
+#[tauri::command]
+async fn discover_variables_from_elf(path: String) -> Result<Vec<VariableInfo>, String> {
+    eprintln!("=== discover_variables_from_elf called with {} ===", path);
+    
+    let data = std::fs::read(&path)
+        .map_err(|e| format!("Failed to read ELF file {}: {}", path, e))?;
+    let variables = elf::discover_variables(&data)?;
+    
+    if variables.is_empty() {
+        Err(format!("No variables found in {}", path))
+    } else {
+        eprintln!("Found {} variables in {}", variables.len(), path);
+        write_guard::register(&variables);
+        Ok(variables)
+    }
+}
+
+#[tauri::command]
+async fn discover_variables_from_elf_section(path: String, allow_unsealed: Option<bool>) -> Result<DiscoveryReport, String> {
+    eprintln!("=== discover_variables_from_elf_section called with {} ===", path);
+    mculink_variables_from_elf(&path, allow_unsealed.unwrap_or(false))
+        .inspect(|report| write_guard::register(&report.variables))
+}
+
+fn read_section(session: &mut Session, addr: u32, len: usize) -> Result<Vec<u8>, String> {
+    if len > MAX_SECTION_SIZE {
+        return Err(format!("MCU Link section size of {} bytes exceeds limit (corrupted header?)", len));
+    }
+    
+    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+    let mut buf = vec![0u8; len];
+    core.read(addr as u64, &mut buf)
+        .map_err(|e| format!("Failed to read {} bytes at 0x{:08X}: {}", len, addr, e))?;
+    Ok(buf)
+}
+
+// Parse a complete in-memory copy of a .mculink section, whichever format it uses
+fn parse_mculink_section(section: &[u8], base_addr: u32, memory_ranges: &[Range<u64>], allow_unsealed: bool) -> Result<DiscoveryReport, String> {
+    let report = match SectionHeader::from_bytes(section)? {
+        SectionHeader::Table(descriptor) => {
+            let mut report = DiscoveryReport::new("v2 table");
+            match parse_mculink_table(section, base_addr, &descriptor, memory_ranges, allow_unsealed, &mut report) {
+                Ok(()) => report,
+                Err(e) => {
+                    // A legacy record can still pass for a table header; use its records if
+                    // they parse and the table does not
+                    let mut legacy = DiscoveryReport::new("v1 records");
+                    parse_legacy_records(section, base_addr, memory_ranges, &mut legacy);
+                    if legacy.variables.is_empty() {
+                        return Err(e);
+                    }
+                    legacy.warn(format!("Section is not a valid v2 table ({}); parsed as v1 records", e));
+                    legacy
+                },
+            }
+        },
+        SectionHeader::LegacyHeader(header) => {
+            let mut report = DiscoveryReport::new("v1 records");
+            report.descriptor_version = Some(header.version_string());
+            parse_legacy_records(&section[LEGACY_HEADER_SIZE..], base_addr + LEGACY_HEADER_SIZE as u32, memory_ranges, &mut report);
+            report
+        },
+        SectionHeader::Legacy => {
+            let mut report = DiscoveryReport::new("v1 records");
+            parse_legacy_records(section, base_addr, memory_ranges, &mut report);
+            report
+        },
+    };
+    
+    finish_report(report, base_addr)
+}
+
+fn scan_mculink_at_address(start_addr: u32, section_size: Option<u32>, allow_unsealed: bool) -> Result<DiscoveryReport, String> {
+    eprintln!("Scanning for MCU Link variables starting at 0x{:08X}", start_addr);
     
     let mut session_guard = SESSION_MANAGER.lock().unwrap();
     let session = session_guard.as_mut().ok_or("No active MCU session")?;
     
-    let mut variables = Vec::new();
-    let max_variables = 20; // Reasonable limit
-    let max_scan_bytes = 4096; // Only scan 4KB from the start address
+    // Variable addresses must fall inside the target's memory map
+    let memory_ranges: Vec<Range<u64>> = session.target().memory_map.iter()
+        .map(|region| region.address_range())
+        .collect();
     
-    for offset in (0..max_scan_bytes).step_by(4) {
-        if variables.len() >= max_variables {
-            break;
+    let header = SectionHeader::from_bytes(&read_section(session, start_addr, DESCRIPTOR_SIZE)?)?;
+    
+    // An explicit size overrides whatever the header says
+    let section_size = section_size.or(match &header {
+        SectionHeader::Table(descriptor) => Some(descriptor.total_size),
+        SectionHeader::LegacyHeader(init) if init.section_end > start_addr => Some(init.section_end - start_addr),
+        _ => None,
+    });
+    
+    if let Some(size) = section_size {
+        // Section bounds known: a single bulk read covers everything
+        eprintln!("MCU Link section spans 0x{:08X}..0x{:08X} ({})", start_addr, start_addr + size, header.description());
+        let section = read_section(session, start_addr, size as usize)?;
+        return parse_mculink_section(&section, start_addr, &memory_ranges, allow_unsealed);
+    }
+    
+    // Unknown size: read in chunks until a chunk contains no more records
+    eprintln!("Legacy section size unknown, scanning in {} byte chunks", LEGACY_SCAN_CHUNK);
+    let mut report = DiscoveryReport::new("v1 records");
+    let scan_start = match &header {
+        SectionHeader::LegacyHeader(init) => {
+            report.descriptor_version = Some(init.version_string());
+            start_addr + LEGACY_HEADER_SIZE as u32
+        },
+        _ => start_addr,
+    };
+    let mut section = Vec::new();
+    let mut parsed_until = 0;
+    loop {
+        let chunk_addr = scan_start + section.len() as u32;
+        match read_section(session, chunk_addr, LEGACY_SCAN_CHUNK) {
+            Ok(chunk) => section.extend_from_slice(&chunk),
+            Err(e) => {
+                eprintln!("Stopping scan at 0x{:08X}: {}", chunk_addr, e);
+                break;
+            }
         }
         
-        let addr = start_addr + offset;
+        // Records straddling the end of the buffer are picked up after the next chunk
+        let (found, scanned) = parse_legacy_records(&section[parsed_until..], scan_start + parsed_until as u32, &memory_ranges, &mut report);
+        parsed_until += scanned;
+        if found == 0 || section.len() >= MAX_SECTION_SIZE {
+            break;
+        }
+    }
+    
+    finish_report(report, start_addr)
+}
+
+/// Parse the `.mculink` section of a firmware ELF file on disk, without a probe.
+/// Unless `allow_unsealed`, only tables with a valid CRC are used.
+pub fn mculink_variables_from_elf(path: &str, allow_unsealed: bool) -> Result<DiscoveryReport, String> {
+    let data = std::fs::read(path)
+        .map_err(|e| format!("Failed to read ELF file {}: {}", path, e))?;
+    let (address, section) = elf::read_mculink_section(&data)?;
+    eprintln!("Loaded {} byte .mculink section (address 0x{:08X}) from {}", section.len(), address, path);
+    parse_mculink_section(&section, address, &[], allow_unsealed)
+}
+
+/// Write the discovery report for a firmware ELF as JSON, for use from CI.
+/// The JSON goes to `output` if given, otherwise to stdout; the discovery log goes to
+/// stderr, so stdout holds nothing but the JSON.
+/// Returns the process exit code: 0 on success, 1 on errors, 2 if any entry was rejected.
+pub fn list_mculink_cli(path: &str, output: Option<&str>, allow_unsealed: bool) -> i32 {
+    let report = match mculink_variables_from_elf(path, allow_unsealed) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    
+    let json = match serde_json::to_string_pretty(&report) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("Failed to serialize discovery report: {}", e);
+            return 1;
+        }
+    };
+    match output {
+        Some(output) => {
+            if let Err(e) = std::fs::write(output, json) {
+                eprintln!("Failed to write {}: {}", output, e);
+                return 1;
+            }
+        },
+        None => println!("{}", json),
+    }
+    
+    if report.rejected.is_empty() { 0 } else { 2 }
+}
+
+/// Fill in the descriptor CRC of a v2.1+ table in a linked firmware ELF, in place.
+/// Returns the process exit code.
+pub fn seal_mculink_cli(path: &str) -> i32 {
+    let result = (|| -> Result<u32, String> {
+        let mut data = std::fs::read(path)
+            .map_err(|e| format!("Failed to read ELF file {}: {}", path, e))?;
+        let (file_offset, size) = elf::mculink_section_file_range(&data)?;
+        let table = &mut data[file_offset..file_offset + size];
         
-        // Try to read potential magic number
-        let mut magic_buf = [0u8; 4];
-        let read_success = {
-            let mut core = session.core(0).unwrap();
-            core.read(addr as u64, &mut magic_buf).is_ok()
+        let descriptor = match SectionHeader::from_bytes(table)? {
+            SectionHeader::Table(descriptor) if descriptor.crc32.is_some() => descriptor,
+            header => return Err(format!("The .mculink section is not a v{}.{}+ table (found {})",
+                MCULINK_VERSION_MAJOR, MCULINK_VERSION_MINOR_CRC, header.description())),
         };
-        
-        if read_success {
-            let magic = u32::from_le_bytes(magic_buf);
-            
-            if magic == MCULINK_MAGIC {
-                println!("Found MCULINK_MAGIC at address 0x{:08X}", addr);
-                
-                // Try to parse variable entry at this address
-                match parse_mculink_variable(session, addr) {
-                    Ok(variable) => {
-                        println!("Successfully parsed MCU Link variable '{}' at 0x{:08X}", variable.name, addr);
-                        variables.push(variable);
-                    },
-                    Err(e) => {
-                        // Skip entries that aren't valid variables (like MCULINK_INIT magic)
-                        if e.contains("Unknown variable type") {
-                            println!("Skipping non-variable entry at 0x{:08X}: {}", addr, e);
-                        } else {
-                            println!("Failed to parse variable at 0x{:08X}: {}", addr, e);
-                        }
-                    }
-                }
-            }
+        if descriptor.total_size as usize > table.len() {
+            return Err(format!("Descriptor size of {} bytes exceeds the {} byte section", descriptor.total_size, table.len()));
         }
-    }
+        
+        let crc = descriptor_crc(&table[..descriptor.total_size as usize]);
+        table[DESCRIPTOR_CRC_OFFSET..DESCRIPTOR_CRC_OFFSET + 4].copy_from_slice(&crc.to_le_bytes());
+        std::fs::write(path, &data)
+            .map_err(|e| format!("Failed to write ELF file {}: {}", path, e))?;
+        Ok(crc)
+    })();
     
-    if variables.is_empty() {
-        Err(format!("No MCU Link variables found at address 0x{:08X}", start_addr))
-    } else {
-        println!("Found {} MCU Link variables at 0x{:08X}", variables.len(), start_addr);
-        Ok(variables)
+    match result {
+        Ok(crc) => {
+            println!("Sealed .mculink descriptor in {} (CRC32 0x{:08X})", path, crc);
+            0
+        },
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
     }
 }
 
-fn read_mcu_variable(address: u32, var_type: &str) -> Result<f64, String> {
+// Read a variable's bytes along with the host monotonic time of the probe transaction
+fn read_mcu_bytes_timed(address: u32, var_type: &str) -> Result<(u64, Vec<u8>), String> {
+    let size = var_type_size(var_type)?;
+    
     let mut session_guard = SESSION_MANAGER.lock().unwrap();
     let session = session_guard.as_mut().ok_or("No active MCU session")?;
     let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
     
-    match var_type {
-        "UINT8" => {
-            let mut buf = [0u8; 1];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read UINT8: {}", e))?;
-            Ok(buf[0] as f64)
-        },
-        "INT8" => {
-            let mut buf = [0u8; 1];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read INT8: {}", e))?;
-            Ok(buf[0] as i8 as f64)
-        },
-        "UINT16" => {
-            let mut buf = [0u8; 2];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read UINT16: {}", e))?;
-            Ok(u16::from_le_bytes(buf) as f64)
-        },
-        "INT16" => {
-            let mut buf = [0u8; 2];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read INT16: {}", e))?;
-            Ok(i16::from_le_bytes(buf) as f64)
-        },
-        "UINT32" => {
-            let mut buf = [0u8; 4];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read UINT32: {}", e))?;
-            Ok(u32::from_le_bytes(buf) as f64)
-        },
-        "INT32" => {
-            let mut buf = [0u8; 4];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read INT32: {}", e))?;
-            Ok(i32::from_le_bytes(buf) as f64)
-        },
-        "FLOAT" => {
-            let mut buf = [0u8; 4];
-            core.read(address as u64, &mut buf)
-                .map_err(|e| format!("Failed to read FLOAT: {}", e))?;
-            Ok(f32::from_le_bytes(buf) as f64)
-        },
-        _ => Err(format!("Unsupported variable type: {}", var_type))
-    }
+    let mut buf = vec![0u8; size];
+    let (timestamp_us, result) = timing::timed(|| core.read(address as u64, &mut buf));
+    result.map_err(|e| format!("Failed to read {}: {}", var_type, e))?;
+    Ok((timestamp_us, buf))
+}
+
+fn read_mcu_bytes(address: u32, var_type: &str) -> Result<Vec<u8>, String> {
+    read_mcu_bytes_timed(address, var_type).map(|(_, buf)| buf)
 }
 
+fn read_mcu_value(address: u32, var_type: &str) -> Result<RawValue, String> {
+    decode_value(var_type, &read_mcu_bytes(address, var_type)?)
+}
+
+/// Read a variable in engineering units, stamped with the host monotonic time of the read.
+/// Without an MCU session, simulated values are returned for demos.
 #[tauri::command]
-async fn read_variable(address: u32, var_type: String) -> Result<f64, String> {
-    // Try real MCU read first
-    match read_mcu_variable(address, &var_type) {
-        Ok(value) => Ok(value),
+async fn read_variable(address: u32, var_type: String, scaling: Option<Scaling>) -> Result<TimedValue, String> {
+    // Try real MCU read first, converting raw counts to engineering units
+    let read = read_mcu_bytes_timed(address, &var_type)
+        .and_then(|(timestamp_us, bytes)| Ok((timestamp_us, decode_value(&var_type, &bytes)?.as_f64())));
+    match read {
+        Ok((timestamp_us, value)) => Ok(TimedValue { value: scaling.unwrap_or_default().to_engineering(value), timestamp_us }),
+        // A failed read of a connected target must not pass for a sample
+        Err(e) if SESSION_MANAGER.lock().unwrap().is_some() => Err(e),
         Err(_) => {
+            let timestamp_us = timing::monotonic_us();
             // Fallback to mock simulation if no MCU session
             match var_type.as_str() {
-                "UINT8" => {
+                "UINT8" | "BOOL" => {
                     // Simulate button state (0 or 1)
-                    Ok(if std::ptr::addr_of!(address) as usize % 2 == 0 { 0.0 } else { 1.0 })
+                    let value = if (std::ptr::addr_of!(address) as usize).is_multiple_of(2) { 0.0 } else { 1.0 };
+                    Ok(TimedValue { value, timestamp_us })
                 },
-                "FLOAT" => {
+                "FLOAT" | "DOUBLE" => {
                     // Simulate dynamic temperature reading
                     let now = std::time::SystemTime::now()
                         .duration_since(std::time::UNIX_EPOCH)
@@ -447,128 +885,502 @@ async fn read_variable(address: u32, var_type: String) -> Result<f64, String> {
                     let fast_noise = (now * 5.0).sin() * 0.5;
                     let random_noise = (now * 17.3).sin() * 0.2;
                     
-                    Ok(base_temp + slow_drift + fast_noise + random_noise)
+                    Ok(TimedValue { value: base_temp + slow_drift + fast_noise + random_noise, timestamp_us })
                 },
-                _ => Ok(0.0) // Default fallback
+                _ => Ok(TimedValue { value: 0.0, timestamp_us }) // Default fallback
             }
         }
     }
 }
 
-fn write_mcu_variable(address: u32, var_type: &str, value: f64) -> Result<(), String> {
-    println!("write_mcu_variable: attempting to write {} to 0x{:08X} (type: {})", value, address, var_type);
-    
+/// Read a variable as exact decimal text. Unlike `read_variable`, 64-bit integers
+/// beyond 2^53 are not rounded to the nearest f64.
+#[tauri::command]
+async fn read_variable_exact(address: u32, var_type: String) -> Result<String, String> {
+    read_mcu_value(address, &var_type).map(|value| value.to_string())
+}
+
+// Values of `count` elements of `variable` from `address`, in engineering units, for
+// the rate limit of write_guard::guard
+fn read_current(variable: &VariableInfo, address: u32, count: usize) -> Result<Vec<f64>, String> {
+    let size = var_type_size(&variable.var_type)?;
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let session = session_guard.as_mut().ok_or("No active MCU session")?;
+    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+    let mut buf = vec![0u8; size * count];
+    core.read(address as u64, &mut buf)
+        .map_err(|e| format!("Failed to read current value of {}: {}", variable.name, e))?;
+    let scaling = Scaling::of(variable);
+    buf.chunks_exact(size)
+        .map(|element| decode_value(&variable.var_type, element).map(|raw| scaling.to_engineering(raw.as_f64())))
+        .collect()
+}
+
+// Write with the target's write mode. A failed background write is retried with the
+// core halted; the report says which method succeeded.
+fn write_mcu_variable(address: u32, var_type: &str, bytes: &[u8]) -> Result<WriteReport, String> {
+    eprintln!("write_mcu_variable: attempting to write {:?} to 0x{:08X} (type: {})", bytes, address, var_type);
+    let mut fallback_reason = None;
+    if current_write_mode()? == WriteMode::Background {
+        match write_mcu_background(address, var_type, bytes) {
+            Ok(()) => return Ok(WriteReport { method: WriteMode::Background, fallback_reason, clamped_from: None, clamped_to: None, verification: None }),
+            Err(e) => {
+                eprintln!("Background write failed, retrying with the core halted: {}", e);
+                fallback_reason = Some(e);
+            },
+        }
+    }
+    modify_mcu_variable(address, var_type, bytes.len(), |_| Ok(bytes.to_vec()))?;
+    Ok(WriteReport { method: WriteMode::Halt, fallback_reason, clamped_from: None, clamped_to: None, verification: None })
+}
+
+// Write mode of the connected target
+fn current_write_mode() -> Result<WriteMode, String> {
+    let session_guard = SESSION_MANAGER.lock().unwrap();
+    let session = session_guard.as_ref().ok_or("No active MCU session")?;
+    Ok(write_mode::mode_for(&session.target().name))
+}
+
+// Write through the memory access port without halting the core
+fn write_mcu_background(address: u32, var_type: &str, bytes: &[u8]) -> Result<(), String> {
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let session = session_guard.as_mut().ok_or("No active MCU session")?;
+    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+    core.write(address as u64, bytes)
+        .map_err(|e| format!("Failed to write {} in the background: {}", var_type, e))?;
+    eprintln!("{} background write completed successfully", var_type);
+    Ok(())
+}
+
+// Read-modify-write of `len` bytes while the core is halted, so firmware updates
+// to the other bits of the variable can't be lost in between. Bit field writes always
+// take this path, whatever the write mode.
+fn modify_mcu_variable(address: u32, var_type: &str, len: usize, modify: impl FnOnce(&[u8]) -> Result<Vec<u8>, String>) -> Result<(), String> {
     let mut session_guard = SESSION_MANAGER.lock().unwrap();
     let session = session_guard.as_mut().ok_or("No active MCU session")?;
     
     // Check core state before write
     let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
     let core_status = core.status().map_err(|e| format!("Failed to get core status: {}", e))?;
-    println!("Core status before write: {:?}", core_status);
+    eprintln!("Core status before write: {:?}", core_status);
     
     // For reliable writes with ST-Link, halt the core temporarily
     let was_running = !core_status.is_halted();
     if was_running {
-        println!("Core is running, halting for reliable write");
+        eprintln!("Core is running, halting for reliable write");
         core.halt(std::time::Duration::from_millis(10))
             .map_err(|e| format!("Failed to halt core for write: {}", e))?;
     } else {
-        println!("Core already halted, proceeding with write");
+        eprintln!("Core already halted, proceeding with write");
     }
     
-    match var_type {
-        "UINT8" => {
-            let val = (value as u8).to_le_bytes();
-            println!("Writing UINT8 value {} (bytes: {:?}) to address 0x{:08X}", value as u8, val, address);
-            
-            // Try to read the current value first
-            let mut read_buf = [0u8; 1];
-            match core.read(address as u64, &mut read_buf) {
-                Ok(_) => println!("Current value at 0x{:08X}: {}", address, read_buf[0]),
-                Err(e) => println!("Failed to read current value: {}", e),
-            }
-            
-            // Attempt the write
-            core.write(address as u64, &val)
-                .map_err(|e| {
-                    println!("UINT8 write failed with error: {}", e);
-                    format!("Failed to write UINT8: {}", e)
-                })?;
-            println!("UINT8 write completed successfully");
-        },
-        "INT8" => {
-            let val = (value as i8).to_le_bytes();
-            core.write(address as u64, &val)
-                .map_err(|e| format!("Failed to write INT8: {}", e))?;
-        },
-        "UINT16" => {
-            let val = (value as u16).to_le_bytes();
-            core.write(address as u64, &val)
-                .map_err(|e| format!("Failed to write UINT16: {}", e))?;
-        },
-        "INT16" => {
-            let val = (value as i16).to_le_bytes();
-            core.write(address as u64, &val)
-                .map_err(|e| format!("Failed to write INT16: {}", e))?;
-        },
-        "UINT32" => {
-            let val = (value as u32).to_le_bytes();
-            core.write(address as u64, &val)
-                .map_err(|e| format!("Failed to write UINT32: {}", e))?;
-        },
-        "INT32" => {
-            let val = (value as i32).to_le_bytes();
-            core.write(address as u64, &val)
-                .map_err(|e| format!("Failed to write INT32: {}", e))?;
-        },
-        "FLOAT" => {
-            let val = (value as f32).to_le_bytes();
-            core.write(address as u64, &val)
-                .map_err(|e| format!("Failed to write FLOAT: {}", e))?;
-        },
-        _ => return Err(format!("Unsupported variable type: {}", var_type))
-    }
+    // Read, modify and write while halted; the core is resumed even if one of them fails
+    let result = (|| {
+        let mut current = vec![0u8; len];
+        core.read(address as u64, &mut current)
+            .map_err(|e| format!("Failed to read current value: {}", e))?;
+        eprintln!("Current value at 0x{:08X}: {:?}", address, current);
+        
+        let bytes = modify(&current)?;
+        core.write(address as u64, &bytes)
+            .map_err(|e| {
+                eprintln!("{} write failed with error: {}", var_type, e);
+                format!("Failed to write {}: {}", var_type, e)
+            })?;
+        eprintln!("{} write completed successfully", var_type);
+        Ok(())
+    })();
     
     // Resume the core if it was running before
     if was_running {
-        println!("Resuming core after write");
+        eprintln!("Resuming core after write");
         core.run().map_err(|e| format!("Failed to resume core after write: {}", e))?;
-        println!("Core resumed successfully");
+        eprintln!("Core resumed successfully");
     }
     
-    Ok(())
+    result
 }
 
+/// Write a variable. `exact` carries the value as decimal text, for 64-bit integers
+/// that an f64 `value` cannot represent; it takes precedence when present. Returns
+/// whether the write ran in the background or with the core halted. With `verify`,
+/// the variable is read back shortly after the write and compared with the value written.
+/// Only discovered variables are written, with their scaling, and checked against
+/// their access flags, range and rate limit; see write_guard.
 #[tauri::command]
-async fn write_variable(address: u32, var_type: String, value: f64) -> Result<(), String> {
+async fn write_variable(address: u32, var_type: String, value: f64, exact: Option<String>, scaling: Option<Scaling>, verify: Option<bool>) -> Result<WriteReport, String> {
+    let scaling = write_guard::scaling_at(address, scaling)?;
+    // Limits are in engineering units; exact text is a raw value
+    let requested = match &exact {
+        Some(text) => scaling.to_engineering(decode_value(&var_type, &encode_value_exact(&var_type, text)?)?.as_f64()),
+        None => value,
+    };
+    let guarded = write_guard::guard(address, &var_type, var_type_size(&var_type)?, &[requested], |variable| {
+        read_current(variable, address, 1)
+    })?;
+    let value = guarded.values[0];
+    let exact = exact.filter(|_| guarded.clamped_from.is_none());
+
+    // Exact text is always a raw value; f64 values are in engineering units
+    let bytes = match &exact {
+        Some(text) => encode_value_exact(&var_type, text)?,
+        None => scaling.encode(&var_type, value)?,
+    };
+    
     // Try real MCU write first
-    match write_mcu_variable(address, &var_type, value) {
-        Ok(_) => {
-            println!("Successfully wrote {} to MCU address 0x{:08X} (type: {})", value, address, var_type);
-            Ok(())
+    match write_mcu_variable(address, &var_type, &bytes) {
+        Ok(mut report) => {
+            eprintln!("Successfully wrote {} to MCU address 0x{:08X} (type: {}, {:?})", exact.as_deref().unwrap_or(&value.to_string()), address, var_type, report.method);
+            guarded.record();
+            if guarded.clamped_from.is_some() {
+                report.clamped_from = guarded.clamped_from;
+                report.clamped_to = Some(value);
+            }
+            if verify.unwrap_or(false) {
+                std::thread::sleep(VERIFY_DELAY);
+                let readback = read_mcu_bytes(address, &var_type)
+                    .map_err(|e| format!("Wrote 0x{:08X} but could not read it back: {}", address, e))?;
+                let (scaling, requested) = match exact {
+                    Some(_) => (Scaling::default(), None),
+                    None => (scaling, Some(guarded.clamped_from.unwrap_or(value))),
+                };
+                let verification = write_mode::verify(&var_type, scaling, requested, &bytes, &readback)?;
+                if verification.overwritten {
+                    eprintln!("Readback of 0x{:08X} is {} instead of {}; the firmware changed it", address, verification.readback, verification.written);
+                }
+                report.verification = Some(verification);
+            }
+            Ok(report)
         },
         Err(e) => {
             // ST-Link doesn't support RAM writes on STM32H735 - this is expected
-            println!("ST-Link write limitation (expected): {}", e);
-            println!("Note: ST-Link + STM32H735 doesn't support debug writes to RAM");
-            println!("For real MCU control, consider using RTT or different probe");
+            eprintln!("ST-Link write limitation (expected): {}", e);
+            eprintln!("Note: ST-Link + STM32H735 doesn't support debug writes to RAM");
+            eprintln!("For real MCU control, consider using RTT or different probe");
             Err(format!("ST-Link write not supported on this target: {}", e))
         }
     }
 }
 
+/// Choose how variables are written to `target` (default: the connected target) for
+/// the rest of this run of the app.
+#[tauri::command]
+async fn set_write_mode(mode: WriteMode, target: Option<String>) -> Result<(), String> {
+    let target = match target {
+        Some(target) => target,
+        None => SESSION_MANAGER.lock().unwrap().as_ref()
+            .map(|session| session.target().name.clone())
+            .ok_or("No active MCU session")?,
+    };
+    write_mode::set_mode(&target, mode);
+    Ok(())
+}
+
+/// Choose whether writes beyond a variable's range or rate limit are rejected (the
+/// default) or clamped.
+#[tauri::command]
+async fn set_limit_policy(policy: LimitPolicy) -> Result<(), String> {
+    write_guard::set_policy(policy);
+    Ok(())
+}
+
+/// Limit how fast the variable `name` may be changed by writes, in engineering units
+/// per second; no `max_change_per_s` removes the limit. Returns every rate limit.
+#[tauri::command]
+async fn set_rate_limit(name: String, max_change_per_s: Option<f64>) -> Result<std::collections::BTreeMap<String, f64>, String> {
+    write_guard::set_rate_limit(&name, max_change_per_s)
+}
+
+/// Read a variable and decode its enum label and bit fields.
+#[tauri::command]
+async fn read_variable_decoded(variable: VariableInfo) -> Result<DecodedValue, String> {
+    let bytes = read_mcu_bytes(variable.address, &variable.var_type)?;
+    decoding::decode(&variable, &bytes)
+}
+
+/// Write an enum variable by label. The labels of a discovered variable are used
+/// instead of the client's, and the write is guarded like write_variable.
+#[tauri::command]
+async fn write_variable_label(variable: VariableInfo, label: String) -> Result<(), String> {
+    let variable = write_guard::discovered(variable);
+    let value = decoding::label_value(&variable, &label)?;
+    let scaling = Scaling::of(&variable);
+    let guarded = write_guard::guard(variable.address, &variable.var_type, var_type_size(&variable.var_type)?, &[scaling.to_engineering(value as f64)], |registered| {
+        read_current(registered, variable.address, 1)
+    })?;
+    let bytes = match guarded.clamped_from {
+        Some(_) => scaling.encode(&variable.var_type, guarded.values[0])?,
+        None => encode_value_exact(&variable.var_type, &value.to_string())?,
+    };
+    eprintln!("Writing label {} ({}) to {}", label, value, variable.name);
+    write_mcu_variable(variable.address, &variable.var_type, &bytes)?;
+    guarded.record();
+    Ok(())
+}
+
+/// Write one bit field of a variable, leaving the other bits unchanged. The bit fields
+/// of a discovered variable are used instead of the client's, and the variable's new
+/// value is guarded like write_variable.
+#[tauri::command]
+async fn write_variable_bits(variable: VariableInfo, field: String, value: u64) -> Result<(), String> {
+    let variable = write_guard::discovered(variable);
+    let size = var_type_size(&variable.var_type)?;
+    let scaling = Scaling::of(&variable);
+    let engineering = |bytes: &[u8]| decode_value(&variable.var_type, bytes).map(|raw| scaling.to_engineering(raw.as_f64()));
+    eprintln!("Setting bit field {}.{} to {}", variable.name, field, value);
+    let mut guarded = None;
+    modify_mcu_variable(variable.address, &variable.var_type, size, |current| {
+        // Checked against the value read while halted, which the new one is based on
+        let bytes = decoding::set_bit_field(&variable, current, &field, value)?;
+        let checked = write_guard::guard(variable.address, &variable.var_type, size, &[engineering(&bytes)?], |_| Ok(vec![engineering(current)?]))?;
+        let bytes = match checked.clamped_from {
+            Some(_) => scaling.encode(&variable.var_type, checked.values[0])?,
+            None => bytes,
+        };
+        guarded = Some(checked);
+        Ok(bytes)
+    })?;
+    if let Some(guarded) = guarded {
+        guarded.record();
+    }
+    Ok(())
+}
+
+/// Attach enum labels and bit fields from a JSON sidecar file to the given variables.
+#[tauri::command]
+async fn apply_decoding_config(path: String, variables: Vec<VariableInfo>) -> Result<Vec<VariableInfo>, String> {
+    let config = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read decoding config {}: {}", path, e))?;
+    // Writes are scaled and checked with the config, whatever scaling a client sends later
+    write_guard::apply_decoding_config(&config)?;
+    decoding::apply_decoding_config(&config, variables)
+}
+
+/// Read a whole array variable in one bulk transfer.
+#[tauri::command]
+async fn read_array(address: u32, var_type: String, element_count: u32, scaling: Option<Scaling>) -> Result<Vec<f64>, String> {
+    let element_size = var_type_size(&var_type)?;
+    let total = element_size * element_count as usize;
+    if element_count == 0 || total > MAX_ARRAY_BYTES {
+        return Err(format!("Invalid array read of {} {} elements", element_count, var_type));
+    }
+    
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let session = session_guard.as_mut().ok_or("No active MCU session")?;
+    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+    
+    let mut buf = vec![0u8; total];
+    core.read(address as u64, &mut buf)
+        .map_err(|e| format!("Failed to read {} element {} array at 0x{:08X}: {}", element_count, var_type, address, e))?;
+    
+    let scaling = scaling.unwrap_or_default();
+    buf.chunks_exact(element_size)
+        .map(|element| decode_value(&var_type, element).map(|raw| scaling.to_engineering(raw.as_f64())))
+        .collect()
+}
+
+/// Write `values` into an array variable starting at element `start_index`.
+/// A single element is written as a one-value slice.
+#[tauri::command]
+async fn write_array(address: u32, var_type: String, element_count: u32, start_index: u32, values: Vec<f64>, scaling: Option<Scaling>) -> Result<(), String> {
+    let end_index = start_index as usize + values.len();
+    if values.is_empty() || end_index > element_count as usize {
+        return Err(format!("Elements {}..{} are out of bounds for an array of {}", start_index, end_index, element_count));
+    }
+    
+    let element_size = var_type_size(&var_type)?;
+    let scaling = write_guard::scaling_at(address, scaling)?;
+    let element_address = address + start_index * element_size as u32;
+    // Every element shares the array's range and rate limit
+    let guarded = write_guard::guard(element_address, &var_type, values.len() * element_size, &values, |variable| {
+        read_current(variable, element_address, values.len())
+    })?;
+    let mut bytes = Vec::with_capacity(values.len() * element_size);
+    for &value in &guarded.values {
+        bytes.extend(scaling.encode(&var_type, value)?);
+    }
+    
+    eprintln!("Writing elements {}..{} of array at 0x{:08X}", start_index, end_index, address);
+    write_mcu_variable(element_address, &var_type, &bytes)?;
+    guarded.record();
+    Ok(())
+}
+
+/// Read a STRING variable (a char buffer of `length` bytes) as text, up to its first NUL.
+#[tauri::command]
+async fn read_string(address: u32, length: u32) -> Result<String, String> {
+    if length == 0 || length as usize > MAX_ARRAY_BYTES {
+        return Err(format!("Invalid string buffer length {}", length));
+    }
+    
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let session = session_guard.as_mut().ok_or("No active MCU session")?;
+    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+    
+    let mut buf = vec![0u8; length as usize];
+    core.read(address as u64, &mut buf)
+        .map_err(|e| format!("Failed to read {} byte string at 0x{:08X}: {}", length, address, e))?;
+    decode_string(&buf)
+}
+
+/// Write text into a STRING variable. The text must leave room for its NUL terminator;
+/// the rest of the buffer is zeroed.
+#[tauri::command]
+async fn write_string(address: u32, length: u32, value: String) -> Result<(), String> {
+    if length == 0 || length as usize > MAX_ARRAY_BYTES {
+        return Err(format!("Invalid string buffer length {}", length));
+    }
+    let bytes = encode_string(&value, length as usize)?;
+    let guarded = write_guard::guard(address, "STRING", bytes.len(), &[], |_| Ok(Vec::new()))?;
+    eprintln!("Writing string {:?} to {} byte buffer at 0x{:08X}", value, length, address);
+    write_mcu_variable(address, "STRING", &bytes)?;
+    guarded.record();
+    Ok(())
+}
+
+/// Read several scalar variables in as few probe transactions as possible, stamped with
+/// the host monotonic time of the read. Unreadable variables (or all of them without a
+/// session) are null.
+#[tauri::command]
+async fn read_variables(variables: Vec<VariableInfo>, max_gap: Option<u32>) -> Result<TimedValues, String> {
+    sampler::read_once(&variables, max_gap)
+}
+
+/// Start streaming samples of `variables` at `rate_hz` (0 = as fast as possible) as
+/// "sampling-batch" events. Variables up to `max_gap` bytes apart (default 64) are
+/// read together in one block. With `core_clock_hz`, every sample also carries the
+/// target time from the DWT cycle counter. Returns the run id carried by each batch.
+#[tauri::command]
+async fn start_sampling(app: tauri::AppHandle, variables: Vec<VariableInfo>, rate_hz: f64, max_gap: Option<u32>, core_clock_hz: Option<f64>) -> Result<u64, String> {
+    sampler::start(app, &variables, rate_hz, max_gap, core_clock_hz)
+}
+
+/// Change the rate of the running sampler.
+#[tauri::command]
+async fn set_sampling_rate(rate_hz: f64) -> Result<(), String> {
+    sampler::set_rate(rate_hz)
+}
+
+/// Interval jitter and missed deadlines of every active sample stream: the sampler,
+/// then RTT binary streams (whose missed deadlines are dropped frames), then SWO data
+/// trace (whose missed deadlines are ITM overflows).
+#[tauri::command]
+async fn sampling_stats() -> Result<Vec<StreamStats>, String> {
+    let mut stats = sampler::stats();
+    stats.extend(rtt::stream_stats());
+    stats.extend(swo::stream_stats());
+    Ok(stats)
+}
+
+/// Stop sampling; with `run_id`, only if that run is still the active one.
+#[tauri::command]
+async fn stop_sampling(run_id: Option<u64>) -> Result<(), String> {
+    if sampler::stop_run(run_id) {
+        eprintln!("Sampling stopped");
+    }
+    Ok(())
+}
+
+/// Attach to the target's SEGGER RTT control block and stream its up channels as
+/// "rtt-data" events. The control block is found at `control_block_address`, else at
+/// the `_SEGGER_RTT` symbol of `elf_path`, else by scanning RAM.
+#[tauri::command]
+async fn rtt_attach(app: tauri::AppHandle, elf_path: Option<String>, control_block_address: Option<u32>) -> Result<rtt::RttInfo, String> {
+    rtt::attach(app, elf_path.as_deref(), control_block_address)
+}
+
+/// Send text to an RTT down channel.
+#[tauri::command]
+async fn rtt_write(channel: usize, data: String) -> Result<(), String> {
+    rtt::write(channel, data.as_bytes())
+}
+
+/// Decode RTT up channel `channel` as mcu_link.h binary sample frames of `variables`,
+/// in the order the firmware packs them. Batches arrive as "sampling-batch" events like
+/// the polling sampler's; with `core_clock_hz` they carry target time. Returns the run id.
+#[tauri::command]
+async fn start_rtt_stream(channel: usize, variables: Vec<VariableInfo>, core_clock_hz: Option<f64>) -> Result<u64, String> {
+    rtt::start_stream(channel, &variables, core_clock_hz)
+}
+
+/// Return an RTT up channel to terminal output; with `run_id`, only if that stream is
+/// still the active one.
+#[tauri::command]
+async fn stop_rtt_stream(channel: usize, run_id: Option<u64>) -> Result<(), String> {
+    rtt::stop_stream(channel, run_id);
+    Ok(())
+}
+
+/// Decode RTT up channel `channel` (default 0) as defmt logs using the defmt table of
+/// `elf_path`. Records arrive as "defmt-log" events, stamped on the same host clock as
+/// sample batches.
+#[tauri::command]
+async fn start_defmt(channel: Option<usize>, elf_path: String) -> Result<(), String> {
+    rtt::start_defmt(channel.unwrap_or(0), &elf_path)
+}
+
+/// Return a defmt channel to terminal output.
+#[tauri::command]
+async fn stop_defmt(channel: Option<usize>) -> Result<(), String> {
+    rtt::stop_defmt(channel.unwrap_or(0));
+    Ok(())
+}
+
+#[tauri::command]
+async fn rtt_detach() -> Result<(), String> {
+    if rtt::detach() {
+        eprintln!("RTT detached");
+    }
+    Ok(())
+}
+
+/// Start SWO trace capture at `baud` (default 1 MBd) with the TPIU clocked at
+/// `core_clock_hz`. ITM stimulus port writes arrive as "swo-data" events.
+#[tauri::command]
+async fn swo_start(app: tauri::AppHandle, core_clock_hz: f64, baud: Option<u32>) -> Result<(), String> {
+    swo::start(app, core_clock_hz, baud.unwrap_or(swo::DEFAULT_BAUD))
+}
+
+/// Watch up to four scalar `variables` with DWT data trace over SWO. Batches arrive as
+/// "sampling-batch" events like the polling sampler's; with `core_clock_hz` they carry
+/// target time from ITM local timestamps. Returns the run id.
+#[tauri::command]
+async fn start_swo_stream(variables: Vec<VariableInfo>, core_clock_hz: Option<f64>) -> Result<u64, String> {
+    swo::start_stream(&variables, core_clock_hz)
+}
+
+/// Stop DWT data trace; with `run_id`, only if that stream is still the active one.
+#[tauri::command]
+async fn stop_swo_stream(run_id: Option<u64>) -> Result<(), String> {
+    swo::stop_stream(run_id);
+    Ok(())
+}
+
+#[tauri::command]
+async fn swo_stop() -> Result<(), String> {
+    if swo::stop() {
+        eprintln!("SWO trace stopped");
+    }
+    Ok(())
+}
+
 #[tauri::command]
 async fn disconnect_probe() -> Result<(), String> {
-    println!("Disconnecting probe and cleaning up session");
+    eprintln!("Disconnecting probe and cleaning up session");
+    // The sampler, RTT and SWO threads take the session lock every poll
+    sampler::stop();
+    rtt::detach();
+    swo::stop();
+    write_guard::clear();
     
     {
         let mut session_guard = SESSION_MANAGER.lock().unwrap();
         if session_guard.is_some() {
-            println!("Session found, disconnecting...");
+            eprintln!("Session found, disconnecting...");
             *session_guard = None;
-            println!("Session cleaned up successfully");
+            eprintln!("Session cleaned up successfully");
         } else {
-            println!("No active session to disconnect");
+            eprintln!("No active session to disconnect");
             return Err("No active session to disconnect".to_string());
         }
     }
@@ -581,7 +1393,7 @@ async fn disconnect_probe() -> Result<(), String> {
 
 #[tauri::command]
 async fn test_ram_writes() -> Result<String, String> {
-    println!("=== Starting RAM write tests ===");
+    eprintln!("=== Starting RAM write tests ===");
     
     let mut session_guard = SESSION_MANAGER.lock().unwrap();
     let session = session_guard.as_mut().ok_or("No active MCU session")?;
@@ -635,7 +1447,7 @@ async fn test_ram_writes() -> Result<String, String> {
         }
     }
     
-    println!("=== RAM write tests completed ===");
+    eprintln!("=== RAM write tests completed ===");
     Ok(results.join("\n"))
 }
 
@@ -653,11 +1465,42 @@ pub fn run() {
             greet, 
             detect_probes, 
             connect_to_mcu,
+            list_supported_targets,
             disconnect_probe,
             discover_variables, 
             discover_variables_at_address,
+            discover_variables_from_elf,
+            discover_variables_from_elf_section,
             read_variable, 
+            read_variable_exact,
             write_variable,
+            read_array,
+            write_array,
+            read_variable_decoded,
+            write_variable_label,
+            write_variable_bits,
+            apply_decoding_config,
+            read_string,
+            write_string,
+            read_variables,
+            start_sampling,
+            set_sampling_rate,
+            stop_sampling,
+            sampling_stats,
+            rtt_attach,
+            rtt_write,
+            rtt_detach,
+            start_rtt_stream,
+            stop_rtt_stream,
+            start_defmt,
+            stop_defmt,
+            set_write_mode,
+            set_limit_policy,
+            set_rate_limit,
+            swo_start,
+            swo_stop,
+            start_swo_stream,
+            stop_swo_stream,
             test_ram_writes
         ])
         .run(tauri::generate_context!())
@@ -2,5 +2,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `mcu-link --list-mculink firmware.elf [report.json] [--allow-unsealed]` lists the exposed
+    // variables (used by CI); tables without a valid CRC are rejected unless allowed
+    // `mcu-link --seal-mculink firmware.elf` fills in the descriptor CRC after linking
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "--list-mculink") {
+        let allow_unsealed = args.iter().any(|arg| arg == "--allow-unsealed");
+        args.retain(|arg| arg != "--allow-unsealed");
+        if !(3..=4).contains(&args.len()) {
+            eprintln!("Usage: mcu-link --list-mculink firmware.elf [report.json] [--allow-unsealed]");
+            std::process::exit(2);
+        }
+        std::process::exit(mcu_link_lib::list_mculink_cli(&args[2], args.get(3).map(|s| s.as_str()), allow_unsealed));
+    }
+    if args.len() == 3 && args[1] == "--seal-mculink" {
+        std::process::exit(mcu_link_lib::seal_mculink_cli(&args[2]));
+    }
+
     mcu_link_lib::run()
 }
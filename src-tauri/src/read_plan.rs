@@ -0,0 +1,140 @@
+// Read planner: coalesces the memory of many variables into a few contiguous,
+// 32-bit aligned block reads so a sample costs one probe transaction per block
+// instead of one per variable.
+
+use probe_rs::MemoryInterface;
+
+pub(crate) const DEFAULT_MAX_GAP: u32 = 64; // Unused bytes worth reading to save a transaction
+const MAX_BLOCK_BYTES: u32 = 4096; // Keeps a single transaction short
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ReadBlock {
+    pub address: u32, // 4-byte aligned
+    pub words: usize, // Length in 32-bit words
+}
+
+// Where one requested variable sits in the block data
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Slot {
+    block: usize,
+    offset: usize,
+    size: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ReadPlan {
+    pub blocks: Vec<ReadBlock>,
+    slots: Vec<Slot>, // In request order
+}
+
+impl ReadPlan {
+    /// Group `(address, size)` requests into aligned blocks. Neighbouring variables are
+    /// merged when at most `max_gap` unused bytes separate them.
+    pub fn new(requests: &[(u32, usize)], max_gap: u32) -> Self {
+        let mut order: Vec<usize> = (0..requests.len()).collect();
+        order.sort_by_key(|&i| requests[i].0);
+
+        let mut blocks: Vec<(u64, u64)> = Vec::new(); // Aligned [start, end)
+        let mut slots = vec![Slot { block: 0, offset: 0, size: 0 }; requests.len()];
+        for i in order {
+            let (address, size) = requests[i];
+            let start = address as u64 & !3;
+            let end = (address as u64 + size as u64).next_multiple_of(4);
+
+            let merge = blocks.last().is_some_and(|&(block_start, block_end)| {
+                start <= block_end + max_gap as u64 && end.max(block_end) - block_start <= MAX_BLOCK_BYTES as u64
+            });
+            if merge {
+                let last = blocks.last_mut().unwrap();
+                last.1 = last.1.max(end);
+            } else {
+                blocks.push((start, end));
+            }
+            let block = blocks.len() - 1;
+            slots[i] = Slot { block, offset: (address as u64 - blocks[block].0) as usize, size };
+        }
+
+        ReadPlan {
+            blocks: blocks.iter()
+                .map(|&(start, end)| ReadBlock { address: start as u32, words: ((end - start) / 4) as usize })
+                .collect(),
+            slots,
+        }
+    }
+
+    /// Read every block with 32-bit accesses. A failed block is `None` and only fails
+    /// the variables inside it.
+    pub fn read(&self, memory: &mut impl MemoryInterface) -> Vec<Option<Vec<u8>>> {
+        self.blocks.iter().map(|block| {
+            let mut words = vec![0u32; block.words];
+            match memory.read_32(block.address as u64, &mut words) {
+                Ok(()) => Some(words.iter().flat_map(|word| word.to_le_bytes()).collect()),
+                Err(e) => {
+                    eprintln!("Failed to read {} bytes at 0x{:08X}: {}", block.words * 4, block.address, e);
+                    None
+                },
+            }
+        }).collect()
+    }
+
+    /// Bytes of request `index`, given the data read for every block (in plan order).
+    pub fn bytes<'a>(&self, data: &'a [Option<Vec<u8>>], index: usize) -> Option<&'a [u8]> {
+        let slot = self.slots[index];
+        data[slot.block].as_ref().map(|block| &block[slot.offset..slot.offset + slot.size])
+    }
+
+    /// Number of requests in the plan.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Total bytes transferred per execution of the plan.
+    pub fn total_bytes(&self) -> usize {
+        self.blocks.iter().map(|block| block.words * 4).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_neighbours_into_one_aligned_block() {
+        // u8 at 0x...01, u16 at 0x...06, float at 0x...10, listed out of order
+        let plan = ReadPlan::new(&[(0x2000_0010, 4), (0x2000_0001, 1), (0x2000_0006, 2)], 16);
+        assert_eq!(plan.blocks, vec![ReadBlock { address: 0x2000_0000, words: 5 }]);
+
+        let data = vec![Some((0u8..20).collect::<Vec<u8>>())];
+        assert_eq!(plan.bytes(&data, 0), Some(&[16, 17, 18, 19][..]));
+        assert_eq!(plan.bytes(&data, 1), Some(&[1][..]));
+        assert_eq!(plan.bytes(&data, 2), Some(&[6, 7][..]));
+    }
+
+    #[test]
+    fn splits_when_the_gap_is_too_large() {
+        let requests = [(0x2000_0000, 4), (0x2000_0100, 4)];
+        assert_eq!(ReadPlan::new(&requests, 64).blocks.len(), 2);
+        assert_eq!(ReadPlan::new(&requests, 256).blocks.len(), 1);
+        assert_eq!(ReadPlan::new(&requests, 64).total_bytes(), 8);
+    }
+
+    #[test]
+    fn overlapping_requests_share_a_block() {
+        // A struct and one of its fields
+        let plan = ReadPlan::new(&[(0x2000_0020, 16), (0x2000_0024, 4)], 0);
+        assert_eq!(plan.blocks, vec![ReadBlock { address: 0x2000_0020, words: 4 }]);
+        let data = vec![Some((0u8..16).collect::<Vec<u8>>())];
+        assert_eq!(plan.bytes(&data, 1), Some(&[4, 5, 6, 7][..]));
+
+        // A failed block fails only its own variables
+        assert_eq!(plan.bytes(&[None], 0), None);
+    }
+
+    #[test]
+    fn caps_block_length() {
+        let requests: Vec<(u32, usize)> = (0..2048).map(|i| (0x2000_0000 + i * 4, 4)).collect();
+        let plan = ReadPlan::new(&requests, DEFAULT_MAX_GAP);
+        assert_eq!(plan.blocks.len(), 2);
+        assert!(plan.blocks.iter().all(|block| block.words * 4 <= MAX_BLOCK_BYTES as usize));
+    }
+}
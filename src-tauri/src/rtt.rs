@@ -0,0 +1,305 @@
+// SEGGER RTT: finds the target's RTT control block, streams up-channel bytes to the
+// frontend as "rtt-data" events and writes to down channels. The poller takes the
+// session lock once per poll, so it runs alongside the sampler and variable reads.
+// Up channels carrying mcu_link.h binary frames are decoded into "sampling-batch"
+// events instead, and a defmt channel into "defmt-log" records.
+
+use probe_rs::rtt::{Rtt, ScanRegion};
+use probe_rs::Core;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::defmt_log::{DefmtLog, DEFMT_LOG_EVENT};
+use crate::rtt_stream::SampleStream;
+use crate::sampler::{self, SAMPLING_BATCH_EVENT};
+use crate::timing::StreamStats;
+use crate::{elf, timing, VariableInfo, SESSION_MANAGER};
+
+pub(crate) const RTT_DATA_EVENT: &str = "rtt-data";
+const RTT_SYMBOL: &str = "_SEGGER_RTT"; // Control block symbol in SEGGER's RTT sources
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+const READ_CHUNK: usize = 16 * 1024; // Bytes read per channel per poll; 10 kHz streams need ~3 KiB
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1); // Wait for a full down buffer to drain
+
+static RTT: Mutex<Option<RttLink>> = Mutex::new(None);
+
+// How an up channel is decoded instead of being passed on as terminal bytes
+enum ChannelDecoder {
+    Samples(Box<SampleStream>),
+    Defmt(DefmtLog),
+}
+
+// Decoders by up channel number
+type Decoders = Arc<Mutex<HashMap<usize, ChannelDecoder>>>;
+
+struct RttLink {
+    rtt: Arc<Mutex<Rtt>>,
+    decoders: Decoders,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RttChannelInfo {
+    pub number: usize,
+    pub name: Option<String>,
+    pub buffer_size: usize,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct RttInfo {
+    pub control_block_address: u64,
+    pub up_channels: Vec<RttChannelInfo>,
+    pub down_channels: Vec<RttChannelInfo>,
+}
+
+/// Bytes received on one up channel, as emitted with the "rtt-data" event.
+#[derive(Debug, Serialize, Clone)]
+pub struct RttData {
+    pub channel: usize,
+    pub bytes: Vec<u8>,
+    pub timestamp_us: u64, // Host monotonic time of the read
+}
+
+// Run `f` on core 0 of the active session
+fn with_core<T>(f: impl FnOnce(&mut Core) -> Result<T, String>) -> Result<T, String> {
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let session = session_guard.as_mut().ok_or("No active MCU session")?;
+    let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+    f(&mut core)
+}
+
+// Where to look for the control block: an explicit address, the ELF symbol, or all RAM
+fn scan_region(elf_path: Option<&str>, address: Option<u32>) -> Result<ScanRegion, String> {
+    if let Some(address) = address {
+        return Ok(ScanRegion::Exact(address as u64));
+    }
+    if let Some(path) = elf_path {
+        let data = std::fs::read(path)
+            .map_err(|e| format!("Failed to read ELF file {}: {}", path, e))?;
+        let address = elf::find_symbol(&data, RTT_SYMBOL)?;
+        eprintln!("Found {} at 0x{:08X} in {}", RTT_SYMBOL, address, path);
+        return Ok(ScanRegion::Exact(address as u64));
+    }
+    Ok(ScanRegion::Ram)
+}
+
+fn poll(app: AppHandle, rtt: Arc<Mutex<Rtt>>, decoders: Decoders, stop: Arc<AtomicBool>) {
+    let mut buf = vec![0u8; READ_CHUNK];
+    while !stop.load(Ordering::Relaxed) {
+        let mut received = Vec::new();
+        {
+            let mut rtt = rtt.lock().unwrap();
+            let result = with_core(|core| {
+                for channel in rtt.up_channels().iter() {
+                    let (timestamp_us, count) = timing::timed(|| channel.read(core, &mut buf));
+                    let count = count.map_err(|e| format!("Failed to read RTT channel {}: {}", channel.number(), e))?;
+                    if count > 0 {
+                        received.push(RttData { channel: channel.number(), bytes: buf[..count].to_vec(), timestamp_us });
+                    }
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                eprintln!("{}", e);
+            }
+        }
+
+        let mut decoders = decoders.lock().unwrap();
+        let mut records = Vec::new();
+        for data in received {
+            match decoders.get_mut(&data.channel) {
+                Some(ChannelDecoder::Samples(stream)) => stream.feed(&data.bytes, data.timestamp_us),
+                Some(ChannelDecoder::Defmt(log)) => records.extend(log.feed(&data.bytes, data.timestamp_us)),
+                None => {
+                    if let Err(e) = app.emit(RTT_DATA_EVENT, data) {
+                        eprintln!("Failed to emit RTT data: {}", e);
+                    }
+                },
+            }
+        }
+        for decoder in decoders.values_mut() {
+            if let ChannelDecoder::Samples(stream) = decoder {
+                if stream.batch.len() > 0 {
+                    if let Err(e) = app.emit(SAMPLING_BATCH_EVENT, stream.batch.take()) {
+                        eprintln!("Failed to emit sample batch: {}", e);
+                    }
+                }
+            }
+        }
+        drop(decoders);
+
+        if !records.is_empty() {
+            if let Err(e) = app.emit(DEFMT_LOG_EVENT, records) {
+                eprintln!("Failed to emit defmt records: {}", e);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    eprintln!("RTT poller stopped");
+}
+
+/// Attach to the RTT control block and start streaming up channels, replacing any
+/// previous attachment. The control block is located at `address`, else at the
+/// `_SEGGER_RTT` symbol of `elf_path`, else by scanning the target RAM.
+pub(crate) fn attach(app: AppHandle, elf_path: Option<&str>, address: Option<u32>) -> Result<RttInfo, String> {
+    let region = scan_region(elf_path, address)?;
+    detach();
+
+    let mut rtt = {
+        let mut session_guard = SESSION_MANAGER.lock().unwrap();
+        let session = session_guard.as_mut().ok_or("No active MCU session")?;
+        let memory_map = session.target().memory_map.clone();
+        let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+        Rtt::attach_region(&mut core, &memory_map, &region)
+            .map_err(|e| format!("Failed to attach to RTT: {}", e))?
+    };
+
+    let channel_info = |number, name: Option<&str>, buffer_size| RttChannelInfo { number, name: name.map(str::to_string), buffer_size };
+    let info = RttInfo {
+        control_block_address: rtt.ptr(),
+        up_channels: rtt.up_channels().iter().map(|c| channel_info(c.number(), c.name(), c.buffer_size())).collect(),
+        down_channels: rtt.down_channels().iter().map(|c| channel_info(c.number(), c.name(), c.buffer_size())).collect(),
+    };
+    eprintln!("Attached to RTT control block at 0x{:08X}: {} up, {} down channels",
+        info.control_block_address, info.up_channels.len(), info.down_channels.len());
+
+    let rtt = Arc::new(Mutex::new(rtt));
+    let decoders = Decoders::default();
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let rtt = rtt.clone();
+        let decoders = decoders.clone();
+        let stop = stop.clone();
+        std::thread::Builder::new()
+            .name("mculink-rtt".to_string())
+            .spawn(move || poll(app, rtt, decoders, stop))
+            .map_err(|e| format!("Failed to start RTT thread: {}", e))?
+    };
+
+    *RTT.lock().unwrap() = Some(RttLink { rtt, decoders, stop, thread });
+    Ok(info)
+}
+
+/// Write `data` to a down channel, waiting up to a second for the target to drain a
+/// full buffer. Returns an error if not every byte could be written.
+pub(crate) fn write(channel: usize, data: &[u8]) -> Result<(), String> {
+    let rtt = RTT.lock().unwrap().as_ref().map(|link| link.rtt.clone()).ok_or("RTT is not attached")?;
+    let mut rtt = rtt.lock().unwrap();
+    let down = rtt.down_channels().get(channel).ok_or_else(|| format!("No RTT down channel {}", channel))?;
+
+    let deadline = Instant::now() + WRITE_TIMEOUT;
+    let mut written = 0;
+    while written < data.len() {
+        written += with_core(|core| down.write(core, &data[written..])
+            .map_err(|e| format!("Failed to write RTT channel {}: {}", channel, e)))?;
+        if written < data.len() {
+            if Instant::now() >= deadline {
+                return Err(format!("RTT channel {} is full; wrote {} of {} bytes", channel, written, data.len()));
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+    Ok(())
+}
+
+/// Decode up channel `channel` as binary sample frames of `variables` (in frame order)
+/// instead of terminal text, replacing any stream already on it. Returns the run id
+/// carried by its sample batches.
+pub(crate) fn start_stream(channel: usize, variables: &[VariableInfo], core_clock_hz: Option<f64>) -> Result<u64, String> {
+    let link = RTT.lock().unwrap();
+    let link = link.as_ref().ok_or("RTT is not attached")?;
+    if link.rtt.lock().unwrap().up_channels().get(channel).is_none() {
+        return Err(format!("No RTT up channel {}", channel));
+    }
+    if let Some(core_clock_hz) = core_clock_hz {
+        crate::dwt::validate_core_clock(core_clock_hz)?;
+    }
+
+    let run_id = sampler::next_run_id();
+    let stream = SampleStream::new(run_id, variables, core_clock_hz)?;
+    link.decoders.lock().unwrap().insert(channel, ChannelDecoder::Samples(Box::new(stream)));
+    eprintln!("RTT channel {} streaming {} variables as run {}", channel, variables.len(), run_id);
+    Ok(run_id)
+}
+
+/// Return up channel `channel` to terminal output. Only stops the stream if it is
+/// still `run_id`, when given.
+pub(crate) fn stop_stream(channel: usize, run_id: Option<u64>) -> bool {
+    let link = RTT.lock().unwrap();
+    let Some(link) = link.as_ref() else {
+        return false;
+    };
+    let mut decoders = link.decoders.lock().unwrap();
+    let Some(ChannelDecoder::Samples(stream)) = decoders.get(&channel) else {
+        return false;
+    };
+    if run_id.is_some_and(|id| stream.run_id != id) {
+        return false;
+    }
+    eprintln!("RTT channel {} stream {} stopped after {} dropped frames", channel, stream.run_id, stream.dropped());
+    decoders.remove(&channel);
+    true
+}
+
+/// Interval statistics and dropped frames of every binary stream.
+pub(crate) fn stream_stats() -> Vec<StreamStats> {
+    let link = RTT.lock().unwrap();
+    let Some(link) = link.as_ref() else {
+        return Vec::new();
+    };
+    let decoders = link.decoders.lock().unwrap();
+    decoders.iter()
+        .filter_map(|(&channel, decoder)| match decoder {
+            ChannelDecoder::Samples(stream) => Some(stream.report(channel)),
+            ChannelDecoder::Defmt(_) => None,
+        })
+        .collect()
+}
+
+/// Decode up channel `channel` as defmt logs, using the defmt table of `elf_path`.
+pub(crate) fn start_defmt(channel: usize, elf_path: &str) -> Result<(), String> {
+    let data = std::fs::read(elf_path)
+        .map_err(|e| format!("Failed to read ELF file {}: {}", elf_path, e))?;
+    let log = DefmtLog::from_elf(&data)?;
+
+    let link = RTT.lock().unwrap();
+    let link = link.as_ref().ok_or("RTT is not attached")?;
+    if link.rtt.lock().unwrap().up_channels().get(channel).is_none() {
+        return Err(format!("No RTT up channel {}", channel));
+    }
+    link.decoders.lock().unwrap().insert(channel, ChannelDecoder::Defmt(log));
+    eprintln!("RTT channel {} decoding defmt logs from {}", channel, elf_path);
+    Ok(())
+}
+
+/// Return a defmt channel to terminal output.
+pub(crate) fn stop_defmt(channel: usize) -> bool {
+    let link = RTT.lock().unwrap();
+    let Some(link) = link.as_ref() else {
+        return false;
+    };
+    let mut decoders = link.decoders.lock().unwrap();
+    if !matches!(decoders.get(&channel), Some(ChannelDecoder::Defmt(_))) {
+        return false;
+    }
+    decoders.remove(&channel);
+    true
+}
+
+/// Stop streaming and forget the control block. Returns whether RTT was attached.
+pub(crate) fn detach() -> bool {
+    let Some(link) = RTT.lock().unwrap().take() else {
+        return false;
+    };
+    link.stop.store(true, Ordering::Relaxed);
+    if link.thread.join().is_err() {
+        eprintln!("RTT poller panicked");
+    }
+    true
+}
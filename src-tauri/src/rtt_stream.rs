@@ -0,0 +1,268 @@
+// Decoder for the binary sample frames produced by the mculink_stream_* helpers in
+// mcu_link.h (see the frame layout there). Frames carry a sequence number, so frames the
+// firmware could not fit into the RTT buffer are counted as dropped.
+
+use crate::dwt::CycleClock;
+use crate::layout::{decode_value, var_type_size};
+use crate::sampler::BatchBuilder;
+use crate::scaling::Scaling;
+use crate::timing::{JitterStats, StreamStats};
+use crate::VariableInfo;
+
+const SYNC: [u8; 2] = [0xA5, 0x5A];
+const HEADER_SIZE: usize = 9; // Sync, seq, timestamp and length
+const FRAME_OVERHEAD: usize = HEADER_SIZE + 1; // Plus CRC
+const MAX_PAYLOAD: usize = 255;
+
+/// CRC-8 with polynomial 0x07 and initial value 0, as computed by the firmware.
+fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |crc, &byte| {
+        (0..8).fold(crc ^ byte, |crc, _| if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 })
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Frame {
+    pub seq: u16,
+    pub timestamp: u32, // Core cycles
+    pub payload: Vec<u8>,
+}
+
+// Finds frames in the byte stream of one RTT channel
+#[derive(Debug, Default)]
+pub(crate) struct FrameDecoder {
+    pending: Vec<u8>,
+    next_seq: Option<u16>,
+    pub dropped: u64, // Frames missing from the sequence
+    pub corrupt: u64, // Bytes skipped while resynchronising
+}
+
+impl FrameDecoder {
+    /// Add received bytes and return the complete frames found so far.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<Frame> {
+        self.pending.extend_from_slice(bytes);
+        let mut frames = Vec::new();
+        let mut start = 0;
+
+        while self.pending.len() - start >= HEADER_SIZE {
+            let data = &self.pending[start..];
+            if data[..2] != SYNC {
+                start += 1;
+                self.corrupt += 1;
+                continue;
+            }
+            let size = FRAME_OVERHEAD + data[8] as usize;
+            if data.len() < size {
+                break;
+            }
+            if crc8(&data[2..size - 1]) != data[size - 1] {
+                // A sync pattern inside sample data, or a damaged frame
+                start += 1;
+                self.corrupt += 1;
+                continue;
+            }
+
+            let seq = u16::from_le_bytes([data[2], data[3]]);
+            if let Some(expected) = self.next_seq {
+                self.dropped += seq.wrapping_sub(expected) as u64;
+            }
+            self.next_seq = Some(seq.wrapping_add(1));
+            frames.push(Frame {
+                seq,
+                timestamp: u32::from_le_bytes([data[4], data[5], data[6], data[7]]),
+                payload: data[HEADER_SIZE..size - 1].to_vec(),
+            });
+            start += size;
+        }
+
+        self.pending.drain(..start);
+        frames
+    }
+}
+
+// What one stream needs to decode its payload
+struct StreamChannel {
+    var_type: String,
+    size: usize,
+    scaling: Scaling,
+}
+
+/// A binary sample stream on one RTT up channel, turned into sample batches like the
+/// polling sampler's.
+pub(crate) struct SampleStream {
+    pub run_id: u64,
+    decoder: FrameDecoder,
+    channels: Vec<StreamChannel>,
+    payload_size: usize,
+    clock: Option<CycleClock>,
+    pub batch: BatchBuilder,
+    reported_dropped: u64,
+    stats: JitterStats, // Over target time, or arrival time without a core clock
+}
+
+impl SampleStream {
+    /// Stream of `variables`, packed in this order. With `core_clock_hz`, frame
+    /// timestamps are converted to target time in seconds.
+    pub fn new(run_id: u64, variables: &[VariableInfo], core_clock_hz: Option<f64>) -> Result<Self, String> {
+        if variables.is_empty() {
+            return Err("No variables to stream".to_string());
+        }
+        let channels = variables.iter().map(|variable| {
+            if variable.element_count != 1 || variable.var_type == "STRING" {
+                return Err(format!("{} is not a scalar and cannot be streamed", variable.name));
+            }
+            Ok(StreamChannel {
+                var_type: variable.var_type.clone(),
+                size: var_type_size(&variable.var_type)?,
+                scaling: Scaling::of(variable),
+            })
+        }).collect::<Result<Vec<_>, String>>()?;
+        let payload_size = channels.iter().map(|channel| channel.size).sum();
+        if payload_size > MAX_PAYLOAD {
+            return Err(format!("{} bytes of samples do not fit one {} byte frame", payload_size, MAX_PAYLOAD));
+        }
+
+        Ok(SampleStream {
+            run_id,
+            decoder: FrameDecoder::default(),
+            batch: BatchBuilder::new(run_id, channels.len(), 0, core_clock_hz.is_some()),
+            channels,
+            payload_size,
+            clock: core_clock_hz.map(CycleClock::new),
+            reported_dropped: 0,
+            stats: JitterStats::new(0.0),
+        })
+    }
+
+    /// Decode received bytes into the pending batch; `host_us` is when they were read.
+    pub fn feed(&mut self, bytes: &[u8], host_us: u64) {
+        for frame in self.decoder.feed(bytes) {
+            if frame.payload.len() != self.payload_size {
+                eprintln!("RTT stream {}: frame {} has {} bytes of samples, expected {}",
+                    self.run_id, frame.seq, frame.payload.len(), self.payload_size);
+                self.decoder.dropped += 1;
+                continue;
+            }
+            let mut offset = 0;
+            let values = self.channels.iter().map(|channel| {
+                let bytes = &frame.payload[offset..offset + channel.size];
+                offset += channel.size;
+                let raw = decode_value(&channel.var_type, bytes).ok()?;
+                Some(channel.scaling.to_engineering(raw.as_f64()))
+            }).collect();
+            let target_time_s = self.clock.as_mut().map(|clock| clock.seconds(frame.timestamp, host_us));
+            self.stats.record(target_time_s.map_or(host_us, |seconds| (seconds * 1e6) as u64));
+            self.batch.push(host_us, target_time_s, values);
+        }
+
+        if self.decoder.dropped > self.reported_dropped {
+            self.batch.add_dropped(self.decoder.dropped - self.reported_dropped);
+            self.reported_dropped = self.decoder.dropped;
+        }
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.decoder.dropped
+    }
+
+    /// Interval statistics, with dropped frames as missed deadlines.
+    pub fn report(&self, channel: usize) -> StreamStats {
+        StreamStats {
+            missed_deadlines: self.dropped(),
+            ..self.stats.report(self.run_id, &format!("RTT channel {} stream {}", channel, self.run_id))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Frame of a float 1.5 and a uint16 0x1234 at timestamp 0xDEADBEEF, seq 0,
+    // as produced by mcu_link.h
+    const FRAME: [u8; 16] = [0xA5, 0x5A, 0x00, 0x00, 0xEF, 0xBE, 0xAD, 0xDE, 0x06, 0x00, 0x00, 0xC0, 0x3F, 0x34, 0x12, 0xE0];
+
+    fn frame(seq: u16) -> Vec<u8> {
+        let mut frame = FRAME.to_vec();
+        frame[2..4].copy_from_slice(&seq.to_le_bytes());
+        frame[15] = crc8(&frame[2..15]);
+        frame
+    }
+
+    #[test]
+    fn decodes_firmware_frames() {
+        let mut decoder = FrameDecoder::default();
+        let frames = decoder.feed(&FRAME);
+        assert_eq!(frames, vec![Frame { seq: 0, timestamp: 0xDEAD_BEEF, payload: FRAME[9..15].to_vec() }]);
+        assert_eq!((decoder.dropped, decoder.corrupt), (0, 0));
+    }
+
+    #[test]
+    fn reassembles_frames_split_across_reads() {
+        let mut decoder = FrameDecoder::default();
+        let bytes = [frame(1), frame(2)].concat();
+        assert!(decoder.feed(&bytes[..5]).is_empty());
+        assert_eq!(decoder.feed(&bytes[5..20]).len(), 1);
+        assert_eq!(decoder.feed(&bytes[20..]).len(), 1);
+    }
+
+    #[test]
+    fn counts_dropped_frames_across_sequence_wrap() {
+        let mut decoder = FrameDecoder::default();
+        decoder.feed(&[frame(0xFFFE), frame(0xFFFF), frame(2)].concat());
+        // Frames 0 and 1 are missing
+        assert_eq!(decoder.dropped, 2);
+    }
+
+    #[test]
+    fn resynchronises_after_garbage() {
+        let mut decoder = FrameDecoder::default();
+        let mut damaged = frame(1);
+        damaged[10] ^= 0xFF;
+        let frames = decoder.feed(&[frame(0), vec![0x00, 0xA5], damaged, frame(2)].concat());
+        assert_eq!(frames.iter().map(|f| f.seq).collect::<Vec<_>>(), vec![0, 2]);
+        assert!(decoder.corrupt > 0);
+        // The damaged frame shows up as a gap in the sequence
+        assert_eq!(decoder.dropped, 1);
+    }
+
+    fn variable(name: &str, var_type: &str) -> VariableInfo {
+        VariableInfo {
+            name: name.to_string(),
+            parent: None,
+            address: 0x2000_0000,
+            var_type: var_type.to_string(),
+            access_flags: "RO".to_string(),
+            category: None,
+            min_value: None,
+            max_value: None,
+            element_count: 1,
+            enum_labels: Vec::new(),
+            bit_fields: Vec::new(),
+            scale: None,
+            offset: None,
+            q_format: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn streams_frames_as_sample_batches() {
+        let variables = [variable("current", "FLOAT"), variable("speed", "UINT16")];
+        let mut stream = SampleStream::new(3, &variables, Some(100e6)).unwrap();
+        stream.feed(&[frame(0), frame(2)].concat(), 1_000);
+
+        let batch = stream.batch.take();
+        assert_eq!(batch.run_id, 3);
+        assert_eq!(batch.values, vec![vec![Some(1.5), Some(1.5)], vec![Some(4660.0), Some(4660.0)]]);
+        assert_eq!(batch.target_times_s, Some(vec![Some(0.0), Some(0.0)]));
+        assert_eq!(batch.dropped_samples, 1);
+        assert_eq!(stream.dropped(), 1);
+
+        // Variables that do not match the frame layout drop every frame
+        let mut mismatched = SampleStream::new(4, &variables[..1], None).unwrap();
+        mismatched.feed(&frame(0), 0);
+        assert_eq!(mismatched.batch.len(), 0);
+        assert_eq!(mismatched.dropped(), 1);
+    }
+}
@@ -0,0 +1,380 @@
+// Background sampling engine: a thread reads every selected variable once per tick and
+// streams timestamped batches to the frontend as "sampling-batch" events, instead of the
+// UI polling read_variable once per variable per tick over IPC. Each tick executes a
+// ReadPlan, so variables sharing a memory region cost one block read between them.
+// Ticks are stamped on the host monotonic clock around the probe transaction and feed
+// the run's interval statistics. Optionally the DWT cycle counter is read in the same
+// tick and converted into a target-side timestamp.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::dwt::{self, CycleClock, CYCCNT_SIZE, DWT_CYCCNT};
+use crate::layout::{decode_value, var_type_size};
+use crate::read_plan::{ReadPlan, DEFAULT_MAX_GAP};
+use crate::scaling::Scaling;
+use crate::timing::{self, JitterStats, StreamStats, TimedValues};
+use crate::{VariableInfo, SESSION_MANAGER};
+
+pub(crate) const SAMPLING_BATCH_EVENT: &str = "sampling-batch";
+const MAX_SAMPLE_RATE_HZ: f64 = 100_000.0; // Upper bound accepted for a fixed rate
+const BATCH_INTERVAL: Duration = Duration::from_millis(20); // UI updates at ~50 Hz
+const MAX_BATCH_SAMPLES: usize = 1000; // Flush early at very high rates
+const MAX_SLEEP: Duration = Duration::from_millis(10); // Keeps stop responsive at low rates
+
+static SAMPLER: Mutex<Option<Sampler>> = Mutex::new(None);
+static NEXT_RUN_ID: AtomicU64 = AtomicU64::new(1);
+
+struct Sampler {
+    run_id: u64,
+    stop: Arc<AtomicBool>,
+    period_ns: Arc<AtomicU64>, // 0 = as fast as the probe allows
+    stats: Arc<Mutex<JitterStats>>,
+    thread: JoinHandle<()>,
+}
+
+/// One batch of samples, column-major: `values[i][j]` is variable `i` at `timestamps_us[j]`.
+/// Failed reads are `None` (null in JSON).
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct SampleBatch {
+    pub run_id: u64,              // Identifies the start_sampling call this batch belongs to
+    pub timestamps_us: Vec<u64>,  // Host monotonic time of each probe transaction
+    pub target_times_s: Option<Vec<Option<f64>>>, // DWT_CYCCNT time since the run started, if enabled
+    pub values: Vec<Vec<Option<f64>>>,
+    pub samples_per_second: f64,  // Achieved tick rate over this batch
+    pub block_reads: usize,       // Probe transactions per tick (0 for RTT streams)
+    pub dropped_samples: u64,     // Samples lost since the previous batch (RTT buffer overflow)
+}
+
+// What the thread needs to decode one variable, resolved once at start
+struct Channel {
+    var_type: String,
+    scaling: Scaling,
+}
+
+/// Allocate a run id; sampler runs and RTT streams share one sequence.
+pub(crate) fn next_run_id() -> u64 {
+    NEXT_RUN_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Sampling period for a rate in Hz; 0 Hz means "as fast as possible" (period 0).
+pub(crate) fn sample_period_ns(rate_hz: f64) -> Result<u64, String> {
+    if rate_hz == 0.0 {
+        return Ok(0);
+    }
+    if !(0.0..=MAX_SAMPLE_RATE_HZ).contains(&rate_hz) {
+        return Err(format!("Sample rate {} Hz is outside 0..{} Hz", rate_hz, MAX_SAMPLE_RATE_HZ));
+    }
+    Ok((1e9 / rate_hz).round() as u64)
+}
+
+// Accumulates ticks until the batch is due
+pub(crate) struct BatchBuilder {
+    run_id: u64,
+    block_reads: usize,
+    dropped_samples: u64,
+    timestamps_us: Vec<u64>,
+    target_times_s: Option<Vec<Option<f64>>>,
+    values: Vec<Vec<Option<f64>>>,
+    last_timestamp_us: Option<u64>, // Last tick of the previous batch
+}
+
+impl BatchBuilder {
+    pub fn new(run_id: u64, channels: usize, block_reads: usize, target_time: bool) -> Self {
+        BatchBuilder {
+            run_id,
+            block_reads,
+            dropped_samples: 0,
+            timestamps_us: Vec::new(),
+            target_times_s: target_time.then(Vec::new),
+            values: vec![Vec::new(); channels],
+            last_timestamp_us: None,
+        }
+    }
+
+    // Ticks per second between the end of the previous batch and the end of this one
+    fn samples_per_second(&self) -> f64 {
+        let (Some(&first), Some(&last)) = (self.timestamps_us.first(), self.timestamps_us.last()) else {
+            return 0.0;
+        };
+        let (start, ticks) = match self.last_timestamp_us {
+            Some(previous) => (previous, self.timestamps_us.len()),
+            None => (first, self.timestamps_us.len() - 1),
+        };
+        if last <= start { 0.0 } else { ticks as f64 * 1e6 / (last - start) as f64 }
+    }
+
+    pub fn push(&mut self, timestamp_us: u64, target_time_s: Option<f64>, tick: Vec<Option<f64>>) {
+        self.timestamps_us.push(timestamp_us);
+        if let Some(target_times_s) = &mut self.target_times_s {
+            target_times_s.push(target_time_s);
+        }
+        for (column, value) in self.values.iter_mut().zip(tick) {
+            column.push(value);
+        }
+    }
+
+    pub fn add_dropped(&mut self, samples: u64) {
+        self.dropped_samples += samples;
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps_us.len()
+    }
+
+    pub fn take(&mut self) -> SampleBatch {
+        let channels = self.values.len();
+        let samples_per_second = self.samples_per_second();
+        self.last_timestamp_us = self.timestamps_us.last().copied().or(self.last_timestamp_us);
+        SampleBatch {
+            run_id: self.run_id,
+            timestamps_us: std::mem::take(&mut self.timestamps_us),
+            target_times_s: self.target_times_s.as_mut().map(std::mem::take),
+            values: std::mem::replace(&mut self.values, vec![Vec::new(); channels]),
+            samples_per_second,
+            block_reads: self.block_reads,
+            dropped_samples: std::mem::take(&mut self.dropped_samples),
+        }
+    }
+}
+
+// Read plan and decoding of a set of scalar variables; with `cycle_counter`, DWT_CYCCNT
+// is appended as the last request of the plan
+fn plan_channels(variables: &[VariableInfo], max_gap: Option<u32>, cycle_counter: bool) -> Result<(ReadPlan, Vec<Channel>), String> {
+    if variables.is_empty() {
+        return Err("No variables to sample".to_string());
+    }
+    let mut requests = variables.iter().map(|variable| {
+        if variable.element_count != 1 || variable.var_type == "STRING" {
+            return Err(format!("{} is not a scalar and cannot be sampled", variable.name));
+        }
+        Ok((variable.address, var_type_size(&variable.var_type)?))
+    }).collect::<Result<Vec<_>, String>>()?;
+    if cycle_counter {
+        requests.push((DWT_CYCCNT, CYCCNT_SIZE));
+    }
+    let plan = ReadPlan::new(&requests, max_gap.unwrap_or(DEFAULT_MAX_GAP));
+    let channels = variables.iter()
+        .map(|variable| Channel { var_type: variable.var_type.clone(), scaling: Scaling::of(variable) })
+        .collect();
+    Ok((plan, channels))
+}
+
+/// Read a set of scalar variables once through a coalesced read plan, in engineering
+/// units. Variables that could not be read are `None`.
+pub(crate) fn read_once(variables: &[VariableInfo], max_gap: Option<u32>) -> Result<TimedValues, String> {
+    let (plan, channels) = plan_channels(variables, max_gap, false)?;
+    let tick = read_tick(&plan, &channels);
+    Ok(TimedValues { values: tick.values, timestamp_us: tick.timestamp_us })
+}
+
+// One execution of the read plan
+struct Tick {
+    timestamp_us: u64,
+    values: Vec<Option<f64>>,
+    cycle_count: Option<u32>, // Raw DWT_CYCCNT, if the plan reads it
+}
+
+// Read every channel under a single session lock and core attach. The timestamp is
+// taken around the block reads only, not the wait for the session lock.
+fn read_tick(plan: &ReadPlan, channels: &[Channel]) -> Tick {
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let Some(mut core) = session_guard.as_mut().and_then(|session| session.core(0).ok()) else {
+        return Tick { timestamp_us: timing::monotonic_us(), values: vec![None; channels.len()], cycle_count: None };
+    };
+    let (timestamp_us, data) = timing::timed(|| plan.read(&mut core));
+
+    let values = channels.iter().enumerate().map(|(index, channel)| {
+        let raw = decode_value(&channel.var_type, plan.bytes(&data, index)?).ok()?;
+        Some(channel.scaling.to_engineering(raw.as_f64()))
+    }).collect();
+    let cycle_count = (plan.len() > channels.len())
+        .then(|| plan.bytes(&data, channels.len()))
+        .flatten()
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()));
+    Tick { timestamp_us, values, cycle_count }
+}
+
+// Settings of one sampler run that stay fixed while it runs
+struct RunConfig {
+    run_id: u64,
+    plan: ReadPlan,
+    channels: Vec<Channel>,
+    cycle_clock: Option<CycleClock>,
+}
+
+fn run(app: AppHandle, config: RunConfig, stop: Arc<AtomicBool>, period_ns: Arc<AtomicU64>, stats: Arc<Mutex<JitterStats>>) {
+    let RunConfig { run_id, plan, channels, mut cycle_clock } = config;
+    let start = Instant::now();
+    let mut next_tick = start;
+    let mut last_flush = start;
+    let mut batch = BatchBuilder::new(run_id, channels.len(), plan.blocks.len(), cycle_clock.is_some());
+
+    while !stop.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        if now < next_tick {
+            std::thread::sleep((next_tick - now).min(MAX_SLEEP));
+            continue;
+        }
+
+        let tick = read_tick(&plan, &channels);
+        stats.lock().unwrap().record(tick.timestamp_us);
+        let target_time_s = cycle_clock.as_mut()
+            .zip(tick.cycle_count)
+            .map(|(clock, count)| clock.seconds(count, tick.timestamp_us));
+        batch.push(tick.timestamp_us, target_time_s, tick.values);
+
+        // Ticks missed while the probe was busy are dropped rather than bursted
+        let period = Duration::from_nanos(period_ns.load(Ordering::Relaxed));
+        next_tick = (next_tick + period).max(Instant::now());
+
+        if last_flush.elapsed() >= BATCH_INTERVAL || batch.len() >= MAX_BATCH_SAMPLES {
+            if let Err(e) = app.emit(SAMPLING_BATCH_EVENT, batch.take()) {
+                eprintln!("Failed to emit sample batch: {}", e);
+            }
+            last_flush = Instant::now();
+        }
+    }
+
+    if batch.len() > 0 {
+        app.emit(SAMPLING_BATCH_EVENT, batch.take()).ok();
+    }
+    eprintln!("Sampler run {} stopped", run_id);
+}
+
+/// Start sampling `variables` at `rate_hz` (0 = as fast as possible), replacing any
+/// running sampler. Variables closer than `max_gap` bytes are read in one block. With
+/// `core_clock_hz`, the DWT cycle counter is enabled and read every tick as target time.
+/// Returns the run id carried by every batch of this run.
+pub(crate) fn start(app: AppHandle, variables: &[VariableInfo], rate_hz: f64, max_gap: Option<u32>, core_clock_hz: Option<f64>) -> Result<u64, String> {
+    let period = sample_period_ns(rate_hz)?;
+    if let Some(core_clock_hz) = core_clock_hz {
+        dwt::validate_core_clock(core_clock_hz)?;
+    }
+    let (plan, channels) = plan_channels(variables, max_gap, core_clock_hz.is_some())?;
+
+    stop();
+
+    if core_clock_hz.is_some() {
+        let mut session_guard = SESSION_MANAGER.lock().unwrap();
+        let session = session_guard.as_mut().ok_or("No active MCU session")?;
+        let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+        dwt::enable_cycle_counter(&mut core)?;
+        eprintln!("DWT cycle counter enabled");
+    }
+
+    let run_id = next_run_id();
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    let period_ns = Arc::new(AtomicU64::new(period));
+    let stats = Arc::new(Mutex::new(JitterStats::new(period as f64 / 1000.0)));
+    eprintln!("Starting sampler run {}: {} variables at {} Hz in {} block reads ({} bytes) per tick",
+        run_id, channels.len(), rate_hz, plan.blocks.len(), plan.total_bytes());
+
+    let thread = {
+        let stop_flag = stop_flag.clone();
+        let period_ns = period_ns.clone();
+        let stats = stats.clone();
+        let config = RunConfig { run_id, plan, channels, cycle_clock: core_clock_hz.map(CycleClock::new) };
+        std::thread::Builder::new()
+            .name("mculink-sampler".to_string())
+            .spawn(move || run(app, config, stop_flag, period_ns, stats))
+            .map_err(|e| format!("Failed to start sampler thread: {}", e))?
+    };
+
+    *SAMPLER.lock().unwrap() = Some(Sampler { run_id, stop: stop_flag, period_ns, stats, thread });
+    Ok(run_id)
+}
+
+/// Change the rate of the running sampler without restarting it. Interval statistics
+/// restart against the new period.
+pub(crate) fn set_rate(rate_hz: f64) -> Result<(), String> {
+    let period = sample_period_ns(rate_hz)?;
+    let sampler = SAMPLER.lock().unwrap();
+    let sampler = sampler.as_ref().ok_or("Sampler is not running")?;
+    sampler.period_ns.store(period, Ordering::Relaxed);
+    *sampler.stats.lock().unwrap() = JitterStats::new(period as f64 / 1000.0);
+    eprintln!("Sampler run {} rate set to {} Hz", sampler.run_id, rate_hz);
+    Ok(())
+}
+
+/// Interval statistics of the running sampler, one entry per stream (empty when idle).
+pub(crate) fn stats() -> Vec<StreamStats> {
+    let sampler = SAMPLER.lock().unwrap();
+    sampler.iter()
+        .map(|sampler| sampler.stats.lock().unwrap().report(sampler.run_id, &format!("sampler run {}", sampler.run_id)))
+        .collect()
+}
+
+/// Stop the running sampler, if any, and wait for its last batch.
+pub(crate) fn stop() -> bool {
+    stop_run(None)
+}
+
+/// Stop the sampler only if it is still running `run_id` (any run if `None`), so a late
+/// stop from a replaced run cannot end its successor.
+pub(crate) fn stop_run(run_id: Option<u64>) -> bool {
+    let sampler = {
+        let mut guard = SAMPLER.lock().unwrap();
+        if run_id.is_some_and(|id| guard.as_ref().is_some_and(|s| s.run_id != id)) {
+            return false;
+        }
+        guard.take()
+    };
+    let Some(sampler) = sampler else {
+        return false;
+    };
+    sampler.stop.store(true, Ordering::Relaxed);
+    if sampler.thread.join().is_err() {
+        eprintln!("Sampler run {} panicked", sampler.run_id);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_rates_to_periods() {
+        assert_eq!(sample_period_ns(0.0), Ok(0));
+        assert_eq!(sample_period_ns(1000.0), Ok(1_000_000));
+        assert_eq!(sample_period_ns(62.5), Ok(16_000_000));
+        assert!(sample_period_ns(-1.0).is_err());
+        assert!(sample_period_ns(f64::NAN).is_err());
+        assert!(sample_period_ns(1e6).is_err());
+    }
+
+    #[test]
+    fn batches_are_column_major_and_reset_on_take() {
+        let mut batch = BatchBuilder::new(7, 2, 1, false);
+        batch.push(0, None, vec![Some(1.0), None]);
+        batch.push(100, None, vec![Some(2.0), Some(0.5)]);
+
+        let taken = batch.take();
+        assert_eq!(taken.run_id, 7);
+        assert_eq!(taken.timestamps_us, vec![0, 100]);
+        assert_eq!(taken.target_times_s, None);
+        assert_eq!(taken.values, vec![vec![Some(1.0), Some(2.0)], vec![None, Some(0.5)]]);
+        assert_eq!(taken.samples_per_second, 10_000.0);
+        assert_eq!(batch.len(), 0);
+        assert_eq!(batch.values.len(), 2);
+
+        // Later batches measure from the last tick of the previous one
+        batch.push(300, None, vec![None, None]);
+        batch.push(500, None, vec![None, None]);
+        assert_eq!(batch.take().samples_per_second, 5_000.0);
+    }
+
+    #[test]
+    fn batches_carry_target_time_when_enabled() {
+        let mut batch = BatchBuilder::new(1, 1, 2, true);
+        batch.push(0, Some(0.0), vec![Some(1.0)]);
+        batch.push(100, None, vec![Some(2.0)]);
+        assert_eq!(batch.take().target_times_s, Some(vec![Some(0.0), None]));
+        assert_eq!(batch.take().target_times_s, Some(vec![]));
+    }
+}
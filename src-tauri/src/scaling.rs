@@ -0,0 +1,149 @@
+// Linear and fixed-point scaling between raw target values and engineering units.
+// engineering = raw / 2^q_format * scale + offset; writes invert this, rounding and
+// saturating to the variable's integer type.
+
+use serde::{Deserialize, Serialize};
+
+use crate::layout::{encode_value, var_type_size};
+use crate::VariableInfo;
+
+// Integers beyond 2^53 cannot be written through f64 (see encode_value)
+const MAX_EXACT_F64_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Scaling metadata of a variable, as passed to the read/write commands.
+/// Missing fields leave the value unchanged (scale 1, offset 0, no fractional bits).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+pub struct Scaling {
+    #[serde(default)]
+    pub scale: Option<f64>,
+    #[serde(default)]
+    pub offset: Option<f64>,
+    #[serde(default)]
+    pub q_format: Option<u8>, // Number of fractional bits (Qn)
+}
+
+impl Scaling {
+    pub fn of(variable: &VariableInfo) -> Self {
+        Scaling {
+            scale: variable.scale,
+            offset: variable.offset,
+            q_format: variable.q_format,
+        }
+    }
+
+    pub fn is_identity(self) -> bool {
+        self.scale.is_none_or(|s| s == 1.0) && self.offset.is_none_or(|o| o == 0.0) && self.q_format.is_none_or(|q| q == 0)
+    }
+
+    // Engineering units per raw count, before the offset
+    fn factor(self) -> f64 {
+        self.scale.unwrap_or(1.0) / 2f64.powi(self.q_format.unwrap_or(0) as i32)
+    }
+
+    /// Convert a raw value read from the target into engineering units.
+    pub fn to_engineering(self, raw: f64) -> f64 {
+        raw * self.factor() + self.offset.unwrap_or(0.0)
+    }
+
+    /// Convert engineering units into the raw value to write, rounded to the nearest
+    /// integer and saturated to the range of integer types.
+    pub fn to_raw(self, var_type: &str, value: f64) -> Result<f64, String> {
+        if !value.is_finite() {
+            return Err(format!("Cannot write {} to a scaled variable", value));
+        }
+        let raw = (value - self.offset.unwrap_or(0.0)) / self.factor();
+        let (min, max) = match var_type {
+            "UINT8" => (0.0, u8::MAX as f64),
+            "INT8" => (i8::MIN as f64, i8::MAX as f64),
+            "UINT16" => (0.0, u16::MAX as f64),
+            "INT16" => (i16::MIN as f64, i16::MAX as f64),
+            "UINT32" => (0.0, u32::MAX as f64),
+            "INT32" => (i32::MIN as f64, i32::MAX as f64),
+            "UINT64" => (0.0, MAX_EXACT_F64_INTEGER),
+            "INT64" => (-MAX_EXACT_F64_INTEGER, MAX_EXACT_F64_INTEGER),
+            "FLOAT" => return Ok(raw.clamp(f32::MIN as f64, f32::MAX as f64)),
+            "DOUBLE" => return Ok(raw),
+            _ => return Err(format!("{} variables cannot be scaled", var_type)),
+        };
+        Ok(raw.round().clamp(min, max))
+    }
+
+    /// Encode a value in engineering units as target memory (see layout::encode_value).
+    pub fn encode(self, var_type: &str, value: f64) -> Result<Vec<u8>, String> {
+        if self.is_identity() {
+            return encode_value(var_type, value);
+        }
+        encode_value(var_type, self.to_raw(var_type, value)?)
+    }
+}
+
+/// Check that a variable's scaling metadata is usable for its type.
+pub fn validate_scaling(variable: &VariableInfo) -> Result<(), String> {
+    let scaling = Scaling::of(variable);
+    if scaling.is_identity() {
+        return Ok(());
+    }
+    if matches!(variable.var_type.as_str(), "BOOL" | "STRING") {
+        return Err(format!("{} variables cannot be scaled", variable.var_type));
+    }
+    if let Some(scale) = scaling.scale {
+        if !scale.is_finite() || scale == 0.0 {
+            return Err(format!("Scale {} is not a finite, nonzero number", scale));
+        }
+    }
+    if scaling.offset.is_some_and(|o| !o.is_finite()) {
+        return Err("Offset is not a finite number".to_string());
+    }
+    if let Some(q) = scaling.q_format.filter(|&q| q > 0) {
+        let bits = var_type_size(&variable.var_type)? * 8;
+        if matches!(variable.var_type.as_str(), "FLOAT" | "DOUBLE") || q as usize >= bits {
+            return Err(format!("Q{} format does not fit a {}", q, variable.var_type));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scaling(scale: Option<f64>, offset: Option<f64>, q_format: Option<u8>) -> Scaling {
+        Scaling { scale, offset, q_format }
+    }
+
+    #[test]
+    fn converts_q15_both_ways() {
+        let q15 = scaling(None, None, Some(15));
+        assert_eq!(q15.to_engineering(16384.0), 0.5);
+        assert_eq!(q15.to_engineering(-32768.0), -1.0);
+        assert_eq!(q15.to_raw("INT16", 0.5), Ok(16384.0));
+        // +1.0 is not representable in Q15 and saturates
+        assert_eq!(q15.to_raw("INT16", 1.0), Ok(32767.0));
+    }
+
+    #[test]
+    fn converts_adc_counts_with_scale_and_offset() {
+        // 12-bit ADC over 3.3 V, centred on 1.65 V
+        let adc = scaling(Some(3.3 / 4096.0), Some(-1.65), None);
+        assert!((adc.to_engineering(2048.0) - 0.0).abs() < 1e-12);
+        assert_eq!(adc.to_raw("UINT16", 0.0), Ok(2048.0));
+        // Rounds to the nearest count and saturates at the type limits
+        assert_eq!(adc.to_raw("UINT16", 0.0005), Ok(2049.0));
+        assert_eq!(adc.to_raw("UINT16", -5.0), Ok(0.0));
+    }
+
+    #[test]
+    fn leaves_float_writes_unrounded() {
+        let gain = scaling(Some(2.0), None, None);
+        assert_eq!(gain.to_raw("FLOAT", 1.0), Ok(0.5));
+        assert!(gain.to_raw("BOOL", 1.0).is_err());
+        assert!(gain.to_raw("INT32", f64::NAN).is_err());
+    }
+
+    #[test]
+    fn identity_when_unset() {
+        assert!(Scaling::default().is_identity());
+        assert!(scaling(Some(1.0), Some(0.0), Some(0)).is_identity());
+        assert!(!scaling(None, None, Some(31)).is_identity());
+    }
+}
@@ -0,0 +1,230 @@
+// SWO trace: configures the TPIU/SWO output and the ITM through probe-rs, then polls
+// the trace data the probe captured. Stimulus port writes are sent to the frontend as
+// "swo-data" events; DWT data trace of watched variables is decoded into
+// "sampling-batch" events. Data trace is non-intrusive: the core is never halted and
+// no debug reads compete with the firmware for the bus.
+
+use probe_rs::architecture::arm::component::TraceSink;
+use probe_rs::architecture::arm::SwoConfig;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::itm::{DataTraceStream, ItmDecoder, ItmEvent};
+use crate::sampler::{self, SAMPLING_BATCH_EVENT};
+use crate::timing::{self, StreamStats};
+use crate::{dwt, VariableInfo, SESSION_MANAGER};
+
+pub(crate) const SWO_DATA_EVENT: &str = "swo-data";
+pub(crate) const DEFAULT_BAUD: u32 = 1_000_000; // probe-rs default, supported by most probes
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+static SWO: Mutex<Option<SwoTrace>> = Mutex::new(None);
+
+struct SwoTrace {
+    stream: Arc<Mutex<Option<DataTraceStream>>>,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+/// Bytes written to one ITM stimulus port, as emitted with the "swo-data" event.
+#[derive(Debug, Serialize, Clone)]
+pub struct SwoData {
+    pub port: u8,
+    pub bytes: Vec<u8>,
+    pub timestamp_us: u64, // Host monotonic time of the read
+}
+
+fn poll(app: AppHandle, stream: Arc<Mutex<Option<DataTraceStream>>>, stop: Arc<AtomicBool>) {
+    let mut decoder = ItmDecoder::default();
+    while !stop.load(Ordering::Relaxed) {
+        let read = {
+            let mut session_guard = SESSION_MANAGER.lock().unwrap();
+            match session_guard.as_mut() {
+                Some(session) => {
+                    let (timestamp_us, data) = timing::timed(|| session.read_trace_data());
+                    data.map(|bytes| (timestamp_us, bytes)).map_err(|e| format!("Failed to read SWO data: {}", e))
+                },
+                None => Err("No active MCU session".to_string()),
+            }
+        };
+        let (timestamp_us, bytes) = match read {
+            Ok(read) => read,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            },
+        };
+
+        // Consecutive writes to one port are merged into one event
+        let mut text: Vec<SwoData> = Vec::new();
+        let mut stream = stream.lock().unwrap();
+        for event in decoder.feed(&bytes) {
+            match event {
+                ItmEvent::Instrumentation { port, payload } => match text.last_mut() {
+                    Some(data) if data.port == port => data.bytes.extend(payload),
+                    _ => text.push(SwoData { port, bytes: payload, timestamp_us }),
+                },
+                event => {
+                    if let Some(stream) = stream.as_mut() {
+                        stream.event(&event, timestamp_us);
+                    }
+                },
+            }
+        }
+        if let Some(stream) = stream.as_mut() {
+            if stream.batch.len() > 0 {
+                if let Err(e) = app.emit(SAMPLING_BATCH_EVENT, stream.batch.take()) {
+                    eprintln!("Failed to emit sample batch: {}", e);
+                }
+            }
+        }
+        drop(stream);
+
+        for data in text {
+            if let Err(e) = app.emit(SWO_DATA_EVENT, data) {
+                eprintln!("Failed to emit SWO data: {}", e);
+            }
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    eprintln!("SWO poller stopped");
+}
+
+/// Configure SWO output at `baud` (UART encoding) and the ITM with local timestamps,
+/// and start capturing, replacing any previous capture. The TPIU is assumed to run
+/// at the core clock, `core_clock_hz`.
+pub(crate) fn start(app: AppHandle, core_clock_hz: f64, baud: u32) -> Result<(), String> {
+    dwt::validate_core_clock(core_clock_hz)?;
+    if baud == 0 || baud as f64 > core_clock_hz {
+        return Err(format!("SWO baud rate {} is not between 1 and the core clock ({} Hz)", baud, core_clock_hz));
+    }
+    stop();
+
+    {
+        let mut session_guard = SESSION_MANAGER.lock().unwrap();
+        let session = session_guard.as_mut().ok_or("No active MCU session")?;
+        let config = SwoConfig::new(core_clock_hz as u32).set_baud(baud);
+        session.setup_tracing(0, TraceSink::Swo(config))
+            .map_err(|e| format!("Failed to configure SWO trace: {}", e))?;
+    }
+    eprintln!("SWO trace at {} baud, core clock {} Hz", baud, core_clock_hz);
+
+    let stream = Arc::new(Mutex::new(None));
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread = {
+        let stream = stream.clone();
+        let stop = stop.clone();
+        std::thread::Builder::new()
+            .name("mculink-swo".to_string())
+            .spawn(move || poll(app, stream, stop))
+            .map_err(|e| format!("Failed to start SWO thread: {}", e))?
+    };
+
+    *SWO.lock().unwrap() = Some(SwoTrace { stream, stop, thread });
+    Ok(())
+}
+
+// Stop data trace on the first `units` DWT comparators
+fn remove_data_trace(units: usize) {
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    let Some(session) = session_guard.as_mut() else {
+        return;
+    };
+    for unit in 0..units {
+        if let Err(e) = session.remove_swv_data_trace(unit) {
+            eprintln!("Failed to stop data trace on DWT comparator {}: {}", unit, e);
+        }
+    }
+}
+
+/// Watch up to four `variables` with DWT data trace, one comparator each, replacing any
+/// watched before. Returns the run id carried by their sample batches.
+pub(crate) fn start_stream(variables: &[VariableInfo], core_clock_hz: Option<f64>) -> Result<u64, String> {
+    let swo = SWO.lock().unwrap();
+    let swo = swo.as_ref().ok_or("SWO trace is not running")?;
+    if let Some(core_clock_hz) = core_clock_hz {
+        dwt::validate_core_clock(core_clock_hz)?;
+    }
+    let run_id = sampler::next_run_id();
+    let stream = DataTraceStream::new(run_id, variables, core_clock_hz)?;
+
+    let previous = swo.stream.lock().unwrap().take();
+    if let Some(previous) = previous {
+        remove_data_trace(previous.channels());
+    }
+    {
+        let mut session_guard = SESSION_MANAGER.lock().unwrap();
+        let session = session_guard.as_mut().ok_or("No active MCU session")?;
+        let comparators = {
+            let mut core = session.core(0).map_err(|e| format!("Failed to get core: {}", e))?;
+            dwt::comparator_count(&mut core)?
+        };
+        if variables.len() > comparators {
+            return Err(format!("This core has {} DWT comparators for {} variables", comparators, variables.len()));
+        }
+        for (unit, variable) in variables.iter().enumerate() {
+            session.add_swv_data_trace(unit, variable.address)
+                .map_err(|e| format!("Failed to trace {} on DWT comparator {}: {}", variable.name, unit, e))?;
+        }
+    }
+
+    *swo.stream.lock().unwrap() = Some(stream);
+    eprintln!("SWO data trace of {} variables as run {}", variables.len(), run_id);
+    Ok(run_id)
+}
+
+/// Stop data trace; with `run_id`, only if it is still the active stream.
+pub(crate) fn stop_stream(run_id: Option<u64>) -> bool {
+    let swo = SWO.lock().unwrap();
+    let Some(swo) = swo.as_ref() else {
+        return false;
+    };
+    let mut stream = swo.stream.lock().unwrap();
+    let Some(active) = stream.as_ref() else {
+        return false;
+    };
+    if run_id.is_some_and(|id| active.run_id != id) {
+        return false;
+    }
+    let stream = stream.take().unwrap();
+    eprintln!("SWO data trace {} stopped after {} overflows", stream.run_id, stream.overflows());
+    remove_data_trace(stream.channels());
+    true
+}
+
+/// Interval statistics and overflows of the data trace stream.
+pub(crate) fn stream_stats() -> Vec<StreamStats> {
+    let swo = SWO.lock().unwrap();
+    let Some(swo) = swo.as_ref() else {
+        return Vec::new();
+    };
+    let stream = swo.stream.lock().unwrap();
+    stream.iter().map(DataTraceStream::report).collect()
+}
+
+/// Stop capturing and turn trace generation off. Returns whether SWO was running.
+pub(crate) fn stop() -> bool {
+    let Some(swo) = SWO.lock().unwrap().take() else {
+        return false;
+    };
+    swo.stop.store(true, Ordering::Relaxed);
+    if swo.thread.join().is_err() {
+        eprintln!("SWO poller panicked");
+    }
+    if let Some(stream) = swo.stream.lock().unwrap().take() {
+        remove_data_trace(stream.channels());
+    }
+
+    let mut session_guard = SESSION_MANAGER.lock().unwrap();
+    if let Some(session) = session_guard.as_mut() {
+        if let Err(e) = session.disable_swv(0) {
+            eprintln!("Failed to disable SWO trace: {}", e);
+        }
+    }
+    true
+}
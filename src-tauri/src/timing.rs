@@ -0,0 +1,154 @@
+// Host monotonic timestamps and sampling interval statistics.
+// All timestamps produced by the backend share one epoch, so samples of different
+// streams (and single reads) can be compared directly.
+
+use serde::Serialize;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+static EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// Microseconds on the host monotonic clock since the backend first took a timestamp.
+pub(crate) fn monotonic_us() -> u64 {
+    EPOCH.get_or_init(Instant::now).elapsed().as_micros() as u64
+}
+
+/// Run a probe transaction and timestamp it at the midpoint of its start and end, which
+/// is the best host-side estimate of when the target memory was actually sampled.
+pub(crate) fn timed<T>(transaction: impl FnOnce() -> T) -> (u64, T) {
+    let before = monotonic_us();
+    let result = transaction();
+    let after = monotonic_us();
+    (before + (after - before) / 2, result)
+}
+
+/// A single value and the host monotonic time it was read at.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+pub struct TimedValue {
+    pub value: f64,
+    pub timestamp_us: u64,
+}
+
+/// Values of several variables read in one transaction, in request order.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct TimedValues {
+    pub values: Vec<Option<f64>>,
+    pub timestamp_us: u64,
+}
+
+/// Interval statistics of one sample stream.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct StreamStats {
+    pub run_id: u64,             // Run id carried by the stream's sample batches
+    pub stream: String,          // e.g. "sampler run 3"
+    pub target_interval_us: f64, // 0 when running as fast as possible
+    pub samples: u64,
+    pub min_interval_us: f64,
+    pub max_interval_us: f64,
+    pub mean_interval_us: f64,
+    pub stddev_interval_us: f64,
+    pub missed_deadlines: u64, // Ticks that could not be taken on schedule
+}
+
+// Running interval statistics (Welford's algorithm, so no samples are kept)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JitterStats {
+    target_interval_us: f64,
+    samples: u64,
+    last_us: Option<u64>,
+    intervals: u64,
+    min: f64,
+    max: f64,
+    mean: f64,
+    m2: f64,
+    missed: u64,
+}
+
+impl JitterStats {
+    pub fn new(target_interval_us: f64) -> Self {
+        JitterStats { target_interval_us, ..Default::default() }
+    }
+
+    /// Record a sample taken at `timestamp_us`.
+    pub fn record(&mut self, timestamp_us: u64) {
+        self.samples += 1;
+        let Some(last) = self.last_us.replace(timestamp_us) else { return };
+        let interval = timestamp_us.saturating_sub(last) as f64;
+
+        self.intervals += 1;
+        if self.intervals == 1 {
+            self.min = interval;
+            self.max = interval;
+        } else {
+            self.min = self.min.min(interval);
+            self.max = self.max.max(interval);
+        }
+        let delta = interval - self.mean;
+        self.mean += delta / self.intervals as f64;
+        self.m2 += delta * (interval - self.mean);
+
+        // An interval of more than 1.5 periods means at least one tick was skipped
+        if self.target_interval_us > 0.0 && interval > 1.5 * self.target_interval_us {
+            self.missed += (interval / self.target_interval_us).round() as u64 - 1;
+        }
+    }
+
+    pub fn report(&self, run_id: u64, stream: &str) -> StreamStats {
+        StreamStats {
+            run_id,
+            stream: stream.to_string(),
+            target_interval_us: self.target_interval_us,
+            samples: self.samples,
+            min_interval_us: self.min,
+            max_interval_us: self.max,
+            mean_interval_us: self.mean,
+            stddev_interval_us: if self.intervals > 1 { (self.m2 / (self.intervals - 1) as f64).sqrt() } else { 0.0 },
+            missed_deadlines: self.missed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_interval_statistics() {
+        let mut stats = JitterStats::new(100.0);
+        for timestamp in [0, 100, 190, 310, 400] {
+            stats.record(timestamp);
+        }
+        let report = stats.report(1, "test");
+        assert_eq!(report.samples, 5);
+        assert_eq!(report.min_interval_us, 90.0);
+        assert_eq!(report.max_interval_us, 120.0);
+        assert_eq!(report.mean_interval_us, 100.0);
+        // Sample stddev of 100, 90, 120, 90
+        assert!((report.stddev_interval_us - 14.142).abs() < 1e-3);
+        assert_eq!(report.missed_deadlines, 0);
+    }
+
+    #[test]
+    fn counts_skipped_ticks_as_missed_deadlines() {
+        let mut stats = JitterStats::new(100.0);
+        for timestamp in [0, 100, 400, 500] {
+            stats.record(timestamp);
+        }
+        assert_eq!(stats.report(1, "test").missed_deadlines, 2);
+
+        // Free-running streams have no deadlines
+        let mut free = JitterStats::new(0.0);
+        free.record(0);
+        free.record(1_000);
+        assert_eq!(free.report(1, "test").missed_deadlines, 0);
+    }
+
+    #[test]
+    fn timestamps_fall_inside_the_transaction() {
+        let before = monotonic_us();
+        let (timestamp, ()) = timed(|| std::thread::sleep(std::time::Duration::from_millis(2)));
+        let after = monotonic_us();
+        assert!(before <= timestamp && timestamp <= after);
+        assert!(timestamp >= before + 1_000);
+    }
+}
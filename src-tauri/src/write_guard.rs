@@ -0,0 +1,490 @@
+// Server-side write safety. Writes are checked against the metadata of the variables
+// discovered on the target instead of trusting the UI: memory outside every
+// discovered variable is never written, RO variables reject writes,
+// values outside min_value..max_value are rejected or clamped, and an optional rate
+// limit bounds how fast a variable may change. Rate limits are kept by variable name,
+// so they survive rebuilds of the firmware and reconnects. Every write command goes
+// through `guard`, and values are converted with the scaling registered here, never
+// with scaling sent by the client.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::layout::var_type_size;
+use crate::scaling::Scaling;
+use crate::VariableInfo;
+
+const MAX_RATE_WINDOW_S: f64 = 1.0; // A write after a pause may change by one second's worth
+
+/// What happens to a write beyond a variable's range or rate limit.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitPolicy {
+    #[default]
+    Reject,
+    Clamp,
+}
+
+struct Guard {
+    variables: BTreeMap<String, VariableInfo>, // Discovered variables by name
+    policy: LimitPolicy,
+    rate_limits: BTreeMap<String, f64>,     // Maximum change per second by name
+    last_writes: BTreeMap<String, Instant>, // Time of the last rate limited write by name
+}
+
+static GUARD: Mutex<Guard> = Mutex::new(Guard {
+    variables: BTreeMap::new(),
+    policy: LimitPolicy::Reject,
+    rate_limits: BTreeMap::new(),
+    last_writes: BTreeMap::new(),
+});
+
+/// Rate limit of one write: the variable's current value and how long ago it was last
+/// written through the limit (None if never).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct RateLimit {
+    pub max_change_per_s: f64,
+    pub current: f64,
+    pub elapsed_s: Option<f64>,
+}
+
+/// A write that passed the guard: the values to write, clamped under
+/// `LimitPolicy::Clamp`, and the discovered variables it falls in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Guarded {
+    pub values: Vec<f64>,
+    pub clamped_from: Option<f64>, // First value that was clamped, as requested
+    variables: Vec<String>,
+}
+
+impl Guarded {
+    /// Note that the write succeeded, so the next one is rate limited from now.
+    pub(crate) fn record(&self) {
+        self.record_at(Instant::now());
+    }
+
+    fn record_at(&self, now: Instant) {
+        let mut guard = GUARD.lock().unwrap();
+        for name in &self.variables {
+            if guard.rate_limits.contains_key(name) {
+                guard.last_writes.insert(name.clone(), now);
+            }
+        }
+    }
+}
+
+/// Remember the variables found by a discovery, replacing earlier entries of the same name.
+pub(crate) fn register(variables: &[VariableInfo]) {
+    let mut guard = GUARD.lock().unwrap();
+    for variable in variables {
+        guard.variables.insert(variable.name.clone(), variable.clone());
+    }
+}
+
+/// Apply a decoding config (see decoding::apply_decoding_config) to the discovered
+/// variables, so writes are checked with the scaling it sets.
+pub(crate) fn apply_decoding_config(config_json: &str) -> Result<(), String> {
+    let mut guard = GUARD.lock().unwrap();
+    let variables = guard.variables.values().cloned().collect();
+    for variable in crate::decoding::apply_decoding_config(config_json, variables)? {
+        guard.variables.insert(variable.name.clone(), variable);
+    }
+    Ok(())
+}
+
+/// Forget the discovered variables of a session that ended.
+pub(crate) fn clear() {
+    GUARD.lock().unwrap().variables.clear();
+}
+
+pub(crate) fn set_policy(policy: LimitPolicy) {
+    eprintln!("Out-of-limit writes are now {:?}", policy);
+    GUARD.lock().unwrap().policy = policy;
+}
+
+pub(crate) fn policy() -> LimitPolicy {
+    GUARD.lock().unwrap().policy
+}
+
+/// Limit how fast variable `name` may change, in engineering units per second, or
+/// remove its limit. Returns every rate limit.
+pub(crate) fn set_rate_limit(name: &str, max_change_per_s: Option<f64>) -> Result<BTreeMap<String, f64>, String> {
+    let mut guard = GUARD.lock().unwrap();
+    match max_change_per_s {
+        Some(rate) if !rate.is_finite() || rate <= 0.0 => {
+            return Err(format!("Rate limit {} of {} is not a positive number", rate, name));
+        },
+        Some(rate) => {
+            guard.rate_limits.insert(name.to_string(), rate);
+        },
+        None => {
+            guard.rate_limits.remove(name);
+            guard.last_writes.remove(name);
+        },
+    }
+    Ok(guard.rate_limits.clone())
+}
+
+/// The discovered variable of the same name and address as `variable`, whose labels,
+/// bit fields and limits are used instead of the client's; `variable` if there is none.
+pub(crate) fn discovered(variable: VariableInfo) -> VariableInfo {
+    let guard = GUARD.lock().unwrap();
+    match guard.variables.get(&variable.name) {
+        Some(registered) if registered.address == variable.address => registered.clone(),
+        _ => variable,
+    }
+}
+
+/// The discovered variable containing `address`, if any.
+pub(crate) fn variable_at(address: u32) -> Option<VariableInfo> {
+    let guard = GUARD.lock().unwrap();
+    guard.variables.values()
+        .find(|variable| variable_range(variable).is_some_and(|(start, end)| (start..end).contains(&(address as u64))))
+        .cloned()
+}
+
+// Address range of a variable; None for unknown types
+fn variable_range(variable: &VariableInfo) -> Option<(u64, u64)> {
+    let size = crate::layout::var_type_size(&variable.var_type).ok()? as u64;
+    let start = variable.address as u64;
+    Some((start, start + size * variable.element_count as u64))
+}
+
+/// Check a write of `var_type` data covering `len` bytes at `address`, holding `values`
+/// in engineering units (none for text), against every discovered variable it touches,
+/// in this order: RO variables reject it, values outside the range are rejected or
+/// clamped by the limit policy, then so are changes faster than the rate limit.
+/// `current` reads the values at `address` of a rate limited variable. Call
+/// `Guarded::record` once the write succeeded. Writes outside every discovered
+/// variable are rejected.
+pub(crate) fn guard(address: u32, var_type: &str, len: usize, values: &[f64], current: impl FnMut(&VariableInfo) -> Result<Vec<f64>, String>) -> Result<Guarded, String> {
+    guard_at(policy(), Instant::now(), address, var_type, len, values, current)
+}
+
+fn guard_at(
+    policy: LimitPolicy,
+    now: Instant,
+    address: u32,
+    var_type: &str,
+    len: usize,
+    values: &[f64],
+    mut current: impl FnMut(&VariableInfo) -> Result<Vec<f64>, String>,
+) -> Result<Guarded, String> {
+    let (start, end) = (address as u64, address as u64 + len as u64);
+    let (variables, rates) = {
+        let guard = GUARD.lock().unwrap();
+        let variables: Vec<VariableInfo> = guard.variables.values()
+            .filter(|variable| variable_range(variable).is_some_and(|(v_start, v_end)| start < v_end && v_start < end))
+            .cloned()
+            .collect();
+        if variables.is_empty() {
+            return Err(undiscovered(address));
+        }
+        if let Some(variable) = variables.iter().find(|variable| variable.access_flags == "RO") {
+            return Err(format!("{} is read-only", variable.name));
+        }
+        let rates: Vec<Option<(f64, Option<f64>)>> = variables.iter()
+            .map(|variable| {
+                let rate = *guard.rate_limits.get(&variable.name)?;
+                let elapsed_s = guard.last_writes.get(&variable.name).map(|time| now.saturating_duration_since(*time).as_secs_f64());
+                Some((rate, elapsed_s))
+            })
+            .collect();
+        (variables, rates)
+    };
+
+    let mut values = values.to_vec();
+    let mut clamped_from = None;
+    for (variable, rate) in variables.iter().zip(rates) {
+        check_layout(variable, address, var_type, len, values.len())?;
+        let current = match rate {
+            Some(_) if !values.is_empty() => current(variable)?,
+            _ => Vec::new(),
+        };
+        for (index, value) in values.iter_mut().enumerate() {
+            let rate = match rate {
+                Some((max_change_per_s, elapsed_s)) => {
+                    let current = *current.get(index).ok_or_else(|| format!("Could not read the current value of {}", variable.name))?;
+                    Some(RateLimit { max_change_per_s, current, elapsed_s })
+                },
+                None => None,
+            };
+            let limited = apply_limits(variable, policy, *value, rate)?;
+            if limited != *value {
+                eprintln!("Clamped write of {} from {} to {}", variable.name, value, limited);
+                clamped_from.get_or_insert(*value);
+                *value = limited;
+            }
+        }
+    }
+    Ok(Guarded { values, clamped_from, variables: variables.into_iter().map(|variable| variable.name).collect() })
+}
+
+// A write must lie inside each variable it touches, with the variable's type and on
+// element boundaries, so its values are the values the variable will hold. Only
+// STRING variables take text.
+fn check_layout(variable: &VariableInfo, address: u32, var_type: &str, len: usize, value_count: usize) -> Result<(), String> {
+    let size = var_type_size(&variable.var_type)?;
+    let (v_start, v_end) = variable_range(variable).ok_or_else(|| format!("Unsupported variable type: {}", variable.var_type))?;
+    let (start, end) = (address as u64, address as u64 + len as u64);
+    let is_text = variable.var_type == "STRING";
+    if var_type != variable.var_type || start < v_start || end > v_end || !(start - v_start).is_multiple_of(size as u64)
+        || (!is_text && value_count * size != len) {
+        return Err(format!("Write of {} {} bytes at 0x{:08X} does not match {} ({} x {} at 0x{:08X})",
+            len, var_type, address, variable.name, variable.element_count, variable.var_type, variable.address));
+    }
+    Ok(())
+}
+
+fn undiscovered(address: u32) -> String {
+    format!("0x{:08X} is not part of a discovered variable; discover variables before writing", address)
+}
+
+/// Scaling of the discovered variable at `address`, to use instead of the scaling a
+/// client sends. Scaling that differs from it is rejected; none means the registered one.
+pub(crate) fn scaling_at(address: u32, requested: Option<Scaling>) -> Result<Scaling, String> {
+    let variable = variable_at(address).ok_or_else(|| undiscovered(address))?;
+    let scaling = Scaling::of(&variable);
+    match requested {
+        Some(requested) if requested != scaling && !(requested.is_identity() && scaling.is_identity()) => {
+            Err(format!("Scaling {:?} does not match the scaling {:?} of {}", requested, scaling, variable.name))
+        },
+        _ => Ok(scaling),
+    }
+}
+
+/// Apply the range and rate limits of `variable` to a value in engineering units.
+/// Returns the value to write, clamped under `LimitPolicy::Clamp`.
+pub(crate) fn apply_limits(variable: &VariableInfo, policy: LimitPolicy, value: f64, rate: Option<RateLimit>) -> Result<f64, String> {
+    let mut value = value;
+    let (min, max) = (variable.min_value.unwrap_or(f64::NEG_INFINITY), variable.max_value.unwrap_or(f64::INFINITY));
+    if !(min..=max).contains(&value) {
+        if policy == LimitPolicy::Reject || value.is_nan() {
+            return Err(format!("{} is outside the range {}..{} of {}", value, min, max, variable.name));
+        }
+        value = value.clamp(min, max);
+    }
+
+    if let Some(rate) = rate {
+        let window_s = rate.elapsed_s.unwrap_or(MAX_RATE_WINDOW_S).min(MAX_RATE_WINDOW_S);
+        let max_step = rate.max_change_per_s * window_s;
+        if (value - rate.current).abs() > max_step {
+            if policy == LimitPolicy::Reject {
+                return Err(format!("Changing {} from {} to {} exceeds its rate limit of {}/s", variable.name, rate.current, value, rate.max_change_per_s));
+            }
+            value = value.clamp(rate.current - max_step, rate.current + max_step);
+        }
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(min_value: Option<f64>, max_value: Option<f64>) -> VariableInfo {
+        VariableInfo {
+            name: "speed_setpoint".to_string(),
+            parent: None,
+            address: 0x2000_0000,
+            var_type: "FLOAT".to_string(),
+            access_flags: "RW".to_string(),
+            category: None,
+            min_value,
+            max_value,
+            element_count: 1,
+            enum_labels: Vec::new(),
+            bit_fields: Vec::new(),
+            scale: None,
+            offset: None,
+            q_format: None,
+            unit: None,
+        }
+    }
+
+    // Register a variable of its own; tests share the guard, so each uses its own
+    // name and addresses
+    fn discover(name: &str, address: u32, var_type: &str, element_count: u32, access_flags: &str, min_value: Option<f64>, max_value: Option<f64>) -> VariableInfo {
+        let variable = VariableInfo {
+            name: name.to_string(),
+            address,
+            var_type: var_type.to_string(),
+            access_flags: access_flags.to_string(),
+            element_count,
+            ..variable(min_value, max_value)
+        };
+        register(std::slice::from_ref(&variable));
+        variable
+    }
+
+    // Current values for a write without a rate limit, which must not read the target
+    fn unread(_: &VariableInfo) -> Result<Vec<f64>, String> {
+        Err("current value read without a rate limit".to_string())
+    }
+
+    #[test]
+    fn range_limits_reject_or_clamp() {
+        let setpoint = variable(Some(0.0), Some(3000.0));
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Reject, 1500.0, None), Ok(1500.0));
+        assert!(apply_limits(&setpoint, LimitPolicy::Reject, 3500.0, None).is_err());
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Clamp, 3500.0, None), Ok(3000.0));
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Clamp, -1.0, None), Ok(0.0));
+        assert!(apply_limits(&setpoint, LimitPolicy::Clamp, f64::NAN, None).is_err());
+        // Without metadata every value is in range
+        assert_eq!(apply_limits(&variable(None, None), LimitPolicy::Reject, -1e9, None), Ok(-1e9));
+    }
+
+    #[test]
+    fn rate_limits_scale_with_time_since_the_last_write() {
+        let setpoint = variable(Some(0.0), Some(3000.0));
+        let rate = |elapsed_s| Some(RateLimit { max_change_per_s: 100.0, current: 1000.0, elapsed_s });
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Reject, 1050.0, rate(Some(0.5))), Ok(1050.0));
+        assert!(apply_limits(&setpoint, LimitPolicy::Reject, 1050.0, rate(Some(0.1))).is_err());
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Clamp, 900.0, rate(Some(0.1))), Ok(990.0));
+        // A first write, or one after a long pause, may change by one second's worth
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Clamp, 3000.0, rate(None)), Ok(1100.0));
+        assert_eq!(apply_limits(&setpoint, LimitPolicy::Clamp, 3000.0, rate(Some(60.0))), Ok(1100.0));
+    }
+
+    #[test]
+    fn guard_rejects_or_clamps_at_each_bound() {
+        discover("guard_bounds", 0x2000_1000, "FLOAT", 1, "RW", Some(0.0), Some(100.0));
+        let write = |policy, value| guard_at(policy, Instant::now(), 0x2000_1000, "FLOAT", 4, &[value], unread);
+        for policy in [LimitPolicy::Reject, LimitPolicy::Clamp] {
+            assert_eq!(write(policy, 0.0).unwrap().values, vec![0.0]);
+            assert_eq!(write(policy, 100.0).unwrap().values, vec![100.0]);
+            assert_eq!(write(policy, 100.0).unwrap().clamped_from, None);
+        }
+        assert!(write(LimitPolicy::Reject, -0.5).is_err());
+        assert!(write(LimitPolicy::Reject, 100.5).is_err());
+        let low = write(LimitPolicy::Clamp, -0.5).unwrap();
+        assert_eq!((low.values, low.clamped_from), (vec![0.0], Some(-0.5)));
+        let high = write(LimitPolicy::Clamp, 100.5).unwrap();
+        assert_eq!((high.values, high.clamped_from), (vec![100.0], Some(100.5)));
+    }
+
+    #[test]
+    fn guard_rejects_writes_touching_read_only_variables() {
+        discover("guard_read_only", 0x2000_1100, "UINT32", 1, "RO", None, None);
+        for policy in [LimitPolicy::Reject, LimitPolicy::Clamp] {
+            let error = guard_at(policy, Instant::now(), 0x2000_1100, "UINT32", 4, &[1.0], unread).unwrap_err();
+            assert!(error.contains("read-only"), "{}", error);
+        }
+        // Also when the write only overlaps it
+        assert!(guard_at(LimitPolicy::Reject, Instant::now(), 0x2000_10FE, "UINT32", 4, &[1.0], unread).unwrap_err().contains("read-only"));
+    }
+
+    #[test]
+    fn guard_rejects_writes_that_overlap_a_variable() {
+        discover("guard_layout", 0x2000_1200, "UINT16", 4, "RW", Some(0.0), Some(1000.0));
+        let write = |address, var_type, values: &[f64]| {
+            let len = var_type_size(var_type).unwrap() * values.len();
+            guard_at(LimitPolicy::Clamp, Instant::now(), address, var_type, len, values, unread)
+        };
+        assert_eq!(write(0x2000_1204, "UINT16", &[1.0, 2.0]).unwrap().values, vec![1.0, 2.0]);
+        // Starting before it, past its end, between elements or with another type
+        assert!(write(0x2000_11FE, "UINT32", &[1.0]).is_err());
+        assert!(write(0x2000_1206, "UINT16", &[1.0, 2.0]).is_err());
+        assert!(write(0x2000_1201, "UINT16", &[1.0]).is_err());
+        assert!(write(0x2000_1200, "INT16", &[1.0]).is_err());
+        // Memory outside every discovered variable is not written at all
+        assert!(write(0x2000_1208, "UINT32", &[5000.0]).unwrap_err().contains("not part of a discovered variable"));
+    }
+
+    #[test]
+    fn guard_rate_limit_resets_over_time() {
+        discover("guard_rate", 0x2000_1300, "FLOAT", 1, "RW", None, None);
+        set_rate_limit("guard_rate", Some(10.0)).unwrap();
+        let start = Instant::now();
+        let write = |policy, seconds: f64, current: f64, value| {
+            let now = start + std::time::Duration::from_secs_f64(seconds);
+            guard_at(policy, now, 0x2000_1300, "FLOAT", 4, &[value], |_| Ok(vec![current]))
+        };
+        // The first write may change by one second's worth
+        assert!(write(LimitPolicy::Reject, 0.0, 0.0, 10.5).is_err());
+        write(LimitPolicy::Reject, 0.0, 0.0, 5.0).unwrap().record_at(start);
+        // 100 ms later, 1.0
+        assert!(write(LimitPolicy::Reject, 0.1, 5.0, 7.0).is_err());
+        assert_eq!(write(LimitPolicy::Clamp, 0.1, 5.0, 7.0).unwrap().values, vec![6.0]);
+        // 500 ms later, 5.0
+        assert_eq!(write(LimitPolicy::Reject, 0.5, 5.0, 10.0).unwrap().values, vec![10.0]);
+        // And never more than one second's worth
+        assert!(write(LimitPolicy::Reject, 60.0, 5.0, 15.5).is_err());
+        assert!(write(LimitPolicy::Reject, 60.0, 5.0, 15.0).is_ok());
+    }
+
+    #[test]
+    fn guard_checks_label_writes() {
+        // write_variable_label: the discovered labels and range apply, not the client's
+        let mut mode = discover("guard_label", 0x2000_1400, "INT32", 1, "RW", Some(0.0), Some(2.0));
+        mode.enum_labels = vec![crate::decoding::EnumLabel { value: 3, label: "FAULT".to_string() }];
+        register(std::slice::from_ref(&mode));
+        let client = VariableInfo { enum_labels: Vec::new(), max_value: None, ..mode.clone() };
+        let variable = discovered(client);
+        let value = crate::decoding::label_value(&variable, "FAULT").unwrap() as f64;
+        assert!(guard_at(LimitPolicy::Reject, Instant::now(), 0x2000_1400, "INT32", 4, &[value], unread).is_err());
+        assert!(guard_at(LimitPolicy::Reject, Instant::now(), 0x2000_1400, "INT32", 4, &[1.0], unread).is_ok());
+    }
+
+    #[test]
+    fn guard_checks_bit_field_writes() {
+        // write_variable_bits: the whole new value is checked against the one it replaces
+        let mut flags = discover("guard_bits", 0x2000_1500, "UINT8", 1, "RW", None, Some(15.0));
+        flags.bit_fields = vec![crate::decoding::BitField { name: "mode".to_string(), offset: 2, width: 3 }];
+        register(std::slice::from_ref(&flags));
+        set_rate_limit("guard_bits", Some(8.0)).unwrap();
+        let write = |current: u8, value| {
+            let bytes = crate::decoding::set_bit_field(&flags, &[current], "mode", value).unwrap();
+            guard_at(LimitPolicy::Reject, Instant::now(), 0x2000_1500, "UINT8", 1, &[bytes[0] as f64], |_| Ok(vec![current as f64]))
+        };
+        assert_eq!(write(0x01, 2).unwrap().values, vec![9.0]);
+        assert!(write(0x01, 4).is_err()); // 0x11 is out of range
+        assert!(write(0x0D, 0).is_err()); // Down by 12, faster than the rate limit
+    }
+
+    #[test]
+    fn guard_checks_array_writes() {
+        // write_array: every element of the slice shares the array's range and rate limit
+        discover("guard_array", 0x2000_1600, "FLOAT", 4, "RW", Some(0.0), Some(10.0));
+        set_rate_limit("guard_array", Some(4.0)).unwrap();
+        let mut reads = 0;
+        let guarded = guard_at(LimitPolicy::Clamp, Instant::now(), 0x2000_1604, "FLOAT", 8, &[3.0, 20.0], |_| {
+            reads += 1;
+            Ok(vec![1.0, 8.0])
+        }).unwrap();
+        assert_eq!((guarded.values, guarded.clamped_from, reads), (vec![3.0, 10.0], Some(20.0), 1));
+        assert!(guard_at(LimitPolicy::Reject, Instant::now(), 0x2000_1604, "FLOAT", 8, &[3.0, 20.0], |_| Ok(vec![1.0, 8.0])).is_err());
+    }
+
+    #[test]
+    fn guard_checks_string_writes() {
+        // write_string: text fits the buffer of a writable STRING variable
+        discover("guard_string", 0x2000_1700, "STRING", 8, "RW", None, None);
+        discover("guard_string_ro", 0x2000_1710, "STRING", 8, "RO", None, None);
+        discover("guard_number", 0x2000_1720, "UINT8", 8, "RW", None, Some(1.0));
+        let write = |address, len| guard_at(LimitPolicy::Reject, Instant::now(), address, "STRING", len, &[], unread);
+        assert!(write(0x2000_1700, 8).is_ok());
+        assert!(write(0x2000_1700, 9).is_err());
+        assert!(write(0x2000_1710, 8).unwrap_err().contains("read-only"));
+        assert!(write(0x2000_1720, 8).is_err());
+    }
+
+    #[test]
+    fn writes_use_the_discovered_scaling() {
+        // write_variable and write_array: client scaling must match the discovered one
+        discover("guard_scaled", 0x2000_1800, "INT16", 1, "RW", None, None);
+        let client = |scale| Some(Scaling { scale: Some(scale), ..Scaling::default() });
+        assert_eq!(scaling_at(0x2000_1800, None), Ok(Scaling::default()));
+        assert_eq!(scaling_at(0x2000_1800, client(1.0)), Ok(Scaling::default()));
+        assert!(scaling_at(0x2000_1800, client(0.1)).is_err());
+
+        apply_decoding_config(r#"{"variables": [{"name": "guard_scaled", "scale": 0.1}]}"#).unwrap();
+        assert_eq!(scaling_at(0x2000_1800, None), Ok(Scaling { scale: Some(0.1), ..Scaling::default() }));
+        assert!(scaling_at(0x2000_1800, client(0.1)).is_ok());
+        assert!(scaling_at(0x2000_1800, client(10.0)).is_err());
+        // Memory outside every discovered variable has no scaling to write with
+        assert!(scaling_at(0x2000_1900, client(10.0)).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+// How variables are written: through the memory access port while the core keeps
+// running, or with the core halted around a read-modify-write. Background writes don't
+// disturb control loops or watchdogs but a few probe/target combinations reject them,
+// so the mode is chosen per target and a failed background write falls back to halting.
+// A verified write reads the variable back to catch values the firmware clamped.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::layout::decode_value;
+use crate::scaling::Scaling;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WriteMode {
+    #[default]
+    Background, // AP write while the core runs
+    Halt,       // Halt, read-modify-write, resume
+}
+
+/// How one write was performed, as returned by write_variable.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct WriteReport {
+    pub method: WriteMode,
+    pub fallback_reason: Option<String>, // Why a background write fell back to halting
+    pub clamped_from: Option<f64>,       // Requested value, when a range or rate limit clamped it
+    pub clamped_to: Option<f64>,         // Value written instead, in engineering units
+    pub verification: Option<WriteVerification>, // Readback, when verification was requested
+}
+
+/// Readback after a write, in engineering units.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct WriteVerification {
+    pub requested: f64,    // As requested, before rounding to the variable's type
+    pub written: f64,      // The requested value as the variable's type stores it
+    pub readback: f64,
+    pub overwritten: bool, // The firmware changed the value after the write, e.g. clamped it
+}
+
+// Modes chosen by target name; targets not listed use the default
+static WRITE_MODES: Mutex<BTreeMap<String, WriteMode>> = Mutex::new(BTreeMap::new());
+
+/// Write mode of a target.
+pub(crate) fn mode_for(target: &str) -> WriteMode {
+    WRITE_MODES.lock().unwrap().get(target).copied().unwrap_or_default()
+}
+
+/// Choose the write mode of a target for the rest of this run of the app.
+pub(crate) fn set_mode(target: &str, mode: WriteMode) {
+    eprintln!("Write mode for {} set to {:?}", target, mode);
+    WRITE_MODES.lock().unwrap().insert(target.to_string(), mode);
+}
+
+/// Compare the bytes read back with the bytes written, so values match within the
+/// precision of the type. Without `requested`, the written value is the requested one.
+pub(crate) fn verify(var_type: &str, scaling: Scaling, requested: Option<f64>, written: &[u8], readback: &[u8]) -> Result<WriteVerification, String> {
+    let value = |bytes: &[u8]| decode_value(var_type, bytes).map(|raw| scaling.to_engineering(raw.as_f64()));
+    let written_value = value(written)?;
+    Ok(WriteVerification {
+        requested: requested.unwrap_or(written_value),
+        written: written_value,
+        readback: value(readback)?,
+        overwritten: written != readback,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modes_are_per_target_and_default_to_background() {
+        set_mode("TEST_TARGET_A", WriteMode::Halt);
+        assert_eq!(mode_for("TEST_TARGET_A"), WriteMode::Halt);
+        assert_eq!(mode_for("TEST_TARGET_B"), WriteMode::Background);
+        assert_eq!(serde_json::to_string(&WriteMode::Halt).unwrap(), "\"halt\"");
+    }
+
+    #[test]
+    fn verification_compares_within_type_precision() {
+        // 0.1 is not exact as a float, but reads back as the same float
+        let written = 0.1f32.to_le_bytes();
+        let verification = verify("FLOAT", Scaling::default(), Some(0.1), &written, &written).unwrap();
+        assert!(!verification.overwritten);
+        assert_eq!(verification.written, 0.1f32 as f64);
+
+        // Firmware clamped 200 to 100
+        let verification = verify("UINT8", Scaling::default(), None, &[200], &[100]).unwrap();
+        assert_eq!(verification, WriteVerification { requested: 200.0, written: 200.0, readback: 100.0, overwritten: true });
+    }
+}
@@ -1,15 +1,19 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { ProbeInfo, SessionInfo, VariableInfo } from './types';
+import { LimitPolicy, ProbeInfo, SessionInfo, VariableInfo, WriteMode } from './types';
 import ProbeSelector from './components/ProbeSelector';
 import McuStatus from './components/McuStatus';
 import VariablePanel from './components/VariablePanel';
 import PlotPanel from './components/PlotPanel';
+import RttTerminal from './components/RttTerminal';
+import DefmtLogView from './components/DefmtLogView';
+import SwoConsole from './components/SwoConsole';
 import "./App.css";
 
 function App() {
   const [probes, setProbes] = useState<ProbeInfo[]>([]);
   const [selectedProbe, setSelectedProbe] = useState<number>(0);
+  const [selectedTarget, setSelectedTarget] = useState<string>('STM32H735ZGTx');
   const [session, setSession] = useState<SessionInfo | null>(null);
   const [error, setError] = useState<string | null>(null);
   const [isConnecting, setIsConnecting] = useState<boolean>(false);
@@ -23,6 +27,10 @@ function App() {
   const [mculinkAddress, setMculinkAddress] = useState<string>('0x080F0000');
   const [hasDiscoveredVariables, setHasDiscoveredVariables] = useState<boolean>(false);
   const [sidebarOpen, setSidebarOpen] = useState<boolean>(true);
+  const [limitPolicy, setLimitPolicy] = useState<LimitPolicy>('reject');
+  const [rateLimits, setRateLimits] = useState<Record<string, number>>({});
+  const [rateLimitVariable, setRateLimitVariable] = useState<string>('');
+  const [rateLimitValue, setRateLimitValue] = useState<string>('');
 
   const handleVariablesDiscovered = (discoveredVariables: VariableInfo[]) => {
     console.log("App - Variables discovered:", discoveredVariables);
@@ -62,6 +70,36 @@ function App() {
     }
   };
 
+  // Write mode of the connected target; kept by the backend per target name
+  const changeWriteMode = async (mode: WriteMode) => {
+    try {
+      await invoke("set_write_mode", { mode });
+      setSession(prev => prev ? { ...prev, write_mode: mode } : prev);
+    } catch (err) {
+      setError(`Failed to set write mode: ${err}`);
+    }
+  };
+
+  const changeLimitPolicy = async (policy: LimitPolicy) => {
+    try {
+      await invoke("set_limit_policy", { policy });
+      setLimitPolicy(policy);
+    } catch (err) {
+      setError(`Failed to set limit policy: ${err}`);
+    }
+  };
+
+  // Set, or with no rate clear, the rate limit of a variable; kept by the backend per name
+  const changeRateLimit = async (name: string, maxChangePerS: number | null) => {
+    try {
+      const limits = await invoke<Record<string, number>>("set_rate_limit", { name, maxChangePerS });
+      setRateLimits(limits);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to set rate limit: ${err}`);
+    }
+  };
+
   const disconnectFromMcu = async () => {
     try {
       await invoke("disconnect_probe");
@@ -93,7 +131,8 @@ function App() {
       try {
         console.log(`Connection attempt ${attempt}/${retryCount} for probe ${index}`);
         const sessionInfo = await invoke<SessionInfo>("connect_to_mcu", { 
-          probeIndex: index 
+          probeIndex: index,
+          target: selectedTarget
         });
         setSession(sessionInfo);
         setError(null); // Clear any previous errors on successful connection
@@ -155,6 +194,8 @@ function App() {
                   probes={probes}
                   selectedProbe={selectedProbe}
                   onProbeSelect={setSelectedProbe}
+                  selectedTarget={selectedTarget}
+                  onTargetSelect={setSelectedTarget}
                   onConnect={() => connectToMcu(undefined, true)}
                   onDisconnect={disconnectFromMcu}
                   isConnecting={isConnecting}
@@ -196,6 +237,15 @@ function App() {
           </div>
         );
       
+      case 'rtt':
+        return (
+          <div className="tab-content">
+            <RttTerminal isConnected={session?.connected || false} />
+            <DefmtLogView isConnected={session?.connected || false} />
+            <SwoConsole isConnected={session?.connected || false} />
+          </div>
+        );
+      
       case 'config':
         return (
           <div className="tab-content">
@@ -249,6 +299,84 @@ function App() {
                 </div>
               </div>
 
+              <div className="config-section">
+                <h4>Variable Writes</h4>
+                <div className="config-group">
+                  <label htmlFor="write-mode">
+                    Write Mode{session ? ` (${session.target_name})` : ''}:
+                    <span className="help-text">Background writes don't halt the core; a failed background write falls back to halting</span>
+                  </label>
+                  <select
+                    id="write-mode"
+                    value={session?.write_mode ?? 'background'}
+                    onChange={(e) => changeWriteMode(e.target.value as WriteMode)}
+                    disabled={!session?.connected}
+                  >
+                    <option value="background">Background (core keeps running)</option>
+                    <option value="halt">Halt core during writes</option>
+                  </select>
+                </div>
+              </div>
+
+              <div className="config-section">
+                <h4>Write Safety</h4>
+                <div className="config-group">
+                  <label htmlFor="limit-policy">
+                    Out-of-Limit Writes:
+                    <span className="help-text">Writes beyond a variable's min/max or rate limit; read-only variables always reject writes</span>
+                  </label>
+                  <select
+                    id="limit-policy"
+                    value={limitPolicy}
+                    onChange={(e) => changeLimitPolicy(e.target.value as LimitPolicy)}
+                  >
+                    <option value="reject">Reject</option>
+                    <option value="clamp">Clamp to the limit</option>
+                  </select>
+                </div>
+                <div className="config-group">
+                  <label htmlFor="rate-limit-variable">
+                    Rate Limit:
+                    <span className="help-text">Maximum change per second of a variable, in its units</span>
+                  </label>
+                  <div className="address-input-group">
+                    <select
+                      id="rate-limit-variable"
+                      value={rateLimitVariable}
+                      onChange={(e) => setRateLimitVariable(e.target.value)}
+                    >
+                      <option value="">Select variable</option>
+                      {variables
+                        .filter(v => v.access_flags !== 'RO' && v.element_count === 1 && v.var_type !== 'STRING')
+                        .map(v => (
+                          <option key={v.name} value={v.name}>{v.name}</option>
+                        ))}
+                    </select>
+                    <input
+                      type="number"
+                      value={rateLimitValue}
+                      min={0}
+                      step="any"
+                      onChange={(e) => setRateLimitValue(e.target.value)}
+                      placeholder="units/s"
+                      style={{ width: '90px' }}
+                    />
+                    <button
+                      onClick={() => changeRateLimit(rateLimitVariable, parseFloat(rateLimitValue))}
+                      disabled={!rateLimitVariable || !(parseFloat(rateLimitValue) > 0)}
+                    >
+                      Set
+                    </button>
+                  </div>
+                  {Object.entries(rateLimits).map(([name, rate]) => (
+                    <div key={name} style={{ fontFamily: 'monospace', fontSize: '12px' }}>
+                      {name}: {rate}/s
+                      <button onClick={() => changeRateLimit(name, null)} style={{ marginLeft: '8px' }}>Clear</button>
+                    </div>
+                  ))}
+                </div>
+              </div>
+
               <div className="config-section">
                 <h4>Linker Script Configuration</h4>
                 <div className="code-block">
@@ -260,6 +388,7 @@ function App() {
   . = ALIGN(4);
   KEEP(*(.mculink))        /* MCU Link variable descriptors */
   . = ALIGN(4);
+  __mculink_end = .;       /* Lets MCU Link read the whole section at once */
 } >FLASH`)}
                       className="copy-btn"
                     >
@@ -272,6 +401,7 @@ function App() {
   . = ALIGN(4);
   KEEP(*(.mculink))        /* MCU Link variable descriptors */
   . = ALIGN(4);
+  __mculink_end = .;       /* Lets MCU Link read the whole section at once */
 } >FLASH`}
                   </pre>
                 </div>
@@ -346,7 +476,6 @@ function App() {
             <button 
               className={`nav-item ${activeTab === 'variables' ? 'active' : ''}`}
               onClick={() => setActiveTab('variables')}
-              disabled={!session?.connected}
               title="Variables"
             >
               <span className="nav-icon">📊</span>
@@ -361,6 +490,15 @@ function App() {
               <span className="nav-icon">📈</span>
               {sidebarOpen && <span className="nav-label">Real-Time Plots</span>}
             </button>
+            <button 
+              className={`nav-item ${activeTab === 'rtt' ? 'active' : ''}`}
+              onClick={() => setActiveTab('rtt')}
+              disabled={!session?.connected}
+              title="RTT / SWO"
+            >
+              <span className="nav-icon">💬</span>
+              {sidebarOpen && <span className="nav-label">RTT / SWO</span>}
+            </button>
             <button 
               className={`nav-item ${activeTab === 'config' ? 'active' : ''}`}
               onClick={() => setActiveTab('config')}
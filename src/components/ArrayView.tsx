@@ -0,0 +1,135 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { VariableInfo } from '../types';
+
+interface ArrayViewProps {
+  variable: VariableInfo;
+  isConnected: boolean;
+}
+
+const PLOT_WIDTH = 400;
+const PLOT_HEIGHT = 80;
+
+export default function ArrayView({ variable, isConnected }: ArrayViewProps) {
+  const [elements, setElements] = useState<number[]>([]);
+  const [isLive, setIsLive] = useState<boolean>(false);
+  const [editIndex, setEditIndex] = useState<number | null>(null);
+  const [editValue, setEditValue] = useState<string>('');
+  const [error, setError] = useState<string | null>(null);
+  const isReadOnly = variable.access_flags === "RO";
+
+  const readArray = async () => {
+    try {
+      const values = await invoke<number[]>("read_array", {
+        address: variable.address,
+        varType: variable.var_type,
+        elementCount: variable.element_count,
+        scaling: variable
+      });
+      setElements(values);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to read ${variable.name}: ${err}`);
+    }
+  };
+
+  const writeElement = async (index: number) => {
+    const value = parseFloat(editValue);
+    if (isNaN(value)) return;
+
+    try {
+      await invoke("write_array", {
+        address: variable.address,
+        varType: variable.var_type,
+        elementCount: variable.element_count,
+        startIndex: index,
+        values: [value],
+        scaling: variable
+      });
+      setElements(prev => prev.map((v, i) => i === index ? value : v));
+      setEditIndex(null);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to write ${variable.name}[${index}]: ${err}`);
+    }
+  };
+
+  // Initial read, then periodic refresh while live view is enabled
+  useEffect(() => {
+    if (!isConnected) return;
+    readArray();
+    if (!isLive) return;
+
+    const interval = setInterval(readArray, 200);
+    return () => clearInterval(interval);
+  }, [isConnected, isLive, variable.address]);
+
+  // Waveform preview scaled to the declared min/max (or the data range)
+  const min = variable.min_value ?? Math.min(...elements);
+  const max = variable.max_value ?? Math.max(...elements);
+  const span = max - min || 1;
+  const points = elements.map((value, i) => {
+    const x = elements.length > 1 ? (i / (elements.length - 1)) * PLOT_WIDTH : 0;
+    const y = PLOT_HEIGHT - ((value - min) / span) * PLOT_HEIGHT;
+    return `${x.toFixed(1)},${y.toFixed(1)}`;
+  }).join(' ');
+
+  return (
+    <div className="array-view" style={{ marginBottom: '10px' }}>
+      <div className="discovery-controls">
+        <label>{variable.name}[{variable.element_count}] ({variable.var_type}, {variable.access_flags}){variable.unit && ` [${variable.unit}]`}</label>
+        <button onClick={readArray} disabled={!isConnected} style={{ marginLeft: '8px' }}>
+          Refresh
+        </button>
+        <label style={{ marginLeft: '8px' }}>
+          <input type="checkbox" checked={isLive} onChange={(e) => setIsLive(e.target.checked)} />
+          Live
+        </label>
+      </div>
+
+      {error && (
+        <div className="error">
+          <p>{error}</p>
+        </div>
+      )}
+
+      {elements.length > 0 && (
+        <>
+          <svg width={PLOT_WIDTH} height={PLOT_HEIGHT} style={{ border: '1px solid #ddd' }}>
+            <polyline points={points} fill="none" stroke="#2196f3" strokeWidth="1" />
+          </svg>
+          <div style={{ maxHeight: '150px', overflowY: 'auto', fontFamily: 'monospace', fontSize: '12px' }}>
+            {elements.map((value, index) => (
+              <div key={index}>
+                [{index}] {editIndex === index ? (
+                  <>
+                    <input
+                      type="text"
+                      value={editValue}
+                      onChange={(e) => setEditValue(e.target.value)}
+                      onKeyDown={(e) => e.key === 'Enter' && writeElement(index)}
+                      style={{ width: '80px', fontFamily: 'monospace' }}
+                    />
+                    <button onClick={() => writeElement(index)}>Write</button>
+                    <button onClick={() => setEditIndex(null)}>Cancel</button>
+                  </>
+                ) : (
+                  <span
+                    onClick={() => {
+                      if (isReadOnly) return;
+                      setEditIndex(index);
+                      setEditValue(String(value));
+                    }}
+                    style={{ cursor: isReadOnly ? 'default' : 'pointer' }}
+                  >
+                    {value}
+                  </span>
+                )}
+              </div>
+            ))}
+          </div>
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,150 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { LogRecord } from '../types';
+
+interface DefmtLogViewProps {
+  isConnected: boolean;
+}
+
+const MAX_RECORDS = 5000; // Older records are dropped
+const LEVELS = ['trace', 'debug', 'info', 'warn', 'error'];
+const LEVEL_COLORS: { [level: string]: string } = {
+  trace: '#6c757d',
+  debug: '#17a2b8',
+  info: '#28a745',
+  warn: '#ffc107',
+  error: '#dc3545',
+};
+
+export default function DefmtLogView({ isConnected }: DefmtLogViewProps) {
+  const [elfPath, setElfPath] = useState<string>('');
+  const [channel, setChannel] = useState<number>(0);
+  const [isDecoding, setIsDecoding] = useState<boolean>(false);
+  const [records, setRecords] = useState<LogRecord[]>([]);
+  const [minLevel, setMinLevel] = useState<string>('trace');
+  const [search, setSearch] = useState<string>('');
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    const unlisten = listen<LogRecord[]>("defmt-log", (event) => {
+      setRecords(prev => {
+        const next = [...prev, ...event.payload];
+        return next.length > MAX_RECORDS ? next.slice(-MAX_RECORDS) : next;
+      });
+    });
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
+  useEffect(() => {
+    if (!isConnected) setIsDecoding(false);
+  }, [isConnected]);
+
+  const startDecoding = async () => {
+    try {
+      await invoke("start_defmt", { channel, elfPath });
+      setIsDecoding(true);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to start defmt decoding: ${err}`);
+    }
+  };
+
+  const stopDecoding = async () => {
+    try {
+      await invoke("stop_defmt", { channel });
+      setIsDecoding(false);
+    } catch (err) {
+      setError(`Failed to stop defmt decoding: ${err}`);
+    }
+  };
+
+  // Records without a level (defmt::println!) are always shown
+  const minLevelIndex = LEVELS.indexOf(minLevel);
+  const query = search.toLowerCase();
+  const filteredRecords = records.filter(record =>
+    (!record.level || LEVELS.indexOf(record.level) >= minLevelIndex) &&
+    (record.message.toLowerCase().includes(query) || (record.module || '').toLowerCase().includes(query))
+  );
+
+  return (
+    <div className="defmt-log">
+      <h3>defmt Log</h3>
+      <div className="discovery-controls">
+        <label>
+          Firmware ELF:
+          <input
+            type="text"
+            value={elfPath}
+            onChange={(e) => setElfPath(e.target.value)}
+            placeholder="/path/to/firmware.elf"
+            disabled={isDecoding}
+            style={{ marginLeft: '8px', fontFamily: 'monospace', minWidth: '250px' }}
+          />
+        </label>
+        <label style={{ marginLeft: '8px' }}>
+          Channel:
+          <input
+            type="number"
+            value={channel}
+            min={0}
+            onChange={(e) => setChannel(parseInt(e.target.value) || 0)}
+            disabled={isDecoding}
+            style={{ marginLeft: '8px', width: '50px' }}
+          />
+        </label>
+        {isDecoding ? (
+          <button onClick={stopDecoding} style={{ marginLeft: '8px' }}>Stop</button>
+        ) : (
+          <button onClick={startDecoding} disabled={!isConnected || !elfPath} style={{ marginLeft: '8px' }}>
+            Decode defmt
+          </button>
+        )}
+      </div>
+
+      {error && (
+        <div className="error">
+          <p>{error}</p>
+        </div>
+      )}
+
+      <div className="discovery-controls">
+        <select value={minLevel} onChange={(e) => setMinLevel(e.target.value)}>
+          {LEVELS.map(level => (
+            <option key={level} value={level}>{level} and above</option>
+          ))}
+        </select>
+        <input
+          type="text"
+          value={search}
+          onChange={(e) => setSearch(e.target.value)}
+          placeholder="Filter by message or module..."
+          style={{ marginLeft: '8px' }}
+        />
+        <span style={{ marginLeft: '8px', fontSize: '12px' }}>
+          {filteredRecords.length} of {records.length} records
+        </span>
+        <button onClick={() => setRecords([])} style={{ marginLeft: '8px' }}>Clear</button>
+      </div>
+
+      <div style={{ maxHeight: '400px', overflowY: 'auto', fontFamily: 'monospace', fontSize: '12px' }}>
+        {filteredRecords.map((record, i) => (
+          <div key={i} title={record.file ? `${record.file}:${record.line}` : undefined}>
+            {/* Host time in ms, the same clock the plots use */}
+            <span style={{ color: '#6c757d' }}>{(record.timestamp_us / 1000).toFixed(3)}</span>
+            {record.target_timestamp && <span style={{ marginLeft: '6px' }}>[{record.target_timestamp}]</span>}
+            {record.level && (
+              <span style={{ marginLeft: '6px', color: LEVEL_COLORS[record.level], fontWeight: 'bold' }}>
+                {record.level.toUpperCase()}
+              </span>
+            )}
+            {record.module && <span style={{ marginLeft: '6px', color: '#6c757d' }}>{record.module}</span>}
+            <span style={{ marginLeft: '6px' }}>{record.message}</span>
+          </div>
+        ))}
+      </div>
+    </div>
+  );
+}
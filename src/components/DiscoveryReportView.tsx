@@ -0,0 +1,39 @@
+import { DiscoveryReport } from '../types';
+
+interface DiscoveryReportViewProps {
+  report: DiscoveryReport;
+}
+
+const CRC_LABELS: Record<string, string> = {
+  Valid: 'CRC valid',
+  Mismatch: 'CRC MISMATCH',
+  Unsealed: 'CRC not sealed',
+  NotPresent: 'no CRC',
+};
+
+export default function DiscoveryReportView({ report }: DiscoveryReportViewProps) {
+  return (
+    <div className="discovery-report" style={{ marginTop: '10px', fontSize: '12px' }}>
+      <p>
+        {report.format}{report.descriptor_version && ` v${report.descriptor_version}`} - {CRC_LABELS[report.crc_status]} - {report.variables.length} variables, {report.rejected.length} rejected
+      </p>
+
+      {report.warnings.map((warning, index) => (
+        <p key={index} style={{ color: '#b58900' }}>Warning: {warning}</p>
+      ))}
+
+      {report.rejected.length > 0 && (
+        <div className="error">
+          <p>Rejected entries:</p>
+          <ul style={{ fontFamily: 'monospace' }}>
+            {report.rejected.map((entry) => (
+              <li key={entry.location}>
+                0x{entry.location.toString(16).toUpperCase().padStart(8, '0')} {entry.name || '?'}: {entry.reason}
+              </li>
+            ))}
+          </ul>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,135 @@
+import { useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { DiscoveryReport, VariableInfo } from '../types';
+
+interface ElfSymbolPickerProps {
+  onVariablesSelected: (variables: VariableInfo[]) => void;
+  onDiscoveryReport?: (report: DiscoveryReport) => void;
+  allowUnsealed?: boolean; // Use .mculink tables without a valid CRC
+}
+
+export default function ElfSymbolPicker({ onVariablesSelected, onDiscoveryReport, allowUnsealed }: ElfSymbolPickerProps) {
+  const [elfPath, setElfPath] = useState<string>('');
+  const [symbols, setSymbols] = useState<VariableInfo[]>([]);
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [search, setSearch] = useState<string>('');
+  const [isLoading, setIsLoading] = useState<boolean>(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadElf = async () => {
+    if (!elfPath) return;
+
+    setIsLoading(true);
+    try {
+      const discovered = await invoke<VariableInfo[]>("discover_variables_from_elf", { path: elfPath });
+      setSymbols(discovered);
+      setSelected(new Set());
+      setError(null);
+      console.log(`ElfSymbolPicker - Loaded ${discovered.length} symbols from ${elfPath}`);
+    } catch (err) {
+      setError(`Failed to load ELF: ${err}`);
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  const loadMculinkSection = async () => {
+    if (!elfPath) return;
+
+    setIsLoading(true);
+    try {
+      const report = await invoke<DiscoveryReport>("discover_variables_from_elf_section", { path: elfPath, allowUnsealed });
+      setError(null);
+      console.log(`ElfSymbolPicker - Loaded ${report.variables.length} variables (${report.rejected.length} rejected) from .mculink in ${elfPath}`);
+      onDiscoveryReport?.(report);
+      onVariablesSelected(report.variables);
+    } catch (err) {
+      setError(`Failed to read .mculink section: ${err}`);
+    } finally {
+      setIsLoading(false);
+    }
+  };
+
+  // Keyed by name: bit-fields of one storage unit share an address
+  const toggleSymbol = (name: string) => {
+    setSelected(prev => {
+      const next = new Set(prev);
+      if (next.has(name)) {
+        next.delete(name);
+      } else {
+        next.add(name);
+      }
+      return next;
+    });
+  };
+
+  const query = search.toLowerCase();
+  const filteredSymbols = symbols.filter(symbol =>
+    symbol.name.toLowerCase().includes(query) || (symbol.category || '').toLowerCase().includes(query)
+  );
+
+  return (
+    <div className="elf-symbol-picker" style={{ marginTop: '10px' }}>
+      <div className="discovery-controls">
+        <label>
+          Firmware ELF:
+          <input
+            type="text"
+            value={elfPath}
+            onChange={(e) => setElfPath(e.target.value)}
+            placeholder="/path/to/firmware.elf"
+            style={{ marginLeft: '8px', fontFamily: 'monospace', minWidth: '300px' }}
+          />
+        </label>
+        <button onClick={loadElf} disabled={!elfPath || isLoading}>
+          {isLoading ? 'Loading...' : 'Load Symbols'}
+        </button>
+        <button onClick={loadMculinkSection} disabled={!elfPath || isLoading} style={{ marginLeft: '8px' }}>
+          Load .mculink Section
+        </button>
+      </div>
+
+      {error && (
+        <div className="error">
+          <p>{error}</p>
+        </div>
+      )}
+
+      {symbols.length > 0 && (
+        <div className="symbol-list">
+          <div className="discovery-controls">
+            <input
+              type="text"
+              value={search}
+              onChange={(e) => setSearch(e.target.value)}
+              placeholder="Search symbols..."
+            />
+            <span style={{ marginLeft: '8px', fontSize: '12px' }}>
+              {filteredSymbols.length} of {symbols.length} symbols, {selected.size} selected
+            </span>
+            <button
+              onClick={() => onVariablesSelected(symbols.filter(s => selected.has(s.name)))}
+              disabled={selected.size === 0}
+              style={{ marginLeft: '8px' }}
+            >
+              Watch Selected
+            </button>
+          </div>
+          <div style={{ maxHeight: '300px', overflowY: 'auto', fontFamily: 'monospace', fontSize: '12px' }}>
+            {filteredSymbols.map(symbol => (
+              <label key={symbol.name} style={{ display: 'block' }}>
+                <input
+                  type="checkbox"
+                  checked={selected.has(symbol.name)}
+                  onChange={() => toggleSymbol(symbol.name)}
+                />
+                {symbol.name}{symbol.element_count > 1 && `[${symbol.element_count}]`} @ 0x{symbol.address.toString(16).toUpperCase().padStart(8, '0')} ({symbol.var_type}, {symbol.access_flags})
+                {symbol.category && ` - ${symbol.category}`}
+              </label>
+            ))}
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,5 +1,7 @@
 import { SessionInfo } from '../types';
 
+const hex = (value: number, digits: number) => `0x${value.toString(16).toUpperCase().padStart(digits, '0')}`;
+
 interface McuStatusProps {
   session: SessionInfo | null;
   error: string | null;
@@ -11,8 +13,16 @@ export default function McuStatus({ session, error }: McuStatusProps) {
       <h3>MCU Status</h3>
       {session ? (
         <div className="connected">
-          <p>✅ Connected to {session.target_name}</p>
-          {session.chip_id && <p>Chip ID: {session.chip_id}</p>}
+          <p>✅ Connected to {session.target_name}{session.auto_detected && ' (auto-detected)'}</p>
+          {session.chip_id && (
+            <div className="chip-id">
+              {session.chip_id.idcode != null && <p>IDCODE: {hex(session.chip_id.idcode, 8)}</p>}
+              {session.chip_id.part_number != null && <p>Part number: {hex(session.chip_id.part_number, 3)}</p>}
+              {session.chip_id.revision && <p>Revision: {session.chip_id.revision}</p>}
+              {session.chip_id.core && <p>Core: {session.chip_id.core}</p>}
+              {session.chip_id.manufacturer && <p>Manufacturer: {session.chip_id.manufacturer}</p>}
+            </div>
+          )}
         </div>
       ) : error ? (
         <div className="error">
@@ -1,6 +1,8 @@
-import { useEffect, useState } from 'react';
+import { useEffect, useRef, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { VariableInfo } from '../types';
+import { listen, UnlistenFn } from '@tauri-apps/api/event';
+import { SampleBatch, StreamStats, VariableInfo } from '../types';
+import { CircularBuffer, DataPoint } from '../utils/CircularBuffer';
 import RealTimePlot from './RealTimePlot';
 
 interface PlotPanelProps {
@@ -9,19 +11,36 @@ interface PlotPanelProps {
 }
 
 interface PlotData {
-  [variableName: string]: number[];
+  [variableName: string]: DataPoint[];
 }
 
+const BUFFER_SIZE = 10000; // Points kept per variable
+const STATS_INTERVAL_MS = 1000; // How often the jitter statistics are refreshed
+const DEFAULT_CORE_CLOCK_MHZ = 64;
+const RTT_STREAM_CHANNELS = [1, 2, 3]; // Channel 0 is normally the text terminal
+const MAX_SWO_VARIABLES = 4; // One DWT comparator each
+const SWO_TRACE_TYPES = ["FLOAT", "UINT8", "INT16", "BOOL"]; // Plottable types of at most a word
+
 export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
   const [plotData, setPlotData] = useState<PlotData>({});
+  const buffersRef = useRef<Map<string, CircularBuffer>>(new Map());
   const [isCollecting, setIsCollecting] = useState(false);
   const [isPaused, setIsPaused] = useState(false);
-  const [dataRate, setDataRate] = useState<number>(0); // 0 = LUDICROUS MODE
+  const [dataRate, setDataRate] = useState<number>(0); // Hz, 0 = LUDICROUS MODE
+  const dataRateRef = useRef(dataRate);
+  const runIdRef = useRef<number | null>(null);
+  const [achievedRate, setAchievedRate] = useState<number>(0);
+  const [blockReads, setBlockReads] = useState<number>(0);
+  const [stats, setStats] = useState<StreamStats | null>(null);
   const [timeWindow, setTimeWindow] = useState<number>(1000); // points to show
+  const [useTargetTime, setUseTargetTime] = useState(false); // Plot against DWT_CYCCNT time
+  const [coreClockMHz, setCoreClockMHz] = useState<number>(DEFAULT_CORE_CLOCK_MHZ);
+  const [source, setSource] = useState<string>("reads"); // "reads", "swo" or an RTT channel number
+  const [droppedSamples, setDroppedSamples] = useState<number>(0);
 
   // Filter variables that should be plotted (read-only numeric types)
   const plottableVars = variables.filter(v => 
-    v.access_flags === "RO" && (v.var_type === "FLOAT" || v.var_type === "UINT8" || v.var_type === "INT16")
+    v.access_flags === "RO" && v.element_count === 1 && ["FLOAT", "DOUBLE", "UINT8", "INT16", "UINT64", "INT64", "BOOL"].includes(v.var_type)
   );
 
   // Only log once when variables are first discovered
@@ -31,63 +50,118 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
     }
   }, [variables.length]);
 
-  // High-frequency data collection (with pause capability)
+  // High-frequency data collection (with pause capability): the Rust sampler thread
+  // reads every plotted variable per tick and streams batches as "sampling-batch" events.
+  // An RTT binary stream delivers the same batches from frames pushed by the firmware,
+  // and SWO data trace from DWT packets of the first four word-sized variables.
   useEffect(() => {
     if (!isConnected || plottableVars.length === 0 || isPaused) {
       setIsCollecting(false);
       return;
     }
 
-    setIsCollecting(true);
-    
-    // Initialize plot data arrays
-    const initialData: PlotData = {};
-    plottableVars.forEach(variable => {
-      if (!plotData[variable.name]) {
-        initialData[variable.name] = [];
-      }
-    });
-    
-    if (Object.keys(initialData).length > 0) {
-      setPlotData(prev => ({ ...prev, ...initialData }));
-    }
+    const sampledVars = source === "swo"
+      ? plottableVars.filter(v => SWO_TRACE_TYPES.includes(v.var_type)).slice(0, MAX_SWO_VARIABLES)
+      : plottableVars;
+    let cancelled = false;
+    let unlisten: UnlistenFn | null = null;
 
-    // High-frequency reading loop - TRUE 10kHz!
-    const interval = setInterval(async () => {
-      const newData: PlotData = {};
-      
-      for (const variable of plottableVars) {
-        try {
-          const value = await invoke<number>("read_variable", {
-            address: variable.address,
-            varType: variable.var_type
+    const stopRun = (runId: number) => {
+      if (source === "reads") return invoke("stop_sampling", { runId });
+      if (source === "swo") return invoke("stop_swo_stream", { runId });
+      return invoke("stop_rtt_stream", { channel: parseInt(source), runId });
+    };
+
+    const startSampling = async () => {
+      const stopListening = await listen<SampleBatch>("sampling-batch", (event) => {
+        const batch = event.payload;
+        if (batch.run_id !== runIdRef.current) return; // Late batch from a previous run
+        setAchievedRate(batch.samples_per_second);
+        setBlockReads(batch.block_reads);
+        if (batch.dropped_samples > 0) setDroppedSamples(prev => prev + batch.dropped_samples);
+
+        // Keep the backend's read timestamps (host µs or target s) rather than stamping on arrival
+        const timestampsMs = batch.timestamps_us.map((hostUs, j) => {
+          if (!batch.target_times_s) return hostUs / 1000;
+          const targetS = batch.target_times_s[j];
+          return targetS === null ? null : targetS * 1000;
+        });
+        const next: PlotData = {};
+        sampledVars.forEach((variable, i) => {
+          const buffer = buffersRef.current.get(variable.name) ?? new CircularBuffer(BUFFER_SIZE);
+          buffersRef.current.set(variable.name, buffer);
+          batch.values[i].forEach((value, j) => {
+            const timestamp = timestampsMs[j];
+            if (value !== null && timestamp !== null) buffer.push(value, timestamp);
           });
-          
-          // Keep last 10000 points (1 second at 10kHz, 10 seconds at 1kHz)
-          const currentData = plotData[variable.name] || [];
-          const updatedData = [...currentData, value];
-          if (updatedData.length > 10000) {
-            updatedData.shift(); // Remove oldest point
-          }
-          
-          newData[variable.name] = updatedData;
-        } catch (err) {
-          console.error(`Failed to read ${variable.name} for plotting:`, err);
-        }
+          next[variable.name] = buffer.getAll();
+        });
+        setPlotData(prev => ({ ...prev, ...next }));
+      });
+      if (cancelled) {
+        stopListening();
+        return;
       }
-      
-      if (Object.keys(newData).length > 0) {
-        setPlotData(prev => ({ ...prev, ...newData }));
+      unlisten = stopListening;
+
+      const coreClockHz = useTargetTime ? coreClockMHz * 1e6 : undefined;
+      const runId = source === "reads"
+        ? await invoke<number>("start_sampling", { variables: sampledVars, rateHz: dataRateRef.current, coreClockHz })
+        : source === "swo"
+          ? await invoke<number>("start_swo_stream", { variables: sampledVars, coreClockHz })
+          : await invoke<number>("start_rtt_stream", { channel: parseInt(source), variables: sampledVars, coreClockHz });
+      if (cancelled) {
+        stopRun(runId);
+        return;
       }
-    }, dataRate); // User-configurable data rate!
+      runIdRef.current = runId;
+      setIsCollecting(true);
+    };
+
+    setDroppedSamples(0);
+    startSampling().catch(err => console.error("Failed to start sampling:", err));
 
     return () => {
-      clearInterval(interval);
+      cancelled = true;
+      unlisten?.();
+      if (runIdRef.current !== null) {
+        stopRun(runIdRef.current);
+        runIdRef.current = null;
+      }
       setIsCollecting(false);
     };
-  }, [isConnected, plottableVars.length, dataRate, isPaused]);
+  }, [isConnected, plottableVars.length, isPaused, useTargetTime, coreClockMHz, source]);
+
+  // Interval jitter and missed deadlines of the running sampler
+  useEffect(() => {
+    if (!isCollecting || isPaused) {
+      setStats(null);
+      return;
+    }
+
+    const interval = setInterval(async () => {
+      try {
+        const streams = await invoke<StreamStats[]>("sampling_stats");
+        setStats(streams.find(stream => stream.run_id === runIdRef.current) ?? null);
+      } catch (err) {
+        console.error("Failed to read sampling stats:", err);
+      }
+    }, STATS_INTERVAL_MS);
+
+    return () => clearInterval(interval);
+  }, [isCollecting, isPaused]);
+
+  // Rate changes apply to the running sampler without restarting it
+  useEffect(() => {
+    dataRateRef.current = dataRate;
+    if (runIdRef.current !== null) {
+      invoke("set_sampling_rate", { rateHz: dataRate })
+        .catch(err => console.error("Failed to change sampling rate:", err));
+    }
+  }, [dataRate]);
 
   const clearAllPlots = () => {
+    buffersRef.current.forEach(buffer => buffer.clear());
     const clearedData: PlotData = {};
     plottableVars.forEach(variable => {
       clearedData[variable.name] = [];
@@ -97,23 +171,24 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
 
   const dataRateOptions = [
     { value: 0, label: "🚀 MAX Hz", description: "Maximum sampling rate (no limit)" },
-    { value: 1, label: "⚡ 1000 Hz", description: "1000 samples per second" },
-    { value: 2, label: "🔥 500 Hz", description: "500 samples per second" },
-    { value: 8, label: "⭐ 125 Hz", description: "125 samples per second" },
-    { value: 16, label: "📊 62.5 Hz", description: "62.5 samples per second" },
-    { value: 33, label: "🎯 30 Hz", description: "30 samples per second" },
-    { value: 100, label: "🔋 10 Hz", description: "10 samples per second" },
-    { value: 500, label: "🐌 2 Hz", description: "2 samples per second" },
+    { value: 10000, label: "💥 10000 Hz", description: "10000 samples per second" },
+    { value: 1000, label: "⚡ 1000 Hz", description: "1000 samples per second" },
+    { value: 500, label: "🔥 500 Hz", description: "500 samples per second" },
+    { value: 125, label: "⭐ 125 Hz", description: "125 samples per second" },
+    { value: 62.5, label: "📊 62.5 Hz", description: "62.5 samples per second" },
+    { value: 30, label: "🎯 30 Hz", description: "30 samples per second" },
+    { value: 10, label: "🔋 10 Hz", description: "10 samples per second" },
+    { value: 2, label: "🐌 2 Hz", description: "2 samples per second" },
   ];
 
   const handleDataRateChange = (event: React.ChangeEvent<HTMLSelectElement>) => {
-    const newRate = parseInt(event.target.value);
+    const newRate = parseFloat(event.target.value);
     setDataRate(newRate);
     
     // Clear all plots when sampling rate changes
     clearAllPlots();
     
-    console.log(`📊 Sampling rate changed to: ${newRate === 0 ? 'MAX Hz' : `${1000/newRate} Hz`}`);
+    console.log(`📊 Sampling rate changed to: ${newRate === 0 ? 'MAX Hz' : `${newRate} Hz`}`);
   };
 
   const timeWindowOptions = [
@@ -132,6 +207,27 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
     console.log(`📊 Time window changed to: ${newWindow} points`);
   };
 
+  // Sample source: the sampler's debug reads, binary frames on an RTT up channel, or
+  // SWO data trace (needs the SWO console started)
+  const handleSourceChange = (event: React.ChangeEvent<HTMLSelectElement>) => {
+    setSource(event.target.value);
+    clearAllPlots();
+  };
+
+  // Host and target times are different axes, so switching clears the plots
+  const handleTimeBaseChange = (event: React.ChangeEvent<HTMLSelectElement>) => {
+    setUseTargetTime(event.target.value === "target");
+    clearAllPlots();
+  };
+
+  const handleCoreClockChange = (event: React.FocusEvent<HTMLInputElement>) => {
+    const mhz = parseFloat(event.target.value);
+    if (mhz > 0 && mhz !== coreClockMHz) {
+      setCoreClockMHz(mhz);
+      clearAllPlots();
+    }
+  };
+
   const handlePauseToggle = () => {
     setIsPaused(!isPaused);
     console.log(`📊 Data collection ${!isPaused ? 'paused' : 'resumed'}`);
@@ -161,13 +257,38 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
       <div className="plot-header">
         <h3>Real-Time Plots</h3>
         <div className="plot-controls" style={{ display: 'flex', alignItems: 'center', gap: '12px', flexWrap: 'wrap' }}>
+          <div className="control-group" style={{ display: 'flex', alignItems: 'center' }}>
+            <label htmlFor="sampleSource" style={{ marginRight: '6px', fontWeight: 'bold', fontSize: '14px' }}>Source:</label>
+            <select 
+              id="sampleSource"
+              value={source} 
+              onChange={handleSourceChange}
+              disabled={!isConnected}
+              title="RTT streams decode mcu_link.h frames carrying the plotted variables in this order. SWO data trace watches the first four word-sized variables."
+              style={{ 
+                fontFamily: 'monospace', 
+                padding: '3px 6px',
+                borderRadius: '3px',
+                border: '1px solid #ccc',
+                backgroundColor: isConnected ? 'white' : '#f5f5f5',
+                fontSize: '12px'
+              }}
+            >
+              <option value="reads">Debug reads</option>
+              {RTT_STREAM_CHANNELS.map(channel => (
+                <option key={channel} value={channel}>RTT channel {channel}</option>
+              ))}
+              <option value="swo">SWO data trace</option>
+            </select>
+          </div>
+
           <div className="control-group" style={{ display: 'flex', alignItems: 'center' }}>
             <label htmlFor="dataRate" style={{ marginRight: '6px', fontWeight: 'bold', fontSize: '14px' }}>Rate:</label>
             <select 
               id="dataRate"
               value={dataRate} 
               onChange={handleDataRateChange}
-              disabled={!isConnected}
+              disabled={!isConnected || source !== "reads"}
               style={{ 
                 fontFamily: 'monospace', 
                 padding: '3px 6px',
@@ -209,6 +330,40 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
             </select>
           </div>
 
+          <div className="control-group" style={{ display: 'flex', alignItems: 'center' }}>
+            <label htmlFor="timeBase" style={{ marginRight: '6px', fontWeight: 'bold', fontSize: '14px' }}>Time:</label>
+            <select 
+              id="timeBase"
+              value={useTargetTime ? "target" : "host"} 
+              onChange={handleTimeBaseChange}
+              disabled={!isConnected}
+              title="Host: time of the probe read on this computer. Target: Cortex-M DWT cycle counter (ITM local timestamps for SWO)."
+              style={{ 
+                fontFamily: 'monospace', 
+                padding: '3px 6px',
+                borderRadius: '3px',
+                border: '1px solid #ccc',
+                backgroundColor: isConnected ? 'white' : '#f5f5f5',
+                fontSize: '12px'
+              }}
+            >
+              <option value="host">Host</option>
+              <option value="target">Target (DWT)</option>
+            </select>
+            {useTargetTime && (
+              <input
+                type="number"
+                defaultValue={coreClockMHz}
+                min={0}
+                step="any"
+                onBlur={handleCoreClockChange}
+                title="Core clock in MHz, used to convert DWT cycles to seconds"
+                style={{ width: '70px', marginLeft: '6px', fontFamily: 'monospace', fontSize: '12px' }}
+              />
+            )}
+            {useTargetTime && <span style={{ marginLeft: '4px', fontSize: '12px' }}>MHz</span>}
+          </div>
+
 
           <button 
             onClick={handlePauseToggle}
@@ -230,6 +385,30 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
           <span className={`status-indicator ${isCollecting ? 'collecting' : 'stopped'}`} style={{ fontWeight: 'bold', fontSize: '14px' }}>
             {isPaused ? '⏸️ Paused' : ''}
           </span>
+
+          {isCollecting && !isPaused && source === "reads" && (
+            <span style={{ fontFamily: 'monospace', fontSize: '12px' }} title="Achieved sampling rate and probe reads per sample">
+              {achievedRate.toFixed(0)} samples/s, {blockReads} read{blockReads === 1 ? '' : 's'}/sample
+            </span>
+          )}
+
+          {isCollecting && !isPaused && source !== "reads" && (
+            <span
+              style={{ fontFamily: 'monospace', fontSize: '12px', color: droppedSamples > 0 ? '#dc3545' : undefined }}
+              title={source === "swo" ? "ITM overflows, each losing at least one sample" : "Frames the firmware could not fit into the RTT buffer"}
+            >
+              {achievedRate.toFixed(0)} samples/s, {droppedSamples} dropped
+            </span>
+          )}
+
+          {isCollecting && !isPaused && stats && stats.samples > 1 && (
+            <span
+              style={{ fontFamily: 'monospace', fontSize: '12px', color: stats.missed_deadlines > 0 ? '#dc3545' : undefined }}
+              title={`Sample interval over ${stats.samples} samples: mean ${stats.mean_interval_us.toFixed(1)} µs, min ${stats.min_interval_us.toFixed(0)} µs, max ${stats.max_interval_us.toFixed(0)} µs`}
+            >
+              jitter ±{stats.stddev_interval_us.toFixed(1)} µs ({stats.min_interval_us.toFixed(0)}–{stats.max_interval_us.toFixed(0)} µs), {stats.missed_deadlines} missed
+            </span>
+          )}
           
           <button 
             onClick={clearAllPlots} 
@@ -255,9 +434,8 @@ export default function PlotPanel({ isConnected, variables }: PlotPanelProps) {
             data={plotData[variable.name] || []}
             title={variable.name}
             color={variable.name.includes('temperature') ? '#ff6b6b' : '#4ecdc4'}
-            unit={variable.var_type === 'FLOAT' && variable.name.includes('temperature') ? '°C' : ''}
+            unit={variable.unit ?? (variable.var_type === 'FLOAT' && variable.name.includes('temperature') ? '°C' : '')}
             windowSize={timeWindow}
-            bufferSize={10000}
           />
         ))}
       </div>
@@ -1,9 +1,13 @@
-import { ProbeInfo } from '../types';
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { ProbeInfo, TargetInfo } from '../types';
 
 interface ProbeSelectorProps {
   probes: ProbeInfo[];
   selectedProbe: number;
   onProbeSelect: (index: number) => void;
+  selectedTarget: string;
+  onTargetSelect: (target: string) => void;
   onConnect: () => void;
   onDisconnect: () => void;
   isConnecting: boolean;
@@ -14,11 +18,29 @@ export default function ProbeSelector({
   probes, 
   selectedProbe, 
   onProbeSelect, 
+  selectedTarget,
+  onTargetSelect,
   onConnect,
   onDisconnect,
   isConnecting,
   isConnected
 }: ProbeSelectorProps) {
+  const [targets, setTargets] = useState<TargetInfo[]>([]);
+
+  // Refresh the chip picker suggestions as the user types
+  useEffect(() => {
+    const timeout = setTimeout(async () => {
+      try {
+        const matches = await invoke<TargetInfo[]>("list_supported_targets", { query: selectedTarget });
+        setTargets(matches);
+      } catch (err) {
+        console.error("Failed to list supported targets:", err);
+      }
+    }, 200);
+
+    return () => clearTimeout(timeout);
+  }, [selectedTarget]);
+
   return (
     <div className="probe-selector">
       <h3>Debug Probes</h3>
@@ -38,6 +60,26 @@ export default function ProbeSelector({
               </option>
             ))}
           </select>
+          <div className="target-picker">
+            <label htmlFor="target-chip">Target chip:</label>
+            <input
+              id="target-chip"
+              type="text"
+              list="target-chip-options"
+              value={selectedTarget}
+              onChange={(e) => onTargetSelect(e.target.value)}
+              disabled={isConnecting || isConnected}
+              placeholder="auto or e.g. STM32G474RETx"
+            />
+            <datalist id="target-chip-options">
+              <option value="auto">Auto-detect from chip ID</option>
+              {targets.map((target) => (
+                <option key={target.name} value={target.name}>
+                  {target.family}{target.manufacturer && ` (${target.manufacturer})`}
+                </option>
+              ))}
+            </datalist>
+          </div>
           <div className="probe-actions">
             {!isConnected ? (
               <button 
@@ -1,57 +1,49 @@
 import { useEffect, useState } from 'react';
 import { LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer } from 'recharts';
-import { CircularBuffer, DataPoint } from '../utils/CircularBuffer';
+import { DataPoint } from '../utils/CircularBuffer';
 
 interface RealTimePlotProps {
-  data: number[];
+  data: DataPoint[]; // Timestamps in ms on the backend's monotonic clock
   title: string;
   color?: string;
   unit?: string;
-  bufferSize?: number;
   windowSize?: number; // number of points to display
 }
 
+interface ChartPoint {
+  value: number;
+  x: number; // ms relative to the latest sample
+}
+
 export default function RealTimePlot({ 
   data, 
   title, 
   color = "#8884d8", 
   unit = "", 
-  bufferSize = 10000,
   windowSize = 1000
 }: RealTimePlotProps) {
-  const [buffer] = useState(() => new CircularBuffer(bufferSize));
-  const [plotData, setPlotData] = useState<DataPoint[]>([]);
+  const [plotData, setPlotData] = useState<ChartPoint[]>([]);
 
   useEffect(() => {
-    if (data.length > 0) {
-      const latestValue = data[data.length - 1];
-      buffer.push(latestValue);
-      
-      // LUDICROUS SPEED - ZERO THROTTLING! 🚀
-      // REMOVED ALL LIMITS - MAXIMUM CHAOS MODE!
-      
-      // Use sample index instead of timestamps for stable x-axis
-      const allData = buffer.getAll();
-      
-      // Use configurable window size
-      const recentData = allData.slice(-windowSize);
-      
-      // Convert to chart data with categorical x-axis (no padding with nulls)
-      const chartData = recentData.map((point, index) => ({
-        timestamp: point.timestamp,
-        value: point.value,
-        x: index.toString() // String index for categorical axis
-      }));
-      
-      setPlotData(chartData);
+    // LUDICROUS SPEED - ZERO THROTTLING! 🚀
+    // REMOVED ALL LIMITS - MAXIMUM CHAOS MODE!
+    
+    // Use configurable window size
+    const recentData = data.slice(-windowSize);
+    if (recentData.length === 0) {
+      setPlotData([]);
+      return;
     }
-  }, [data, buffer, windowSize]);
+    
+    // Place samples by the time they were read, so gaps and jitter show up on the x-axis
+    const latest = recentData[recentData.length - 1].timestamp;
+    setPlotData(recentData.map(point => ({
+      value: point.value,
+      x: point.timestamp - latest
+    })));
+  }, [data, windowSize]);
 
-  const formatTooltipLabel = (value: string) => {
-    const index = parseInt(value);
-    const pointsAgo = (windowSize - index);
-    return `${pointsAgo} samples ago`;
-  };
+  const formatTooltipLabel = (value: number) => `${(-value).toFixed(2)} ms ago`;
 
   return (
     <div className="real-time-plot">
@@ -70,7 +62,8 @@ export default function RealTimePlot({
             <CartesianGrid strokeDasharray="3 3" stroke="#e0e0e0" />
             <XAxis 
               dataKey="x"
-              type="category"
+              type="number"
+              domain={['dataMin', 'dataMax']}
               tick={false}
               axisLine={false}
               hide={true}
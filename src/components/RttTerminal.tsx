@@ -0,0 +1,196 @@
+import { useEffect, useRef, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { RttData, RttInfo } from '../types';
+
+interface RttTerminalProps {
+  isConnected: boolean;
+}
+
+const MAX_TERMINAL_CHARS = 100000; // Older output is dropped per channel
+
+export default function RttTerminal({ isConnected }: RttTerminalProps) {
+  const [elfPath, setElfPath] = useState<string>('');
+  const [address, setAddress] = useState<string>('');
+  const [info, setInfo] = useState<RttInfo | null>(null);
+  const [output, setOutput] = useState<Map<number, string>>(new Map());
+  const [upChannel, setUpChannel] = useState<number>(0);
+  const [downChannel, setDownChannel] = useState<number>(0);
+  const [input, setInput] = useState<string>('');
+  const [isAttaching, setIsAttaching] = useState<boolean>(false);
+  const [error, setError] = useState<string | null>(null);
+  // One streaming decoder per channel, so UTF-8 sequences split across reads survive
+  const decodersRef = useRef<Map<number, TextDecoder>>(new Map());
+  const outputRef = useRef<HTMLPreElement>(null);
+
+  useEffect(() => {
+    const unlisten = listen<RttData>("rtt-data", (event) => {
+      const { channel, bytes } = event.payload;
+      const decoder = decodersRef.current.get(channel) ?? new TextDecoder();
+      decodersRef.current.set(channel, decoder);
+      const text = decoder.decode(new Uint8Array(bytes), { stream: true });
+
+      setOutput(prev => {
+        const next = new Map(prev);
+        const updated = (prev.get(channel) ?? '') + text;
+        next.set(channel, updated.length > MAX_TERMINAL_CHARS ? updated.slice(-MAX_TERMINAL_CHARS) : updated);
+        return next;
+      });
+    });
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
+  // Keep the newest output in view
+  useEffect(() => {
+    if (outputRef.current) {
+      outputRef.current.scrollTop = outputRef.current.scrollHeight;
+    }
+  }, [output, upChannel]);
+
+  useEffect(() => {
+    if (!isConnected) setInfo(null);
+  }, [isConnected]);
+
+  const attach = async () => {
+    setIsAttaching(true);
+    try {
+      const controlBlockAddress = address.trim() ? parseInt(address, 16) : undefined;
+      if (controlBlockAddress !== undefined && isNaN(controlBlockAddress)) {
+        throw new Error(`Invalid address ${address}`);
+      }
+      const attached = await invoke<RttInfo>("rtt_attach", {
+        elfPath: elfPath.trim() || undefined,
+        controlBlockAddress
+      });
+      setInfo(attached);
+      setUpChannel(attached.up_channels[0]?.number ?? 0);
+      setDownChannel(attached.down_channels[0]?.number ?? 0);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to attach to RTT: ${err}`);
+    } finally {
+      setIsAttaching(false);
+    }
+  };
+
+  const detach = async () => {
+    try {
+      await invoke("rtt_detach");
+      setInfo(null);
+    } catch (err) {
+      setError(`Failed to detach RTT: ${err}`);
+    }
+  };
+
+  const send = async () => {
+    try {
+      await invoke("rtt_write", { channel: downChannel, data: input + "\n" });
+      setInput('');
+      setError(null);
+    } catch (err) {
+      setError(`Failed to write RTT channel ${downChannel}: ${err}`);
+    }
+  };
+
+  const clearOutput = () => {
+    setOutput(prev => {
+      const next = new Map(prev);
+      next.delete(upChannel);
+      return next;
+    });
+  };
+
+  const channelLabel = (number: number, name?: string) => name ? `${number}: ${name}` : `${number}`;
+
+  return (
+    <div className="rtt-terminal">
+      <h3>RTT Terminal</h3>
+      <div className="discovery-controls">
+        <label>
+          Firmware ELF:
+          <input
+            type="text"
+            value={elfPath}
+            onChange={(e) => setElfPath(e.target.value)}
+            placeholder="optional, for _SEGGER_RTT"
+            style={{ marginLeft: '8px', fontFamily: 'monospace', minWidth: '250px' }}
+          />
+        </label>
+        <label style={{ marginLeft: '8px' }}>
+          Control block:
+          <input
+            type="text"
+            value={address}
+            onChange={(e) => setAddress(e.target.value)}
+            placeholder="scan RAM"
+            style={{ marginLeft: '8px', fontFamily: 'monospace', width: '110px' }}
+          />
+        </label>
+        {info ? (
+          <button onClick={detach} style={{ marginLeft: '8px' }}>Detach</button>
+        ) : (
+          <button onClick={attach} disabled={!isConnected || isAttaching} style={{ marginLeft: '8px' }}>
+            {isAttaching ? 'Attaching...' : 'Attach'}
+          </button>
+        )}
+      </div>
+
+      {error && (
+        <div className="error">
+          <p>{error}</p>
+        </div>
+      )}
+
+      {info && (
+        <>
+          <div className="discovery-controls">
+            <span style={{ fontFamily: 'monospace', fontSize: '12px' }}>
+              Control block @ 0x{info.control_block_address.toString(16).toUpperCase().padStart(8, '0')}
+            </span>
+            <label style={{ marginLeft: '12px' }}>
+              Up:
+              <select value={upChannel} onChange={(e) => setUpChannel(parseInt(e.target.value))} style={{ marginLeft: '6px' }}>
+                {info.up_channels.map(channel => (
+                  <option key={channel.number} value={channel.number}>
+                    {channelLabel(channel.number, channel.name)} ({channel.buffer_size} B)
+                  </option>
+                ))}
+              </select>
+            </label>
+            <button onClick={clearOutput} style={{ marginLeft: '8px' }}>Clear</button>
+          </div>
+
+          <pre
+            ref={outputRef}
+            style={{ height: '400px', overflowY: 'auto', fontFamily: 'monospace', fontSize: '12px', background: '#1e1e1e', color: '#d4d4d4', padding: '8px', whiteSpace: 'pre-wrap' }}
+          >
+            {output.get(upChannel) ?? ''}
+          </pre>
+
+          {info.down_channels.length > 0 && (
+            <div className="discovery-controls">
+              <select value={downChannel} onChange={(e) => setDownChannel(parseInt(e.target.value))}>
+                {info.down_channels.map(channel => (
+                  <option key={channel.number} value={channel.number}>
+                    {channelLabel(channel.number, channel.name)}
+                  </option>
+                ))}
+              </select>
+              <input
+                type="text"
+                value={input}
+                onChange={(e) => setInput(e.target.value)}
+                onKeyDown={(e) => e.key === 'Enter' && send()}
+                placeholder="Send to target..."
+                style={{ marginLeft: '8px', fontFamily: 'monospace', minWidth: '300px' }}
+              />
+              <button onClick={send} disabled={!isConnected} style={{ marginLeft: '8px' }}>Send</button>
+            </div>
+          )}
+        </>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,77 @@
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { VariableInfo } from '../types';
+
+interface StringViewProps {
+  variable: VariableInfo;
+  isConnected: boolean;
+}
+
+export default function StringView({ variable, isConnected }: StringViewProps) {
+  const [text, setText] = useState<string>('');
+  const [editValue, setEditValue] = useState<string>('');
+  const [error, setError] = useState<string | null>(null);
+  const isReadOnly = variable.access_flags === "RO";
+  // One byte of the buffer is reserved for the NUL terminator
+  const maxLength = variable.element_count - 1;
+
+  const readString = async () => {
+    try {
+      const value = await invoke<string>("read_string", {
+        address: variable.address,
+        length: variable.element_count
+      });
+      setText(value);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to read ${variable.name}: ${err}`);
+    }
+  };
+
+  const writeString = async () => {
+    try {
+      await invoke("write_string", {
+        address: variable.address,
+        length: variable.element_count,
+        value: editValue
+      });
+      setText(editValue);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to write ${variable.name}: ${err}`);
+    }
+  };
+
+  useEffect(() => {
+    if (isConnected) readString();
+  }, [isConnected, variable.address]);
+
+  return (
+    <div className="control-display">
+      <label>{variable.name}[{variable.element_count}]:</label>
+      <span className="value-display" style={{ fontFamily: 'monospace' }}>"{text}"</span>
+      <button onClick={readString} disabled={!isConnected} style={{ marginLeft: '8px' }}>
+        Refresh
+      </button>
+      {!isReadOnly && (
+        <>
+          <input
+            type="text"
+            value={editValue}
+            maxLength={maxLength}
+            onChange={(e) => setEditValue(e.target.value)}
+            onKeyDown={(e) => e.key === 'Enter' && writeString()}
+            placeholder={`up to ${maxLength} characters`}
+            style={{ marginLeft: '8px', fontFamily: 'monospace' }}
+          />
+          <button onClick={writeString} disabled={!isConnected}>Write</button>
+        </>
+      )}
+      {error && (
+        <div className="error">
+          <p>{error}</p>
+        </div>
+      )}
+    </div>
+  );
+}
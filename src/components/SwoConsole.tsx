@@ -0,0 +1,151 @@
+import { useEffect, useRef, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { SwoData } from '../types';
+
+interface SwoConsoleProps {
+  isConnected: boolean;
+}
+
+const MAX_CONSOLE_CHARS = 100000; // Older output is dropped per port
+const DEFAULT_CORE_CLOCK_MHZ = 64;
+const DEFAULT_BAUD = 1000000;
+
+export default function SwoConsole({ isConnected }: SwoConsoleProps) {
+  const [coreClockMHz, setCoreClockMHz] = useState<number>(DEFAULT_CORE_CLOCK_MHZ);
+  const [baud, setBaud] = useState<number>(DEFAULT_BAUD);
+  const [isRunning, setIsRunning] = useState<boolean>(false);
+  const [output, setOutput] = useState<Map<number, string>>(new Map());
+  const [port, setPort] = useState<number>(0);
+  const [error, setError] = useState<string | null>(null);
+  // One streaming decoder per stimulus port, so UTF-8 sequences split across reads survive
+  const decodersRef = useRef<Map<number, TextDecoder>>(new Map());
+  const outputRef = useRef<HTMLPreElement>(null);
+
+  useEffect(() => {
+    const unlisten = listen<SwoData>("swo-data", (event) => {
+      const { port, bytes } = event.payload;
+      const decoder = decodersRef.current.get(port) ?? new TextDecoder();
+      decodersRef.current.set(port, decoder);
+      const text = decoder.decode(new Uint8Array(bytes), { stream: true });
+
+      setOutput(prev => {
+        const next = new Map(prev);
+        const updated = (prev.get(port) ?? '') + text;
+        next.set(port, updated.length > MAX_CONSOLE_CHARS ? updated.slice(-MAX_CONSOLE_CHARS) : updated);
+        return next;
+      });
+    });
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
+  // Keep the newest output in view
+  useEffect(() => {
+    if (outputRef.current) {
+      outputRef.current.scrollTop = outputRef.current.scrollHeight;
+    }
+  }, [output, port]);
+
+  useEffect(() => {
+    if (!isConnected) setIsRunning(false);
+  }, [isConnected]);
+
+  const start = async () => {
+    try {
+      await invoke("swo_start", { coreClockHz: coreClockMHz * 1e6, baud });
+      setIsRunning(true);
+      setError(null);
+    } catch (err) {
+      setError(`Failed to start SWO trace: ${err}`);
+    }
+  };
+
+  const stop = async () => {
+    try {
+      await invoke("swo_stop");
+      setIsRunning(false);
+    } catch (err) {
+      setError(`Failed to stop SWO trace: ${err}`);
+    }
+  };
+
+  const clearOutput = () => {
+    setOutput(prev => {
+      const next = new Map(prev);
+      next.delete(port);
+      return next;
+    });
+  };
+
+  // Ports that have received output, plus the selected one
+  const ports = Array.from(new Set([port, ...output.keys()])).sort((a, b) => a - b);
+
+  return (
+    <div className="swo-console">
+      <h3>SWO Console</h3>
+      <div className="discovery-controls">
+        <label>
+          Core clock:
+          <input
+            type="number"
+            value={coreClockMHz}
+            min={0}
+            step="any"
+            onChange={(e) => setCoreClockMHz(parseFloat(e.target.value) || 0)}
+            disabled={isRunning}
+            title="TPIU clock, normally the core clock"
+            style={{ marginLeft: '8px', width: '70px', fontFamily: 'monospace' }}
+          />
+          <span style={{ marginLeft: '4px' }}>MHz</span>
+        </label>
+        <label style={{ marginLeft: '8px' }}>
+          Baud:
+          <input
+            type="number"
+            value={baud}
+            min={1}
+            onChange={(e) => setBaud(parseInt(e.target.value) || 0)}
+            disabled={isRunning}
+            style={{ marginLeft: '8px', width: '90px', fontFamily: 'monospace' }}
+          />
+        </label>
+        {isRunning ? (
+          <button onClick={stop} style={{ marginLeft: '8px' }}>Stop</button>
+        ) : (
+          <button onClick={start} disabled={!isConnected} style={{ marginLeft: '8px' }}>Start SWO</button>
+        )}
+      </div>
+
+      {error && (
+        <div className="error">
+          <p>{error}</p>
+        </div>
+      )}
+
+      {isRunning && (
+        <>
+          <div className="discovery-controls">
+            <label>
+              Stimulus port:
+              <select value={port} onChange={(e) => setPort(parseInt(e.target.value))} style={{ marginLeft: '6px' }}>
+                {ports.map(number => (
+                  <option key={number} value={number}>{number}</option>
+                ))}
+              </select>
+            </label>
+            <button onClick={clearOutput} style={{ marginLeft: '8px' }}>Clear</button>
+          </div>
+
+          <pre
+            ref={outputRef}
+            style={{ height: '300px', overflowY: 'auto', fontFamily: 'monospace', fontSize: '12px', background: '#1e1e1e', color: '#d4d4d4', padding: '8px', whiteSpace: 'pre-wrap' }}
+          >
+            {output.get(port) ?? ''}
+          </pre>
+        </>
+      )}
+    </div>
+  );
+}
@@ -1,19 +1,80 @@
-import { useState } from 'react';
-import { VariableInfo } from '../types';
+import { useEffect, useState } from 'react';
+import { invoke } from '@tauri-apps/api/core';
+import { DecodedValue, Scaling, VariableInfo } from '../types';
+
+// 64-bit integers can exceed the 2^53 a JS number holds exactly, so they are shown and written as text
+const EXACT_TYPES = ["UINT64", "INT64"];
 
 interface VariableControlProps {
   variable: VariableInfo;
   value: number;
-  onValueChange: (address: number, varType: string, value: number) => void;
+  onValueChange: (address: number, varType: string, value: number, scaling?: Scaling) => void;
 }
 
 export default function VariableControl({ variable, value, onValueChange }: VariableControlProps) {
   const [localValue, setLocalValue] = useState(value);
+  const [exactText, setExactText] = useState<string>(String(value));
+  const [exactInput, setExactInput] = useState<string>('');
+  const [decoded, setDecoded] = useState<DecodedValue | null>(null);
+  // Scaled variables are shown and written in engineering units, so never as exact raw text
+  const isScaled = variable.scale != null || variable.offset != null || !!variable.q_format;
+  const isExact = EXACT_TYPES.includes(variable.var_type) && !isScaled;
+  const isDecoded = (variable.enum_labels?.length ?? 0) > 0 || (variable.bit_fields?.length ?? 0) > 0;
+
+  // Refresh the exact text whenever the polled (rounded) value changes
+  useEffect(() => {
+    if (!isExact) return;
+    invoke<string>("read_variable_exact", { address: variable.address, varType: variable.var_type })
+      .then(setExactText)
+      .catch(() => setExactText(String(value)));
+  }, [isExact, value, variable.address, variable.var_type]);
+
+  const refreshDecoded = () =>
+    invoke<DecodedValue>("read_variable_decoded", { variable })
+      .then(setDecoded)
+      .catch((err) => console.warn(`Decoded read failed: ${err}`));
+
+  // Enum labels and bit fields are decoded by the backend from the raw bytes
+  useEffect(() => {
+    if (isDecoded) refreshDecoded();
+  }, [isDecoded, value, variable]);
+
+  const handleLabelWrite = async (label: string) => {
+    try {
+      await invoke("write_variable_label", { variable, label });
+      await refreshDecoded();
+    } catch (err) {
+      console.warn(`Write failed: ${err}`);
+    }
+  };
+
+  const handleBitsWrite = async (field: string, fieldValue: number) => {
+    try {
+      await invoke("write_variable_bits", { variable, field, value: fieldValue });
+      await refreshDecoded();
+    } catch (err) {
+      console.warn(`Write failed: ${err}`);
+    }
+  };
+
+  const handleExactWrite = async () => {
+    try {
+      await invoke("write_variable", {
+        address: variable.address,
+        varType: variable.var_type,
+        value: 0,
+        exact: exactInput
+      });
+      setExactText(exactInput.trim());
+    } catch (err) {
+      console.warn(`Write failed: ${err}`);
+    }
+  };
 
   const handleChange = async (newValue: number) => {
     setLocalValue(newValue);
     try {
-      await onValueChange(variable.address, variable.var_type, newValue);
+      await onValueChange(variable.address, variable.var_type, newValue, variable);
     } catch (err) {
       console.warn(`Write not supported with current probe: ${err}`);
       // Reset to previous value since write failed
@@ -24,7 +85,75 @@ export default function VariableControl({ variable, value, onValueChange }: Vari
   const renderControl = () => {
     const isReadOnly = variable.access_flags === "RO";
     
-    if (variable.var_type === "UINT8" && variable.max_value === 1) {
+    if (isDecoded) {
+      const enumLabels = variable.enum_labels ?? [];
+      const bitFields = variable.bit_fields ?? [];
+      return (
+        <div className="control-display">
+          <label>{variable.name}:</label>
+          {enumLabels.length > 0 && (isReadOnly ? (
+            <span className="value-display">{decoded?.label ?? decoded?.text ?? '?'}</span>
+          ) : (
+            <select value={decoded?.label ?? ''} onChange={(e) => handleLabelWrite(e.target.value)}>
+              {!decoded?.label && <option value="">{decoded?.text ?? '?'}</option>}
+              {enumLabels.map(entry => (
+                <option key={entry.label} value={entry.label}>{entry.label} ({entry.value})</option>
+              ))}
+            </select>
+          ))}
+          {bitFields.map(field => {
+            const fieldValue = decoded?.bits.find(bit => bit.name === field.name)?.value ?? 0;
+            return (
+              <span key={field.name} style={{ marginLeft: '8px', fontFamily: 'monospace' }}>
+                {field.width === 1 ? (
+                  <label>
+                    <input
+                      type="checkbox"
+                      checked={fieldValue === 1}
+                      disabled={isReadOnly}
+                      onChange={() => handleBitsWrite(field.name, fieldValue === 1 ? 0 : 1)}
+                    />
+                    {field.name}
+                  </label>
+                ) : (
+                  <label>
+                    {field.name}=
+                    <input
+                      type="number"
+                      min={0}
+                      max={2 ** field.width - 1}
+                      value={fieldValue}
+                      disabled={isReadOnly}
+                      onChange={(e) => handleBitsWrite(field.name, parseInt(e.target.value, 10) || 0)}
+                      style={{ width: '60px' }}
+                    />
+                  </label>
+                )}
+              </span>
+            );
+          })}
+        </div>
+      );
+    } else if (isExact) {
+      return (
+        <div className="control-display">
+          <label>{variable.name}:</label>
+          <span className="value-display">{exactText}</span>
+          {!isReadOnly && (
+            <>
+              <input
+                type="text"
+                value={exactInput}
+                onChange={(e) => setExactInput(e.target.value)}
+                placeholder={variable.var_type}
+                style={{ marginLeft: '8px', fontFamily: 'monospace' }}
+              />
+              <button onClick={handleExactWrite} disabled={!exactInput}>Write</button>
+            </>
+          )}
+        </div>
+      );
+    } else if (variable.var_type === "BOOL" || (variable.var_type === "UINT8" && variable.max_value === 1)) {
       // Button/Toggle control
       if (isReadOnly) {
         // Read-only binary indicator
@@ -50,14 +179,14 @@ export default function VariableControl({ variable, value, onValueChange }: Vari
           </div>
         );
       }
-    } else if (variable.var_type === "FLOAT" || variable.var_type === "UINT8") {
+    } else if (["FLOAT", "DOUBLE", "UINT8"].includes(variable.var_type) || isScaled) {
       // Slider or number display
       if (isReadOnly) {
         return (
           <div className="control-display">
             <label>{variable.name}:</label>
             <span className="value-display">{value.toFixed(2)}</span>
-            {variable.var_type === "FLOAT" && <span className="unit">°C</span>}
+            {variable.unit && <span className="unit">{variable.unit}</span>}
           </div>
         );
       } else {
@@ -68,11 +197,12 @@ export default function VariableControl({ variable, value, onValueChange }: Vari
               type="range"
               min={variable.min_value || 0}
               max={variable.max_value || 100}
-              step={variable.var_type === "FLOAT" ? 0.1 : 1}
+              step={variable.var_type === "UINT8" && !isScaled ? 1 : 0.1}
               value={localValue}
               onChange={(e) => handleChange(parseFloat(e.target.value))}
             />
             <span className="value-display">{localValue.toFixed(2)}</span>
+            {variable.unit && <span className="unit">{variable.unit}</span>}
           </div>
         );
       }
@@ -1,7 +1,12 @@
 import { useEffect, useState, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { VariableInfo } from '../types';
+import { DiscoveryReport, Scaling, TimedValue, TimedValues, VariableInfo, WriteReport } from '../types';
 import VariableControl from './VariableControl';
+import ElfSymbolPicker from './ElfSymbolPicker';
+import DiscoveryReportView from './DiscoveryReportView';
+import ArrayView from './ArrayView';
+import StringView from './StringView';
+import VariableTree from './VariableTree';
 
 interface VariablePanelProps {
   isConnected: boolean;
@@ -14,10 +19,57 @@ interface VariablePanelProps {
 export default function VariablePanel({ isConnected, onVariablesDiscovered, mculinkAddress, shouldAutoDiscover, variables }: VariablePanelProps) {
   const [values, setValues] = useState<Map<number, number>>(new Map());
   const [error, setError] = useState<string | null>(null);
+  const [lastWrite, setLastWrite] = useState<WriteReport | null>(null);
+  const [verifyWrites, setVerifyWrites] = useState<boolean>(false);
+  const [allowUnsealed, setAllowUnsealed] = useState<boolean>(false);
   const [isDiscovering, setIsDiscovering] = useState<boolean>(false);
   const isDiscoveringRef = useRef(false);
   const [localMculinkAddress, setLocalMculinkAddress] = useState<string>(mculinkAddress);
   const [testResults, setTestResults] = useState<string | null>(null);
+  const [report, setReport] = useState<DiscoveryReport | null>(null);
+  const [decodingConfigPath, setDecodingConfigPath] = useState<string>('');
+
+  // Attach enum labels and bit fields from a JSON sidecar to the current variables
+  const loadDecodingConfig = async () => {
+    try {
+      const decoded = await invoke<VariableInfo[]>("apply_decoding_config", { path: decodingConfigPath, variables });
+      setError(null);
+      onVariablesDiscovered?.(decoded);
+    } catch (err) {
+      setError(`Failed to load decoding config: ${err}`);
+    }
+  };
+
+  const decodingConfigControls = (
+    <div className="discovery-controls">
+      <label>
+        Decoding config:
+        <input
+          type="text"
+          value={decodingConfigPath}
+          onChange={(e) => setDecodingConfigPath(e.target.value)}
+          placeholder="/path/to/decoding.json"
+          style={{ marginLeft: '8px', fontFamily: 'monospace', minWidth: '300px' }}
+        />
+      </label>
+      <button onClick={loadDecodingConfig} disabled={!decodingConfigPath || variables.length === 0}>
+        Load Decoding
+      </button>
+    </div>
+  );
+
+  // Shared by target and ELF discovery
+  const allowUnsealedControl = (
+    <label style={{ fontSize: '12px' }} title="Use unsealed v2 tables and v2.0 tables, which carry no CRC to check">
+      <input
+        type="checkbox"
+        checked={allowUnsealed}
+        onChange={(e) => setAllowUnsealed(e.target.checked)}
+        style={{ marginRight: '6px' }}
+      />
+      Allow descriptors without a valid CRC
+    </label>
+  );
 
   const discoverVariables = async () => {
     console.log("VariablePanel - discoverVariables called, isConnected:", isConnected, "isDiscovering:", isDiscovering, "ref:", isDiscoveringRef.current);
@@ -38,10 +90,11 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
       // Parse the hex address
       const addressNumber = parseInt(localMculinkAddress, 16);
       console.log("VariablePanel - Calling invoke('discover_variables_at_address') with address:", localMculinkAddress, "->", addressNumber);
-      const discoveredVars = await invoke<VariableInfo[]>("discover_variables_at_address", { address: addressNumber });
-      console.log("VariablePanel - invoke completed, result:", discoveredVars);
+      const discoveryReport = await invoke<DiscoveryReport>("discover_variables_at_address", { address: addressNumber, allowUnsealed });
+      console.log("VariablePanel - invoke completed, result:", discoveryReport);
       
-      console.log("VariablePanel - Raw invoke result:", discoveredVars);
+      const discoveredVars = discoveryReport.variables;
+      setReport(discoveryReport);
       setError(null);
       
       console.log("VariablePanel - Discovered variables:", discoveredVars);
@@ -56,11 +109,13 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
       
       // Initialize values for the discovered variables
       const initialValues = new Map();
-      for (const variable of discoveredVars) {
+      // Arrays are read in bulk by their own view
+      for (const variable of discoveredVars.filter(v => v.element_count === 1)) {
         try {
-          const value = await invoke<number>("read_variable", {
+          const { value } = await invoke<TimedValue>("read_variable", {
             address: variable.address,
-            varType: variable.var_type
+            varType: variable.var_type,
+            scaling: variable
           });
           initialValues.set(variable.address, value);
         } catch (err) {
@@ -77,16 +132,20 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
     }
   };
 
-  const handleValueChange = async (address: number, varType: string, value: number) => {
+  const handleValueChange = async (address: number, varType: string, value: number, scaling?: Scaling) => {
     try {
-      await invoke("write_variable", {
+      const report = await invoke<WriteReport>("write_variable", {
         address: address,
         varType: varType,
-        value: value
+        value: value,
+        scaling: scaling,
+        verify: verifyWrites
       });
+      setLastWrite(report);
       
-      // Update local state
-      setValues(prev => new Map(prev.set(address, value)));
+      // Update local state with what the target actually holds, when known
+      const stored = report.verification ? report.verification.readback : report.clamped_to ?? value;
+      setValues(prev => new Map(prev.set(address, stored)));
     } catch (err) {
       setError(`Failed to write variable: ${err}`);
     }
@@ -110,26 +169,25 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
   useEffect(() => {
     if (!isConnected || variables.length === 0) return;
 
+    // Only read read-only scalars, all in one coalesced batch read
+    const polledVars = variables.filter(v => v.access_flags === "RO" && v.element_count === 1 && v.var_type !== "STRING");
+    if (polledVars.length === 0) return;
+
     const interval = setInterval(async () => {
       const newValues = new Map(values);
       let hasChanges = false;
 
-      for (const variable of variables) {
-        if (variable.access_flags === "RO") { // Only read read-only variables
-          try {
-            const value = await invoke<number>("read_variable", {
-              address: variable.address,
-              varType: variable.var_type
-            });
-            
-            if (newValues.get(variable.address) !== value) {
-              newValues.set(variable.address, value);
-              hasChanges = true;
-            }
-          } catch (err) {
-            console.error(`Failed to read ${variable.name}:`, err);
+      try {
+        const results = await invoke<TimedValues>("read_variables", { variables: polledVars });
+        polledVars.forEach((variable, i) => {
+          const value = results.values[i];
+          if (value !== null && newValues.get(variable.address) !== value) {
+            newValues.set(variable.address, value);
+            hasChanges = true;
           }
-        }
+        });
+      } catch (err) {
+        console.error("Failed to read variables:", err);
       }
 
       if (hasChanges) {
@@ -155,16 +213,44 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
   }, [variables]);
 
   if (!isConnected) {
+    // Offline inspection: variables can still be read from the firmware ELF
     return (
       <div className="variable-panel">
         <h3>Variables</h3>
-        <p>Connect to MCU to discover variables</p>
+        <p>Connect to MCU to discover variables, or inspect a firmware ELF offline</p>
+        {allowUnsealedControl}
+        <ElfSymbolPicker
+          onVariablesSelected={(selectedVars) => onVariablesDiscovered?.(selectedVars)}
+          onDiscoveryReport={setReport}
+          allowUnsealed={allowUnsealed}
+        />
+        {report && <DiscoveryReportView report={report} />}
+        {variables.length > 0 && decodingConfigControls}
+        {error && (
+          <div className="error">
+            <p>{error}</p>
+          </div>
+        )}
+        {variables.length > 0 && (
+          <div style={{ fontFamily: 'monospace', fontSize: '12px' }}>
+            <VariableTree
+              variables={variables}
+              renderVariable={(variable) => (
+                <>
+                  {variable.name}{variable.element_count > 1 && `[${variable.element_count}]`} @ 0x{variable.address.toString(16).toUpperCase().padStart(8, '0')} ({variable.var_type}, {variable.access_flags}){variable.category && ` - ${variable.category}`}
+                </>
+              )}
+            />
+          </div>
+        )}
       </div>
     );
   }
 
-  // Group variables by category
-  const categorizedVars = variables.reduce((acc, variable) => {
+  // Group scalar variables by category; arrays and text buffers get their own sections
+  const stringVars = variables.filter(v => v.var_type === "STRING");
+  const arrayVars = variables.filter(v => v.element_count > 1 && v.var_type !== "STRING");
+  const categorizedVars = variables.filter(v => v.element_count === 1 && v.var_type !== "STRING").reduce((acc, variable) => {
     const category = variable.category || 'Other';
     if (!acc[category]) acc[category] = [];
     acc[category].push(variable);
@@ -181,6 +267,30 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
         </div>
       )}
 
+      <label style={{ fontSize: '12px' }}>
+        <input
+          type="checkbox"
+          checked={verifyWrites}
+          onChange={(e) => setVerifyWrites(e.target.checked)}
+          style={{ marginRight: '6px' }}
+        />
+        Verify writes (read back after writing)
+      </label>
+
+      {lastWrite && (
+        <div
+          style={{ fontFamily: 'monospace', fontSize: '12px', color: lastWrite.fallback_reason || lastWrite.verification?.overwritten ? '#dc3545' : undefined }}
+          title={lastWrite.fallback_reason ? `Background write failed: ${lastWrite.fallback_reason}` : undefined}
+        >
+          Last write: {lastWrite.method === 'background' ? 'background (core running)' : 'core halted'}
+          {lastWrite.fallback_reason && ' after background write failed'}
+          {lastWrite.clamped_from !== undefined && lastWrite.clamped_from !== null && `; clamped from ${lastWrite.clamped_from} to ${lastWrite.clamped_to}`}
+          {lastWrite.verification && (lastWrite.verification.overwritten
+            ? `; requested ${lastWrite.verification.requested}, read back ${lastWrite.verification.readback} (changed by firmware)`
+            : `; verified ${lastWrite.verification.readback}`)}
+        </div>
+      )}
+
       <div className="discovery-controls">
         <label>
           MCU Link Section Address:
@@ -200,6 +310,17 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
           Test RAM Writes
         </button>
       </div>
+      {allowUnsealedControl}
+
+      <ElfSymbolPicker
+        onVariablesSelected={(selectedVars) => onVariablesDiscovered?.(selectedVars)}
+        onDiscoveryReport={setReport}
+        allowUnsealed={allowUnsealed}
+      />
+
+      {report && <DiscoveryReportView report={report} />}
+
+      {variables.length > 0 && decodingConfigControls}
 
       {testResults && (
         <div className="test-results" style={{ 
@@ -220,17 +341,37 @@ export default function VariablePanel({ isConnected, onVariablesDiscovered, mcul
         <div key={category} className="variable-category">
           <h4>{category}</h4>
           <div className="variables-grid">
-            {categoryVars.map((variable) => (
-              <VariableControl
-                key={variable.address}
-                variable={variable}
-                value={values.get(variable.address) || 0}
-                onValueChange={handleValueChange}
-              />
-            ))}
+            <VariableTree
+              variables={categoryVars}
+              renderVariable={(variable) => (
+                <VariableControl
+                  variable={variable}
+                  value={values.get(variable.address) || 0}
+                  onValueChange={handleValueChange}
+                />
+              )}
+            />
           </div>
         </div>
       ))}
+
+      {stringVars.length > 0 && (
+        <div className="variable-category">
+          <h4>Text</h4>
+          {stringVars.map((variable) => (
+            <StringView key={variable.address} variable={variable} isConnected={isConnected} />
+          ))}
+        </div>
+      )}
+
+      {arrayVars.length > 0 && (
+        <div className="variable-category">
+          <h4>Arrays</h4>
+          {arrayVars.map((variable) => (
+            <ArrayView key={variable.address} variable={variable} isConnected={isConnected} />
+          ))}
+        </div>
+      )}
     </div>
   );
 }
\ No newline at end of file
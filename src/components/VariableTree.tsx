@@ -0,0 +1,46 @@
+import { ReactNode } from 'react';
+import { VariableInfo } from '../types';
+
+interface VariableTreeProps {
+  variables: VariableInfo[];
+  renderVariable: (variable: VariableInfo) => ReactNode;
+}
+
+interface TreeNode {
+  children: Map<string, TreeNode>;
+  variables: VariableInfo[];
+}
+
+// Build a tree from the dotted parent paths of struct fields (e.g. "motor.current")
+function buildTree(variables: VariableInfo[]): TreeNode {
+  const root: TreeNode = { children: new Map(), variables: [] };
+  for (const variable of variables) {
+    let node = root;
+    for (const segment of variable.parent ? variable.parent.split('.') : []) {
+      if (!node.children.has(segment)) {
+        node.children.set(segment, { children: new Map(), variables: [] });
+      }
+      node = node.children.get(segment)!;
+    }
+    node.variables.push(variable);
+  }
+  return root;
+}
+
+export default function VariableTree({ variables, renderVariable }: VariableTreeProps) {
+  const renderNode = (node: TreeNode): ReactNode => (
+    <>
+      {node.variables.map(variable => (
+        <div key={variable.address + variable.name}>{renderVariable(variable)}</div>
+      ))}
+      {[...node.children.entries()].map(([segment, child]) => (
+        <details key={segment} open style={{ marginLeft: '12px' }}>
+          <summary style={{ fontFamily: 'monospace' }}>{segment}</summary>
+          {renderNode(child)}
+        </details>
+      ))}
+    </>
+  );
+
+  return <div className="variable-tree">{renderNode(buildTree(variables))}</div>;
+}
@@ -6,23 +6,195 @@ export interface ProbeInfo {
   probe_type: string;
 }
 
+export interface TargetInfo {
+  name: string;
+  family: string;
+  manufacturer?: string;
+}
+
+// Fields that could not be read are null
+export interface ChipIdentity {
+  idcode: number | null;
+  part_number: number | null;
+  revision: string | null;
+  cpuid: number | null;
+  core: string | null;
+  manufacturer: string | null;
+  rom_table_part: number | null;
+}
+
 export interface SessionInfo {
   target_name: string;
   connected: boolean;
-  chip_id?: string;
+  auto_detected: boolean;
+  chip_id: ChipIdentity | null;
+  write_mode: WriteMode;
+}
+
+// Background: AP write while the core runs. Halt: halt, read-modify-write, resume.
+export type WriteMode = 'background' | 'halt';
+
+// What happens to writes beyond a variable's min/max or rate limit
+export type LimitPolicy = 'reject' | 'clamp';
+
+// How one write_variable call was performed
+export interface WriteReport {
+  method: WriteMode;
+  fallback_reason?: string; // Why a background write fell back to halting
+  clamped_from?: number; // Requested value, when a range or rate limit clamped it
+  clamped_to?: number; // Value written instead
+  verification?: WriteVerification; // Present when the write was verified
+}
+
+// Readback after a verified write, in engineering units
+export interface WriteVerification {
+  requested: number;
+  written: number; // The requested value as the variable's type stores it
+  readback: number;
+  overwritten: boolean; // Firmware changed the value after the write (e.g. clamped it)
 }
 
 export interface VariableInfo {
-  name: string;
+  name: string; // Dotted path for struct fields, e.g. "motor.current.q"
+  parent?: string; // Path of the enclosing struct, e.g. "motor.current"
   address: number;
-  var_type: string; // "UINT8", "FLOAT", etc.
+  var_type: string; // "UINT8", "FLOAT", "UINT64", "DOUBLE", "BOOL", "STRING", etc.
   access_flags: string; // "RO", "RW"
   category?: string; // "Controls", "Sensors", etc.
   min_value?: number;
   max_value?: number;
+  element_count: number; // Array length, 1 for scalars
+  enum_labels?: EnumLabel[]; // From a DWARF enum type or a decoding config
+  bit_fields?: BitField[]; // From DWARF bit-field members or a decoding config
+  scale?: number; // Engineering units per raw count
+  offset?: number; // Engineering value of a raw 0
+  q_format?: number; // Fractional bits of a Qn fixed-point value
+  unit?: string; // Display unit, e.g. "°C", "V"
+}
+
+// Scaling passed to read_variable/write_variable/read_array/write_array;
+// a VariableInfo can be passed as is. Writes to discovered variables always use
+// the scaling the backend registered, and reject scaling that differs from it
+export interface Scaling {
+  scale?: number;
+  offset?: number;
+  q_format?: number;
+}
+
+export interface EnumLabel {
+  value: number;
+  label: string;
+}
+
+export interface BitField {
+  name: string;
+  offset: number; // Position of the least significant bit
+  width: number; // Number of bits
+}
+
+export interface BitValue {
+  name: string;
+  value: number;
+}
+
+export interface DecodedValue {
+  raw: number;
+  text: string; // Exact raw value
+  label?: string; // Enum label matching the value, if any
+  bits: BitValue[];
+}
+
+// Batch streamed by the Rust sampler as a "sampling-batch" event; values[i][j] is
+// variable i (in start_sampling order) at timestamps_us[j], null for a failed read
+export interface SampleBatch {
+  run_id: number;
+  timestamps_us: number[];
+  target_times_s?: (number | null)[]; // DWT_CYCCNT time since the run started, when enabled
+  values: (number | null)[][];
+  samples_per_second: number; // Achieved rate over this batch
+  block_reads: number; // Coalesced probe reads per sample (0 for RTT streams)
+  dropped_samples: number; // Lost since the previous batch (RTT buffer overflow)
+}
+
+// Timestamps are microseconds on the backend's host monotonic clock, taken around the
+// probe transaction
+export interface TimedValue {
+  value: number;
+  timestamp_us: number;
+}
+
+export interface TimedValues {
+  values: (number | null)[];
+  timestamp_us: number;
+}
+
+// Interval statistics of one sample stream, from sampling_stats
+export interface StreamStats {
+  run_id: number;
+  stream: string;
+  target_interval_us: number; // 0 when sampling as fast as possible
+  samples: number;
+  min_interval_us: number;
+  max_interval_us: number;
+  mean_interval_us: number;
+  stddev_interval_us: number;
+  missed_deadlines: number; // Dropped frames for RTT streams
 }
 
 export interface VariableValue {
   name: string;
   value: number;
-}
\ No newline at end of file
+}
+export interface RejectedEntry {
+  location: number; // Flash address of the rejected record
+  name?: string;
+  reason: string;
+}
+
+export type CrcStatus = "Valid" | "Mismatch" | "Unsealed" | "NotPresent";
+
+export interface DiscoveryReport {
+  format: string; // "v2 table", "v1 records"
+  descriptor_version?: string;
+  crc_status: CrcStatus;
+  variables: VariableInfo[];
+  rejected: RejectedEntry[];
+  warnings: string[];
+}
+
+export interface RttChannelInfo {
+  number: number;
+  name?: string;
+  buffer_size: number;
+}
+
+export interface RttInfo {
+  control_block_address: number;
+  up_channels: RttChannelInfo[];
+  down_channels: RttChannelInfo[];
+}
+
+// Bytes from one RTT up channel, streamed as an "rtt-data" event
+export interface RttData {
+  channel: number;
+  bytes: number[];
+  timestamp_us: number;
+}
+
+// Decoded defmt log record, streamed as "defmt-log" events (arrays of records)
+export interface LogRecord {
+  timestamp_us: number; // Host monotonic time, same clock as sample batches
+  target_timestamp?: string; // From the firmware's defmt::timestamp!
+  level?: 'trace' | 'debug' | 'info' | 'warn' | 'error';
+  module?: string;
+  file?: string;
+  line?: number;
+  message: string;
+}
+
+// Bytes written to one ITM stimulus port, streamed as a "swo-data" event
+export interface SwoData {
+  port: number;
+  bytes: number[];
+  timestamp_us: number;
+}
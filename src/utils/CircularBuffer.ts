@@ -14,8 +14,9 @@ export class CircularBuffer {
     this.buffer = new Array(capacity);
   }
 
-  push(value: number): void {
-    const timestamp = Date.now();
+  // Samples from the backend carry the time they were read; only stamp on arrival
+  // when no timestamp is given
+  push(value: number, timestamp: number = Date.now()): void {
     this.buffer[this.head] = { timestamp, value };
     this.head = (this.head + 1) % this.capacity;
     